@@ -0,0 +1,173 @@
+use crate::executor::{Executor, QueryResult};
+use crate::repl::Mode;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// REPL과 GUI가 함께 쓰는 설정 (~/.squirrelrc, TOML 형식)
+///
+/// ```toml
+/// prompt = "{db}[{tx}]> " # {db}는 데이터베이스 이름, {tx}는 트랜잭션 중이면 *
+///                          # (세션은 메모리에서 실행되며 {db}에 저장되지 않음)
+/// mode = "markdown"  # REPL 결과 출력 형식 (table|markdown)
+/// gui_row_limit = 10000 # GUI가 한 번에 가져오는 결과 행 수 (나머지는 Load more)
+///
+/// [pragmas]
+/// seed = 42
+/// strict = true       # 암시적 형 변환을 오류로 처리
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub prompt: String,
+    pub mode: Mode,
+    pub gui_row_limit: usize,
+    // 데이터베이스를 열 때마다 실행할 PRAGMA (이름 = 값)
+    pub pragmas: BTreeMap<String, toml::Value>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            prompt: "sql> ".into(),
+            mode: Mode::Table,
+            gui_row_limit: 10_000,
+            pragmas: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigErr {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    Pragma { name: String, msg: String },
+}
+
+impl fmt::Display for ConfigErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "Cannot read '{}': {}", path.display(), e),
+            Self::Parse(path, e) => write!(f, "Invalid config '{}': {}", path.display(), e),
+            Self::Pragma { name, msg } => write!(f, "Invalid pragma '{}': {}", name, msg),
+        }
+    }
+}
+
+impl std::error::Error for ConfigErr {}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| Path::new(&home).join(".squirrelrc"))
+    }
+
+    /// 설정 파일을 읽음
+    ///
+    /// 경로를 직접 지정했다면 파일이 꼭 있어야 하고,
+    /// 기본 경로(~/.squirrelrc)는 없으면 기본값을 씁니다.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigErr> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match Self::default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if !required && e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(e) => return Err(ConfigErr::Io(path, e)),
+        };
+        toml::from_str(&text).map_err(|e| ConfigErr::Parse(path, e))
+    }
+
+    /// 프롬프트 템플릿의 `{db}`를 데이터베이스 이름으로 바꿈 (`{tx}`는 REPL이 입력마다 바꿈)
+    ///
+    /// 이름만 보여 줄 뿐, 세션의 테이블은 메모리에 있고 이 데이터베이스에 저장되지 않습니다.
+    pub fn render_prompt(&self, db: &Path) -> String {
+        let name = db.file_stem().unwrap_or(db.as_os_str()).to_string_lossy();
+        self.prompt.replace("{db}", &name)
+    }
+
+    /// 설정된 PRAGMA를 실행기에 적용
+    pub fn apply(&self, exe: &mut Executor) -> Result<(), ConfigErr> {
+        for (name, value) in &self.pragmas {
+            let pragma_err = |msg: String| ConfigErr::Pragma {
+                name: name.clone(),
+                msg,
+            };
+            let value = match value {
+                toml::Value::Integer(n) => n.to_string(),
+                toml::Value::Float(f) => f.to_string(),
+                toml::Value::Boolean(b) => b.to_string().to_uppercase(),
+                // 렉서는 ''가 아니라 \' 로 따옴표를 이스케이프함
+                toml::Value::String(s) => {
+                    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+                }
+                other => {
+                    return Err(pragma_err(format!(
+                        "unsupported value type {}",
+                        other.type_str()
+                    )));
+                }
+            };
+            if let QueryResult::Error(msg) = exe.run(format!("PRAGMA {} = {};", name, value)) {
+                return Err(pragma_err(msg));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_apply() {
+        let config: Config = toml::from_str(
+            "prompt = 'squirrel> '
+             mode = 'markdown'
+             gui_row_limit = 500
+             [pragmas]
+             seed = 42",
+        )
+        .unwrap();
+        assert_eq!(config.prompt, "squirrel> ");
+        assert_eq!(config.mode, Mode::Markdown);
+        assert_eq!(config.gui_row_limit, 500);
+
+        let mut exe = Executor::new();
+        config.apply(&mut exe).unwrap();
+        match exe.run("PRAGMA seed;".into()) {
+            QueryResult::Rows { rows, .. } => assert_eq!(rows, vec![vec!["42".to_string()]]),
+            _ => panic!("Expected rows"),
+        }
+    }
+
+    #[test]
+    fn test_defaults_and_errors() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config, Config::default());
+
+        assert!(toml::from_str::<Config>("promt = '> '").is_err());
+        assert!(toml::from_str::<Config>("mode = 'csv'").is_err());
+
+        let config: Config = toml::from_str("[pragmas]\nnope = 1").unwrap();
+        let err = config.apply(&mut Executor::new()).unwrap_err();
+        assert!(matches!(err, ConfigErr::Pragma { name, .. } if name == "nope"));
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let config = Config {
+            prompt: "{db}> ".into(),
+            ..Config::default()
+        };
+        assert_eq!(config.render_prompt(Path::new("data/shop.sq")), "shop> ");
+        assert_eq!(Config::default().render_prompt(Path::new("x")), "sql> ");
+    }
+}
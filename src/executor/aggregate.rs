@@ -0,0 +1,645 @@
+use super::Executor;
+use super::error::{ExecErr, Result};
+use super::eval::{self, Scope};
+use super::random::{self, Rng};
+use crate::query::{Expr, Order};
+use crate::storage::DataValue;
+use std::collections::HashMap;
+
+/// 집계 함수의 중간 상태
+pub trait Accumulator {
+    fn update(&mut self, args: &[DataValue], exe: &mut Executor) -> Result<()>;
+    fn finish(&self) -> Result<DataValue>;
+}
+
+pub struct Aggregate {
+    pub name: &'static str,
+    pub min_args: usize,
+    pub max_args: usize,
+    init: fn(&mut Rng) -> Box<dyn Accumulator>,
+}
+
+const AGGREGATES: &[Aggregate] = &[
+    Aggregate {
+        name: "COUNT",
+        min_args: 0,
+        max_args: 1,
+        init: |_| Box::new(Count(0)),
+    },
+    Aggregate {
+        name: "SUM",
+        min_args: 1,
+        max_args: 1,
+        init: |_| Box::new(Sum(None)),
+    },
+    Aggregate {
+        name: "AVG",
+        min_args: 1,
+        max_args: 1,
+        init: |_| Box::new(Avg { sum: 0.0, count: 0 }),
+    },
+    Aggregate {
+        name: "MIN",
+        min_args: 1,
+        max_args: 1,
+        init: |_| {
+            Box::new(Extreme {
+                best: None,
+                max: false,
+            })
+        },
+    },
+    Aggregate {
+        name: "MAX",
+        min_args: 1,
+        max_args: 1,
+        init: |_| {
+            Box::new(Extreme {
+                best: None,
+                max: true,
+            })
+        },
+    },
+    Aggregate {
+        name: "GROUP_CONCAT",
+        min_args: 1,
+        max_args: 2,
+        init: |_| Box::new(Concat(None)),
+    },
+    Aggregate {
+        name: "STRING_AGG",
+        min_args: 2,
+        max_args: 2,
+        init: |_| Box::new(Concat(None)),
+    },
+    // 근사 집계: 테이블 크기와 관계없이 고정된 메모리만 사용
+    Aggregate {
+        name: "APPROX_COUNT_DISTINCT",
+        min_args: 1,
+        max_args: 1,
+        init: |rng| Box::new(HyperLogLog::new(rng.hash_state().key())),
+    },
+    Aggregate {
+        name: "APPROX_PERCENTILE",
+        min_args: 2,
+        max_args: 2,
+        init: |rng| Box::new(Reservoir::new(Rng::new(rng.next_u64()))),
+    },
+];
+
+impl Aggregate {
+    /// 인자 수가 `min_args..=max_args` 범위인지 확인
+    pub fn check_args(&self, count: usize) -> Result<()> {
+        if count < self.min_args || count > self.max_args {
+            return Err(ExecErr::InvalidArgument(format!(
+                "{} expects {}..{} argument(s), but got {}",
+                self.name, self.min_args, self.max_args, count
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn accumulator(&self, rng: &mut Rng) -> Box<dyn Accumulator> {
+        (self.init)(rng)
+    }
+}
+
+pub fn lookup(name: &str) -> Option<&'static Aggregate> {
+    AGGREGATES
+        .iter()
+        .find(|a| a.name.eq_ignore_ascii_case(name))
+}
+
+pub fn contains_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::FunctionCall { name, args, .. } => {
+            lookup(name).is_some() || args.iter().any(contains_aggregate)
+        }
+        Expr::Unary { right, .. } => contains_aggregate(right),
+        Expr::Binary { left, right, .. } => contains_aggregate(left) || contains_aggregate(right),
+        Expr::Between {
+            expr, low, high, ..
+        } => contains_aggregate(expr) || contains_aggregate(low) || contains_aggregate(high),
+        Expr::IsNull { expr, .. } | Expr::Cast { expr, .. } => contains_aggregate(expr),
+        _ => false,
+    }
+}
+
+struct Call {
+    agg: &'static Aggregate,
+    args: Vec<Expr>,
+    order_by: Vec<(Expr, Order)>,
+}
+
+/// 집계 함수 호출을 `#agg<n>` 컬럼 참조로 바꾸고, 호출 목록을 `calls`에 모음
+fn extract(expr: &Expr, calls: &mut Vec<Call>) -> Result<Expr> {
+    Ok(match expr {
+        Expr::FunctionCall {
+            name,
+            args,
+            order_by,
+        } if let Some(agg) = lookup(name) => {
+            let order_by = order_by.clone().unwrap_or_default();
+            if args
+                .iter()
+                .chain(order_by.iter().map(|(e, _)| e))
+                .any(contains_aggregate)
+            {
+                return Err(ExecErr::InvalidArgument(format!(
+                    "aggregate function calls cannot be nested inside {}",
+                    agg.name
+                )));
+            }
+            agg.check_args(args.len())?;
+            calls.push(Call {
+                agg,
+                args: args.clone(),
+                order_by,
+            });
+            Expr::Ident(format!("#agg{}", calls.len() - 1).into())
+        }
+        Expr::FunctionCall {
+            name,
+            args,
+            order_by,
+        } => Expr::FunctionCall {
+            name: name.clone(),
+            args: args
+                .iter()
+                .map(|arg| extract(arg, calls))
+                .collect::<Result<_>>()?,
+            order_by: order_by.clone(),
+        },
+        Expr::Unary { op, right } => Expr::Unary {
+            op: op.clone(),
+            right: extract(right, calls)?.boxed(),
+        },
+        Expr::Binary { op, left, right } => Expr::Binary {
+            op: op.clone(),
+            left: extract(left, calls)?.boxed(),
+            right: extract(right, calls)?.boxed(),
+        },
+        Expr::Between {
+            expr,
+            low,
+            high,
+            negated,
+        } => Expr::Between {
+            expr: extract(expr, calls)?.boxed(),
+            low: extract(low, calls)?.boxed(),
+            high: extract(high, calls)?.boxed(),
+            negated: *negated,
+        },
+        Expr::IsNull { expr, negated } => Expr::IsNull {
+            expr: extract(expr, calls)?.boxed(),
+            negated: *negated,
+        },
+        Expr::Cast { expr, ty } => Expr::Cast {
+            expr: extract(expr, calls)?.boxed(),
+            ty: ty.clone(),
+        },
+        expr => expr.clone(),
+    })
+}
+
+/// 현재 묶음 기준(`set`)에 없는 그룹 식은 NULL로, GROUPING()은 비트마스크 값으로 바꿈
+fn mask_grouping(expr: &Expr, keys: &[&Expr], set: &[Expr]) -> Result<Expr> {
+    if keys.contains(&expr) && !set.contains(expr) {
+        return Ok(Expr::Null);
+    }
+    Ok(match expr {
+        Expr::FunctionCall { name, args, .. } if name.eq_ignore_ascii_case("GROUPING") => {
+            let mut bits = 0;
+            for arg in args {
+                if !keys.contains(&arg) {
+                    return Err(ExecErr::InvalidArgument(
+                        "arguments to GROUPING must be grouping expressions".into(),
+                    ));
+                }
+                bits = (bits << 1) | !set.contains(arg) as i64;
+            }
+            Expr::Int(bits)
+        }
+        // 집계 함수의 인자는 묶음 안의 각 행에서 계산되므로 그대로 둠
+        Expr::FunctionCall { name, .. } if lookup(name).is_some() => expr.clone(),
+        Expr::FunctionCall {
+            name,
+            args,
+            order_by,
+        } => Expr::FunctionCall {
+            name: name.clone(),
+            args: args
+                .iter()
+                .map(|arg| mask_grouping(arg, keys, set))
+                .collect::<Result<_>>()?,
+            order_by: order_by.clone(),
+        },
+        Expr::Unary { op, right } => Expr::Unary {
+            op: op.clone(),
+            right: mask_grouping(right, keys, set)?.boxed(),
+        },
+        Expr::Binary { op, left, right } => Expr::Binary {
+            op: op.clone(),
+            left: mask_grouping(left, keys, set)?.boxed(),
+            right: mask_grouping(right, keys, set)?.boxed(),
+        },
+        Expr::Between {
+            expr,
+            low,
+            high,
+            negated,
+        } => Expr::Between {
+            expr: mask_grouping(expr, keys, set)?.boxed(),
+            low: mask_grouping(low, keys, set)?.boxed(),
+            high: mask_grouping(high, keys, set)?.boxed(),
+            negated: *negated,
+        },
+        Expr::IsNull { expr, negated } => Expr::IsNull {
+            expr: mask_grouping(expr, keys, set)?.boxed(),
+            negated: *negated,
+        },
+        Expr::Cast { expr, ty } => Expr::Cast {
+            expr: mask_grouping(expr, keys, set)?.boxed(),
+            ty: ty.clone(),
+        },
+        expr => expr.clone(),
+    })
+}
+
+impl Executor {
+    /// GROUP BY의 묶음 기준(grouping set)마다 행을 묶어 집계함
+    ///
+    /// ROLLUP, CUBE는 파서에서 묶음 기준 목록으로 풀려서 들어옵니다.
+    pub(super) fn eval_grouping(
+        &mut self,
+        names: &[String],
+        rows: &[Vec<DataValue>],
+        columns: &[Expr],
+        sets: &[Vec<Expr>],
+    ) -> Result<Vec<Vec<DataValue>>> {
+        let mut keys: Vec<&Expr> = Vec::new();
+        for expr in sets.iter().flatten() {
+            if !keys.contains(&expr) {
+                keys.push(expr);
+            }
+        }
+        let mut out = Vec::new();
+        for set in sets {
+            let columns = columns
+                .iter()
+                .map(|expr| mask_grouping(expr, &keys, set))
+                .collect::<Result<Vec<_>>>()?;
+            // 묶음은 처음 나온 순서대로 (키 -> 묶음 번호)
+            let mut index: HashMap<Vec<DataValue>, usize, _> =
+                HashMap::with_hasher(self.rng.hash_state());
+            let mut groups: Vec<Vec<Vec<DataValue>>> = Vec::new();
+            for row in rows {
+                let scope = Scope::new(names, row);
+                let key = set
+                    .iter()
+                    .map(|expr| self.eval(expr, &scope))
+                    .collect::<Result<Vec<_>>>()?;
+                let idx = *index.entry(key).or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+                groups[idx].push(row.clone());
+            }
+            // 빈 묶음 기준은 행이 없어도 총계 행 하나를 만듦
+            if set.is_empty() && groups.is_empty() {
+                groups.push(vec![]);
+            }
+            for group in groups {
+                out.push(self.eval_aggregates(names, &group, &columns)?);
+            }
+        }
+        Ok(out)
+    }
+
+    /// 행 묶음 하나를 집계하여 결과 행 하나를 만듦
+    ///
+    /// 집계 함수 밖의 컬럼 참조는 묶음의 첫 번째 행 값을 사용합니다.
+    pub(super) fn eval_aggregates(
+        &mut self,
+        names: &[String],
+        rows: &[Vec<DataValue>],
+        columns: &[Expr],
+    ) -> Result<Vec<DataValue>> {
+        let mut calls = Vec::new();
+        let columns = columns
+            .iter()
+            .map(|expr| extract(expr, &mut calls))
+            .collect::<Result<Vec<_>>>()?;
+        let mut scope_values = Vec::with_capacity(calls.len());
+        for call in &calls {
+            let mut acc = (call.agg.init)(&mut self.rng);
+            // ORDER BY가 없으면 행마다 바로 누적 (근사 집계가 행 수만큼 메모리를 쓰지 않도록)
+            if call.order_by.is_empty() {
+                for row in rows {
+                    let args = self.eval_row(&call.args, names, row)?;
+                    acc.update(&args, self)?;
+                }
+                scope_values.push(acc.finish()?);
+                continue;
+            }
+            // 집계 함수 안의 ORDER BY는 누적하기 전에 입력 순서를 바꿈
+            let mut inputs = Vec::with_capacity(rows.len());
+            for row in rows {
+                inputs.push((
+                    self.eval_row(call.order_by.iter().map(|(expr, _)| expr), names, row)?,
+                    self.eval_row(&call.args, names, row)?,
+                ));
+            }
+            let orders = call
+                .order_by
+                .iter()
+                .map(|(_, order)| *order)
+                .collect::<Vec<_>>();
+            eval::sort_rows(&mut inputs, |(a, _), (b, _)| {
+                eval::compare_keys(a, b, &orders)
+            })?;
+            for (_, args) in &inputs {
+                acc.update(args, self)?;
+            }
+            scope_values.push(acc.finish()?);
+        }
+        let mut scope_names = names.to_vec();
+        scope_names.extend((0..calls.len()).map(|i| format!("#agg{}", i)));
+        let mut scope_row = rows
+            .first()
+            .cloned()
+            .unwrap_or_else(|| vec![DataValue::Null; names.len()]);
+        scope_row.extend(scope_values);
+        let scope = Scope::new(&scope_names, &scope_row);
+        columns.iter().map(|expr| self.eval(expr, &scope)).collect()
+    }
+
+    // 한 행에서 식들의 값을 계산
+    fn eval_row<'a>(
+        &mut self,
+        exprs: impl IntoIterator<Item = &'a Expr>,
+        names: &[String],
+        row: &[DataValue],
+    ) -> Result<Vec<DataValue>> {
+        let scope = Scope::new(names, row);
+        exprs
+            .into_iter()
+            .map(|expr| self.eval(expr, &scope))
+            .collect()
+    }
+}
+
+struct Count(i64);
+
+impl Accumulator for Count {
+    fn update(&mut self, args: &[DataValue], _exe: &mut Executor) -> Result<()> {
+        // COUNT(*)는 인자가 없으므로 모든 행을 셈
+        if args.first().is_none_or(|v| *v != DataValue::Null) {
+            self.0 += 1;
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<DataValue> {
+        Ok(DataValue::Int(self.0))
+    }
+}
+
+struct Sum(Option<DataValue>);
+
+impl Accumulator for Sum {
+    fn update(&mut self, args: &[DataValue], exe: &mut Executor) -> Result<()> {
+        self.0 = match (self.0.take(), &args[0]) {
+            (acc, DataValue::Null) => acc,
+            (None, DataValue::Int(n)) => Some(DataValue::Int(*n)),
+            (None, DataValue::Float(f)) => Some(DataValue::Float(*f)),
+            // 합이 INT 범위를 넘으면 스칼라 연산처럼 엄격한 모드에서는 오류, 아니면 FLOAT로 이어서 더함
+            (Some(DataValue::Int(a)), DataValue::Int(b)) => Some(match a.checked_add(*b) {
+                Some(n) => DataValue::Int(n),
+                None => exe.int_overflow(format!("SUM: {} + {}", a, b), a as f64 + *b as f64)?,
+            }),
+            (Some(DataValue::Int(a)), DataValue::Float(b)) => Some(DataValue::Float(a as f64 + b)),
+            (Some(DataValue::Float(a)), DataValue::Int(b)) => Some(DataValue::Float(a + *b as f64)),
+            (Some(DataValue::Float(a)), DataValue::Float(b)) => Some(DataValue::Float(a + b)),
+            (_, other) => {
+                return Err(ExecErr::TypeMismatch {
+                    expected: "number".into(),
+                    found: other.type_name().into(),
+                });
+            }
+        };
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<DataValue> {
+        Ok(self.0.clone().unwrap_or(DataValue::Null))
+    }
+}
+
+struct Avg {
+    sum: f64,
+    count: u64,
+}
+
+impl Accumulator for Avg {
+    fn update(&mut self, args: &[DataValue], _exe: &mut Executor) -> Result<()> {
+        match &args[0] {
+            DataValue::Null => return Ok(()),
+            DataValue::Int(n) => self.sum += *n as f64,
+            DataValue::Float(f) => self.sum += f,
+            other => {
+                return Err(ExecErr::TypeMismatch {
+                    expected: "number".into(),
+                    found: other.type_name().into(),
+                });
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<DataValue> {
+        Ok(if self.count == 0 {
+            DataValue::Null
+        } else {
+            DataValue::Float(self.sum / self.count as f64)
+        })
+    }
+}
+
+struct Extreme {
+    best: Option<DataValue>,
+    max: bool,
+}
+
+impl Accumulator for Extreme {
+    fn update(&mut self, args: &[DataValue], _exe: &mut Executor) -> Result<()> {
+        let value = &args[0];
+        if *value == DataValue::Null {
+            return Ok(());
+        }
+        let replace = match &self.best {
+            None => true,
+            Some(best) => {
+                let ord = eval::compare(value, best)?;
+                if self.max { ord.is_gt() } else { ord.is_lt() }
+            }
+        };
+        if replace {
+            self.best = Some(value.clone());
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<DataValue> {
+        Ok(self.best.clone().unwrap_or(DataValue::Null))
+    }
+}
+
+/// 값을 구분자로 이어 붙임 (구분자를 생략하면 ',')
+struct Concat(Option<String>);
+
+impl Accumulator for Concat {
+    fn update(&mut self, args: &[DataValue], _exe: &mut Executor) -> Result<()> {
+        if args[0] == DataValue::Null {
+            return Ok(());
+        }
+        let separator = match args.get(1) {
+            None => ",".to_string(),
+            Some(DataValue::String(s)) => s.clone(),
+            Some(other) => {
+                return Err(ExecErr::TypeMismatch {
+                    expected: "String separator".into(),
+                    found: other.type_name().into(),
+                });
+            }
+        };
+        match &mut self.0 {
+            None => self.0 = Some(args[0].to_string()),
+            Some(out) => {
+                out.push_str(&separator);
+                out.push_str(&args[0].to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<DataValue> {
+        Ok(self.0.clone().map_or(DataValue::Null, DataValue::String))
+    }
+}
+
+// 레지스터 수 = 2^HLL_PRECISION, 표준 오차는 약 1.04 / sqrt(레지스터 수) = 1.6%
+const HLL_PRECISION: u32 = 12;
+
+/// 고유 값의 개수를 근사하는 HyperLogLog 스케치
+struct HyperLogLog {
+    registers: Vec<u8>,
+    // 값의 해시에 섞는 키 (`PRAGMA seed`를 따름)
+    key: u64,
+}
+
+impl HyperLogLog {
+    fn new(key: u64) -> Self {
+        Self {
+            registers: vec![0; 1 << HLL_PRECISION],
+            key,
+        }
+    }
+}
+
+impl Accumulator for HyperLogLog {
+    fn update(&mut self, args: &[DataValue], _exe: &mut Executor) -> Result<()> {
+        if args[0] == DataValue::Null {
+            return Ok(());
+        }
+        // FNV는 상위 비트가 덜 섞이므로 레지스터를 고르기 전에 한 번 더 섞음
+        let hash = random::mix64(args[0].stable_hash() ^ self.key);
+        let idx = (hash >> (64 - HLL_PRECISION)) as usize;
+        // 남은 비트가 모두 0이어도 순위가 범위를 넘지 않도록 마지막 비트를 채움
+        let rest = (hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        self.registers[idx] = self.registers[idx].max(rank);
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<DataValue> {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        // 값이 적을 때는 선형 계수법이 더 정확함
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        Ok(DataValue::Int(estimate.round() as i64))
+    }
+}
+
+// APPROX_PERCENTILE이 보관하는 최대 표본 수
+const RESERVOIR_SIZE: usize = 1024;
+
+/// 고정 크기 표본(저수지 표집)으로 백분위수를 근사
+struct Reservoir {
+    rng: Rng,
+    samples: Vec<DataValue>,
+    seen: u64,
+    percentile: Option<f64>,
+}
+
+impl Reservoir {
+    fn new(rng: Rng) -> Self {
+        Self {
+            rng,
+            samples: Vec::new(),
+            seen: 0,
+            percentile: None,
+        }
+    }
+}
+
+impl Accumulator for Reservoir {
+    fn update(&mut self, args: &[DataValue], _exe: &mut Executor) -> Result<()> {
+        let percentile = match &args[1] {
+            DataValue::Int(n) if (0..=1).contains(n) => *n as f64,
+            DataValue::Float(f) if (0.0..=1.0).contains(f) => *f,
+            other => {
+                return Err(ExecErr::InvalidArgument(format!(
+                    "APPROX_PERCENTILE expects a percentile between 0 and 1, but got {}",
+                    other
+                )));
+            }
+        };
+        self.percentile = Some(percentile);
+        if args[0] == DataValue::Null {
+            return Ok(());
+        }
+        self.seen += 1;
+        if self.samples.len() < RESERVOIR_SIZE {
+            self.samples.push(args[0].clone());
+        } else {
+            let idx = self.rng.next_below(self.seen) as usize;
+            if idx < RESERVOIR_SIZE {
+                self.samples[idx] = args[0].clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<DataValue> {
+        let Some(percentile) = self.percentile else {
+            return Ok(DataValue::Null);
+        };
+        if self.samples.is_empty() {
+            return Ok(DataValue::Null);
+        }
+        let mut sorted = self.samples.clone();
+        eval::sort_rows(&mut sorted, eval::compare)?;
+        // nearest-rank 방식
+        let rank = (percentile * sorted.len() as f64).ceil() as usize;
+        Ok(sorted[rank.saturating_sub(1)].clone())
+    }
+}
@@ -0,0 +1,136 @@
+use super::coerce;
+use super::error::{ExecErr, Result};
+use crate::storage::{DataType, DataValue};
+use serde_json::Value;
+
+/// 한 줄에 JSON 객체 하나씩 있는 NDJSON을 행으로 변환
+///
+/// 객체의 키를 컬럼 이름으로 보고, 없는 키는 NULL로 채웁니다. 빈 줄은 건너뜁니다.
+/// 값은 INSERT와 같은 규칙으로 변환합니다 (`strict`면 엄격한 모드).
+pub fn read_ndjson(
+    text: &str,
+    schema: &[(String, DataType)],
+    strict: bool,
+) -> Result<Vec<Vec<DataValue>>> {
+    let mut rows = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let at_line =
+            |msg: String| ExecErr::InvalidArgument(format!("line {}: {}", line_no + 1, msg));
+        let Value::Object(object) =
+            serde_json::from_str(line).map_err(|e| at_line(e.to_string()))?
+        else {
+            return Err(at_line("expected a JSON object".into()));
+        };
+        let mut row = vec![DataValue::Null; schema.len()];
+        for (key, value) in object {
+            let idx = schema
+                .iter()
+                .position(|(name, _)| *name == key)
+                .ok_or_else(|| at_line(format!("column not found: '{}'", key)))?;
+            row[idx] = coerce(from_json(value), schema[idx].1, strict)
+                .map_err(|e| at_line(e.to_string()))?;
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// 행마다 `{"컬럼": 값, ...}` 한 줄씩 (컬럼 순서 유지)
+pub fn write_ndjson(columns: &[String], rows: &[Vec<DataValue>]) -> String {
+    let keys: Vec<String> = columns
+        .iter()
+        .map(|c| Value::from(c.as_str()).to_string())
+        .collect();
+    let mut out = String::new();
+    for row in rows {
+        out.push('{');
+        for (i, (key, value)) in keys.iter().zip(row).enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(key);
+            out.push(':');
+            out.push_str(&to_json(value).to_string());
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+// 배열과 객체는 JSON 문자열 그대로 TEXT 값으로 넣음
+fn from_json(value: Value) -> DataValue {
+    match value {
+        Value::Null => DataValue::Null,
+        Value::Bool(b) => DataValue::Bool(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(n) => DataValue::Int(n),
+            None => DataValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(s) => DataValue::String(s),
+        value => DataValue::String(value.to_string()),
+    }
+}
+
+// JSON에는 NaN과 무한대가 없으므로 null로 씀
+fn to_json(value: &DataValue) -> Value {
+    match value {
+        DataValue::Null => Value::Null,
+        DataValue::Bool(b) => Value::from(*b),
+        DataValue::Int(n) => Value::from(*n),
+        DataValue::Float(f) => serde_json::Number::from_f64(*f).map_or(Value::Null, Value::Number),
+        DataValue::String(s) => Value::from(s.as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ndjson_round_trip() {
+        let schema = vec![
+            ("name".to_string(), DataType::String),
+            ("age".to_string(), DataType::Float),
+            ("tags".to_string(), DataType::String),
+        ];
+        let text =
+            "{\"age\": 18, \"name\": \"Al\\\"pha\", \"tags\": [1, 2]}\n\n{\"name\": \"Beta\"}\n";
+        let rows = read_ndjson(text, &schema, true).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    DataValue::String("Al\"pha".into()),
+                    DataValue::Float(18.0),
+                    DataValue::String("[1,2]".into()),
+                ],
+                vec![
+                    DataValue::String("Beta".into()),
+                    DataValue::Null,
+                    DataValue::Null
+                ],
+            ]
+        );
+
+        let columns: Vec<String> = schema.iter().map(|(name, _)| name.clone()).collect();
+        let out = write_ndjson(&columns, &rows);
+        assert_eq!(
+            out.lines().next(),
+            Some(r#"{"name":"Al\"pha","age":18.0,"tags":"[1,2]"}"#)
+        );
+        assert_eq!(read_ndjson(&out, &schema, true).unwrap(), rows);
+
+        for bad in ["[1, 2]", "{\"nope\": 1}", "{\"age\": \"old\"}", "{"] {
+            assert!(read_ndjson(bad, &schema, true).is_err(), "{}", bad);
+        }
+        // 느슨한 모드에서는 숫자 모양의 문자열도 숫자 컬럼에 넣을 수 있음
+        assert!(read_ndjson("{\"age\": \"18\"}", &schema, true).is_err());
+        assert_eq!(
+            read_ndjson("{\"age\": \"18\"}", &schema, false).unwrap()[0][1],
+            DataValue::Float(18.0)
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, ExecErr>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecErr {
+    TableNotFound(String),
+    TableExists(String),
+    TriggerExists(String),
+    ColumnNotFound(String),
+    AmbiguousColumn(String),
+    UnknownType(String),
+    TypeMismatch { expected: String, found: String },
+    InvalidCast { value: String, ty: String },
+    ColumnCountMismatch { expected: usize, found: usize },
+    ForeignKeyViolation { table: String, references: String },
+    UnknownFunction(String),
+    InvalidArgument(String),
+    DivisionByZero,
+    IntegerOverflow(String),
+    WriteConflict(String),
+    Unsupported(String),
+    Io(String),
+}
+
+impl fmt::Display for ExecErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TableNotFound(t) => write!(f, "Table not found: '{}'", t),
+            Self::TableExists(t) => write!(f, "Table already exists: '{}'", t),
+            Self::TriggerExists(t) => write!(f, "Trigger already exists: '{}'", t),
+            Self::ColumnNotFound(c) => write!(f, "Column not found: '{}'", c),
+            Self::AmbiguousColumn(c) => write!(f, "Column reference is ambiguous: '{}'", c),
+            Self::UnknownType(t) => write!(f, "Unknown type: '{}'", t),
+            Self::TypeMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Type mismatch: expected {}, but found {}",
+                    expected, found
+                )
+            }
+            Self::InvalidCast { value, ty } => write!(f, "Cannot cast '{}' to {}", value, ty),
+            Self::ColumnCountMismatch { expected, found } => {
+                write!(f, "Expected {} values, but found {}", expected, found)
+            }
+            Self::ForeignKeyViolation { table, references } => write!(
+                f,
+                "Foreign key violation: row in '{}' references a missing row in '{}'",
+                table, references
+            ),
+            Self::UnknownFunction(name) => write!(f, "Unknown function: '{}'", name),
+            Self::InvalidArgument(msg) => write!(f, "Invalid argument: {}", msg),
+            Self::DivisionByZero => write!(f, "Division by zero"),
+            Self::IntegerOverflow(what) => write!(f, "Integer overflow: {}", what),
+            Self::WriteConflict(t) => write!(
+                f,
+                "Write conflict: '{}' was changed by another session, transaction rolled back",
+                t
+            ),
+            Self::Unsupported(what) => write!(f, "Unsupported statement: {}", what),
+            Self::Io(msg) => write!(f, "I/O error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ExecErr {}
@@ -0,0 +1,446 @@
+use super::aggregate;
+use super::error::{ExecErr, Result};
+use super::function;
+use super::{Executor, ResultSet};
+use crate::query::lexer::Token;
+use crate::query::{Expr, Order, Stmt};
+use crate::storage::{self, DataType, DataValue};
+use std::cmp::Ordering;
+
+/// 식을 평가할 때 컬럼 이름으로 값을 찾는 범위
+#[derive(Default)]
+pub struct Scope<'a> {
+    pub columns: &'a [String],
+    pub row: &'a [DataValue],
+}
+
+impl<'a> Scope<'a> {
+    pub fn new(columns: &'a [String], row: &'a [DataValue]) -> Self {
+        Self { columns, row }
+    }
+
+    /// 정확히 같은 이름을 먼저 찾고, 없으면 앞쪽 이름을 뗀 이름으로 찾음
+    /// (`analytics.events.id`는 `events.id`나 `id`로도 찾을 수 있음)
+    fn lookup(&self, name: &str) -> Result<DataValue> {
+        if let Some(i) = self.columns.iter().position(|c| c == name) {
+            return Ok(self.row[i].clone());
+        }
+        let mut found = self.columns.iter().enumerate().filter(|(_, c)| {
+            c.strip_suffix(name)
+                .is_some_and(|qualifier| qualifier.ends_with('.'))
+        });
+        match (found.next(), found.next()) {
+            (Some((i, _)), None) => Ok(self.row[i].clone()),
+            (Some(_), Some(_)) => Err(ExecErr::AmbiguousColumn(name.to_string())),
+            (None, _) => Err(ExecErr::ColumnNotFound(name.to_string())),
+        }
+    }
+}
+
+impl Executor {
+    /// 느슨한 모드에서는 문자열과 숫자를 비교할 때 숫자 모양의 문자열을 숫자로 바꿈
+    /// (엄격한 모드에서는 그대로 두어 비교할 때 타입 오류가 남)
+    fn compare_operands(
+        &mut self,
+        op: &Token,
+        left: DataValue,
+        right: DataValue,
+    ) -> (DataValue, DataValue) {
+        use DataValue::{Float, Int, String};
+        let comparison = matches!(
+            op,
+            Token::OpEq | Token::OpLt | Token::OpGt | Token::OpLe | Token::OpGe
+        );
+        if self.strict || !comparison {
+            return (left, right);
+        }
+        match (left, right) {
+            (String(s), n @ (Int(_) | Float(_))) => (self.text_to_number(s), n),
+            (n @ (Int(_) | Float(_)), String(s)) => (n, self.text_to_number(s)),
+            pair => pair,
+        }
+    }
+
+    fn text_to_number(&mut self, s: String) -> DataValue {
+        let text = DataValue::String(s);
+        match cast(text.clone(), DataType::Int).or_else(|_| cast(text.clone(), DataType::Float)) {
+            Ok(n) => {
+                if let Some(to) = n.data_type() {
+                    self.conversion_notice(DataType::String, to, "in a comparison");
+                }
+                n
+            }
+            Err(_) => text,
+        }
+    }
+
+    /// INT 연산이 넘쳤을 때: 엄격한 모드에서는 오류, 아니면 FLOAT로 계산한 값을 쓰고 알림을 남김
+    ///
+    /// f64는 2^53보다 큰 정수를 정확히 나타내지 못하므로 결과는 근삿값임
+    pub(super) fn int_overflow(&mut self, what: String, promoted: f64) -> Result<DataValue> {
+        if self.strict {
+            return Err(ExecErr::IntegerOverflow(what));
+        }
+        self.notice(
+            "integer overflow, the result was promoted to FLOAT and is approximate above 2^53"
+                .into(),
+        );
+        Ok(DataValue::Float(promoted))
+    }
+
+    pub(super) fn eval(&mut self, expr: &Expr, scope: &Scope) -> Result<DataValue> {
+        match expr {
+            Expr::Null => Ok(DataValue::Null),
+            Expr::Bool(b) => Ok(DataValue::Bool(*b)),
+            Expr::Int(n) => Ok(DataValue::Int(*n)),
+            Expr::Float(f) => Ok(DataValue::Float(*f)),
+            Expr::Text(t) => Ok(DataValue::String(t.to_string())),
+            Expr::Param(n) => self.params.get(*n as usize - 1).cloned().ok_or_else(|| {
+                ExecErr::InvalidArgument(format!("no value is bound to parameter ${}", n))
+            }),
+            Expr::Ident(name) => match scope.lookup(name) {
+                Err(ExecErr::ColumnNotFound(_)) => self.lookup_outer(name),
+                result => result,
+            },
+            Expr::FunctionCall { name, .. } if aggregate::lookup(name).is_some() => {
+                Err(ExecErr::InvalidArgument(format!(
+                    "aggregate function {} is not allowed here",
+                    name.to_uppercase()
+                )))
+            }
+            Expr::FunctionCall {
+                order_by: Some(_),
+                name,
+                ..
+            } => Err(ExecErr::InvalidArgument(format!(
+                "ORDER BY is only allowed in aggregate functions, not {}",
+                name.to_uppercase()
+            ))),
+            Expr::FunctionCall { name, args, .. } => {
+                let func = function::lookup(name)
+                    .ok_or_else(|| ExecErr::UnknownFunction(name.to_string()))?;
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg, scope))
+                    .collect::<Result<Vec<_>>>()?;
+                func.call(&args, self)
+            }
+            Expr::Unary { op, right } => {
+                let right = self.eval(right, scope)?;
+                match eval_unary(op, right) {
+                    // i64::MIN의 부호를 바꿀 때만 넘침
+                    Err(ExecErr::IntegerOverflow(what)) => {
+                        self.int_overflow(what, -(i64::MIN as f64))
+                    }
+                    result => result,
+                }
+            }
+            Expr::Binary { op, left, right } => {
+                let left = self.eval(left, scope)?;
+                let right = self.eval(right, scope)?;
+                let (left, right) = self.compare_operands(op, left, right);
+                let ints = match (&left, &right) {
+                    (DataValue::Int(a), DataValue::Int(b)) => Some((*a as f64, *b as f64)),
+                    _ => None,
+                };
+                match (eval_binary(op, left, right), ints) {
+                    (Err(ExecErr::IntegerOverflow(what)), Some((a, b))) => {
+                        self.int_overflow(what, float_arith(op, a, b))
+                    }
+                    (result, _) => result,
+                }
+            }
+            Expr::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => {
+                let value = self.eval(expr, scope)?;
+                let low = self.eval(low, scope)?;
+                let high = self.eval(high, scope)?;
+                let (left, low) = self.compare_operands(&Token::OpGe, value.clone(), low);
+                let ge = eval_binary(&Token::OpGe, left, low)?;
+                let (left, high) = self.compare_operands(&Token::OpLe, value, high);
+                let le = eval_binary(&Token::OpLe, left, high)?;
+                let between = eval_binary(&Token::And, ge, le)?;
+                if *negated {
+                    eval_unary(&Token::Not, between)
+                } else {
+                    Ok(between)
+                }
+            }
+            Expr::IsNull { expr, negated } => {
+                let is_null = self.eval(expr, scope)? == DataValue::Null;
+                Ok(DataValue::Bool(is_null != *negated))
+            }
+            Expr::Cast { expr, ty } => {
+                let ty =
+                    DataType::from_sql(ty).ok_or_else(|| ExecErr::UnknownType(ty.to_string()))?;
+                cast(self.eval(expr, scope)?, ty)
+            }
+            Expr::Window { func, .. } => Err(ExecErr::InvalidArgument(format!(
+                "window function {} is not allowed here",
+                match func.as_ref() {
+                    Expr::FunctionCall { name, .. } => name.to_uppercase(),
+                    _ => "call".to_string(),
+                }
+            ))),
+            Expr::Subquery(stmt) => self.eval_subquery(stmt, scope),
+            Expr::Exists(stmt) => {
+                // 한 행만 찾으면 되므로 LIMIT 1로 실행해서 일찍 멈추게 함
+                let mut stmt = stmt.as_ref().clone();
+                if let Stmt::Select { limit, .. } = &mut stmt {
+                    *limit = Some(1);
+                }
+                let set = self.run_subquery(stmt, scope)?;
+                Ok(DataValue::Bool(!set.rows.is_empty()))
+            }
+        }
+    }
+
+    /// 서브쿼리를 실행해서 하나의 값으로 만듦 (결과가 없으면 NULL)
+    fn eval_subquery(&mut self, stmt: &Stmt, scope: &Scope) -> Result<DataValue> {
+        let set = self.run_subquery(stmt.clone(), scope)?;
+        if set.columns.len() != 1 {
+            return Err(ExecErr::InvalidArgument(format!(
+                "subquery must return one column, but returned {}",
+                set.columns.len()
+            )));
+        }
+        let mut rows = set.rows.into_iter();
+        match (rows.next(), rows.next()) {
+            (None, _) => Ok(DataValue::Null),
+            (Some(mut row), None) => Ok(row.remove(0)),
+            (Some(_), Some(_)) => Err(ExecErr::InvalidArgument(
+                "subquery used as an expression returned more than one row".into(),
+            )),
+        }
+    }
+
+    /// 바깥 쿼리의 현재 행을 참조할 수 있도록 쌓아 둔 채로 서브쿼리를 실행
+    fn run_subquery(&mut self, stmt: Stmt, scope: &Scope) -> Result<ResultSet> {
+        self.outer
+            .push((scope.columns.to_vec(), scope.row.to_vec()));
+        let result = self.run_query(stmt);
+        self.outer.pop();
+        result
+    }
+
+    /// 서브쿼리 안에서 찾지 못한 컬럼은 가까운 바깥 쿼리부터 찾음
+    fn lookup_outer(&self, name: &str) -> Result<DataValue> {
+        for (columns, row) in self.outer.iter().rev() {
+            match Scope::new(columns, row).lookup(name) {
+                Err(ExecErr::ColumnNotFound(_)) => continue,
+                result => return result,
+            }
+        }
+        Err(ExecErr::ColumnNotFound(name.to_string()))
+    }
+}
+
+/// 컬럼을 참조하지 않고 휘발성 함수도 없는 식인지 확인
+/// (이런 식은 행마다 다시 계산할 필요 없이 한 번만 계산해도 됨)
+pub fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ident(_) | Expr::Subquery(_) | Expr::Exists(_) | Expr::Window { .. } => false,
+        Expr::FunctionCall { name, args, .. } => {
+            function::lookup(name).is_some_and(|f| !f.volatile) && args.iter().all(is_constant)
+        }
+        Expr::Unary { right, .. } => is_constant(right),
+        Expr::Binary { left, right, .. } => is_constant(left) && is_constant(right),
+        Expr::Between {
+            expr, low, high, ..
+        } => is_constant(expr) && is_constant(low) && is_constant(high),
+        Expr::IsNull { expr, .. } | Expr::Cast { expr, .. } => is_constant(expr),
+        _ => true,
+    }
+}
+
+/// WHERE 등의 조건식 결과를 참/거짓으로 변환 (NULL은 거짓으로 취급)
+pub fn is_truthy(value: &DataValue) -> Result<bool> {
+    match value {
+        DataValue::Bool(b) => Ok(*b),
+        DataValue::Null => Ok(false),
+        other => Err(ExecErr::TypeMismatch {
+            expected: "Bool".into(),
+            found: other.type_name().into(),
+        }),
+    }
+}
+
+pub fn compare(left: &DataValue, right: &DataValue) -> Result<Ordering> {
+    match (left, right) {
+        (DataValue::Int(a), DataValue::Int(b)) => Ok(a.cmp(b)),
+        (DataValue::Bool(a), DataValue::Bool(b)) => Ok(a.cmp(b)),
+        (DataValue::String(a), DataValue::String(b)) => Ok(a.cmp(b)),
+        (l, r) => Ok(storage::compare_floats(as_f64(l)?, as_f64(r)?)),
+    }
+}
+
+/// 정렬용 비교 (NULL의 위치는 방향과 상관없이 `nulls_first`를 따름)
+pub fn sort_compare(left: &DataValue, right: &DataValue, order: Order) -> Result<Ordering> {
+    let null = if order.nulls_first {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    };
+    match (left, right) {
+        (DataValue::Null, DataValue::Null) => Ok(Ordering::Equal),
+        (DataValue::Null, _) => Ok(null),
+        (_, DataValue::Null) => Ok(null.reverse()),
+        (l, r) if order.asc => compare(l, r),
+        (l, r) => Ok(compare(l, r)?.reverse()),
+    }
+}
+
+/// 여러 정렬 키를 순서대로 비교
+pub fn compare_keys(left: &[DataValue], right: &[DataValue], orders: &[Order]) -> Result<Ordering> {
+    for ((l, r), order) in left.iter().zip(right).zip(orders) {
+        let ord = sort_compare(l, r, *order)?;
+        if ord.is_ne() {
+            return Ok(ord);
+        }
+    }
+    Ok(Ordering::Equal)
+}
+
+/// 비교 중 오류가 나도 멈추지 않는 `sort_by`를 위해 첫 번째 오류를 기억해 둠
+pub fn sort_rows<T>(
+    rows: &mut [T],
+    mut compare: impl FnMut(&T, &T) -> Result<Ordering>,
+) -> Result<()> {
+    let mut err = None;
+    rows.sort_by(|a, b| {
+        compare(a, b).unwrap_or_else(|e| {
+            err.get_or_insert(e);
+            Ordering::Equal
+        })
+    });
+    err.map_or(Ok(()), Err)
+}
+
+/// CAST(<값> AS <타입>) - 변환할 수 없는 값은 오류 (NULL은 항상 NULL)
+pub fn cast(value: DataValue, ty: DataType) -> Result<DataValue> {
+    use DataValue::{Bool, Float, Int, Null, String};
+    let cast = match (&value, ty) {
+        (Null, _) => Some(Null),
+        (Int(n), DataType::Float) => Some(Float(*n as f64)),
+        (Int(n), DataType::Bool) => Some(Bool(*n != 0)),
+        // 실수 -> 정수는 소수점 아래를 버림 (범위를 벗어나면 오류)
+        (Float(f), DataType::Int) => {
+            (f.is_finite() && f.trunc() >= i64::MIN as f64 && f.trunc() < i64::MAX as f64)
+                .then(|| Int(f.trunc() as i64))
+        }
+        (Float(f), DataType::Bool) => Some(Bool(*f != 0.0)),
+        (Bool(b), DataType::Int) => Some(Int(*b as i64)),
+        (Bool(b), DataType::Float) => Some(Float(*b as i64 as f64)),
+        (Bool(b), DataType::String) => Some(String(b.to_string())),
+        (String(s), DataType::Int) => s.trim().parse().ok().map(Int),
+        (String(s), DataType::Float) => s.trim().parse().ok().map(Float),
+        (String(s), DataType::Bool) => match s.trim().to_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(Bool(true)),
+            "false" | "no" | "0" => Some(Bool(false)),
+            _ => None,
+        },
+        (other, DataType::String) => Some(String(other.to_string())),
+        (other, ty) if other.verify(ty) => Some(other.clone()),
+        _ => None,
+    };
+    cast.ok_or_else(|| ExecErr::InvalidCast {
+        value: value.to_string(),
+        ty: ty.as_str().into(),
+    })
+}
+
+fn as_f64(value: &DataValue) -> Result<f64> {
+    match value {
+        DataValue::Int(n) => Ok(*n as f64),
+        DataValue::Float(f) => Ok(*f),
+        other => Err(ExecErr::TypeMismatch {
+            expected: "number".into(),
+            found: other.type_name().into(),
+        }),
+    }
+}
+
+fn eval_unary(op: &Token, right: DataValue) -> Result<DataValue> {
+    match (op, right) {
+        (_, DataValue::Null) => Ok(DataValue::Null),
+        (Token::Not, DataValue::Bool(b)) => Ok(DataValue::Bool(!b)),
+        (Token::OpSub, DataValue::Int(n)) => n
+            .checked_neg()
+            .map(DataValue::Int)
+            .ok_or_else(|| ExecErr::IntegerOverflow(format!("-({})", n))),
+        (Token::OpSub, DataValue::Float(f)) => Ok(DataValue::Float(-f)),
+        (Token::Not, other) => Err(ExecErr::TypeMismatch {
+            expected: "Bool".into(),
+            found: other.type_name().into(),
+        }),
+        (_, other) => Err(ExecErr::TypeMismatch {
+            expected: "number".into(),
+            found: other.type_name().into(),
+        }),
+    }
+}
+
+fn eval_binary(op: &Token, left: DataValue, right: DataValue) -> Result<DataValue> {
+    use DataValue::{Bool, Null};
+    match (op, left, right) {
+        // 논리 연산은 NULL을 '알 수 없음'으로 취급하는 3값 논리를 따름
+        (Token::And, Bool(false), _) | (Token::And, _, Bool(false)) => Ok(Bool(false)),
+        (Token::And, Bool(true), Bool(true)) => Ok(Bool(true)),
+        (Token::Or, Bool(true), _) | (Token::Or, _, Bool(true)) => Ok(Bool(true)),
+        (Token::Or, Bool(false), Bool(false)) => Ok(Bool(false)),
+        (_, Null, _) | (_, _, Null) => Ok(Null),
+        (Token::And | Token::Or, l, r) => Err(ExecErr::TypeMismatch {
+            expected: "Bool".into(),
+            found: if let Bool(_) = l { r } else { l }.type_name().into(),
+        }),
+        (Token::OpEq, l, r) => Ok(Bool(compare(&l, &r)?.is_eq())),
+        (Token::OpLt, l, r) => Ok(Bool(compare(&l, &r)?.is_lt())),
+        (Token::OpGt, l, r) => Ok(Bool(compare(&l, &r)?.is_gt())),
+        (Token::OpLe, l, r) => Ok(Bool(compare(&l, &r)?.is_le())),
+        (Token::OpGe, l, r) => Ok(Bool(compare(&l, &r)?.is_ge())),
+        (Token::OpAdd | Token::OpSub | Token::OpMul | Token::OpDiv | Token::OpMod, l, r) => {
+            eval_arith(op, l, r)
+        }
+        (op, _, _) => Err(ExecErr::InvalidArgument(format!(
+            "unsupported operator {:?}",
+            op
+        ))),
+    }
+}
+
+/// INT끼리의 연산은 넘치면 `IntegerOverflow` 오류 (감싸지 않음)
+fn eval_arith(op: &Token, left: DataValue, right: DataValue) -> Result<DataValue> {
+    if let (DataValue::Int(a), DataValue::Int(b)) = (&left, &right) {
+        let (a, b) = (*a, *b);
+        if b == 0 && matches!(op, Token::OpDiv | Token::OpMod) {
+            return Err(ExecErr::DivisionByZero);
+        }
+        let (result, symbol) = match op {
+            Token::OpAdd => (a.checked_add(b), "+"),
+            Token::OpSub => (a.checked_sub(b), "-"),
+            Token::OpMul => (a.checked_mul(b), "*"),
+            Token::OpDiv => (a.checked_div(b), "/"),
+            // i64::MIN % -1만 None인데, 나머지는 0
+            _ => (Some(a.checked_rem(b).unwrap_or(0)), "%"),
+        };
+        return result
+            .map(DataValue::Int)
+            .ok_or_else(|| ExecErr::IntegerOverflow(format!("{} {} {}", a, symbol, b)));
+    }
+    let (a, b) = (as_f64(&left)?, as_f64(&right)?);
+    Ok(DataValue::Float(float_arith(op, a, b)))
+}
+
+fn float_arith(op: &Token, a: f64, b: f64) -> f64 {
+    match op {
+        Token::OpAdd => a + b,
+        Token::OpSub => a - b,
+        Token::OpMul => a * b,
+        Token::OpDiv => a / b,
+        _ => a % b,
+    }
+}
@@ -0,0 +1,241 @@
+use super::Executor;
+use super::error::{ExecErr, Result};
+use crate::storage::DataValue;
+
+type Body = fn(&[DataValue], &mut Executor) -> Result<DataValue>;
+
+pub struct Function {
+    pub name: &'static str,
+    pub min_args: usize,
+    pub max_args: usize,
+    // 호출할 때마다 결과가 달라지는 함수 (미리 계산하거나 캐시하면 안 됨)
+    pub volatile: bool,
+    body: Body,
+}
+
+const FUNCTIONS: &[Function] = &[
+    Function {
+        name: "RANDOM",
+        min_args: 0,
+        max_args: 0,
+        volatile: true,
+        body: random,
+    },
+    Function {
+        name: "RANDOM_TEXT",
+        min_args: 1,
+        max_args: 1,
+        volatile: true,
+        body: random_text,
+    },
+    Function {
+        name: "RANDOMBLOB",
+        min_args: 1,
+        max_args: 1,
+        volatile: true,
+        body: random_blob,
+    },
+    Function {
+        name: "UPPER",
+        min_args: 1,
+        max_args: 1,
+        volatile: false,
+        body: upper,
+    },
+    Function {
+        name: "LOWER",
+        min_args: 1,
+        max_args: 1,
+        volatile: false,
+        body: lower,
+    },
+    Function {
+        name: "LENGTH",
+        min_args: 1,
+        max_args: 1,
+        volatile: false,
+        body: length,
+    },
+    Function {
+        name: "ABS",
+        min_args: 1,
+        max_args: 1,
+        volatile: false,
+        body: abs,
+    },
+    Function {
+        name: "ROUND",
+        min_args: 1,
+        max_args: 2,
+        volatile: false,
+        body: round,
+    },
+    Function {
+        name: "SQUIRREL_TABLE_SIZE",
+        min_args: 1,
+        max_args: 1,
+        volatile: false,
+        body: table_size,
+    },
+    Function {
+        name: "SQUIRREL_ROW_ESTIMATE",
+        min_args: 1,
+        max_args: 1,
+        volatile: false,
+        body: row_estimate,
+    },
+];
+
+pub fn lookup(name: &str) -> Option<&'static Function> {
+    FUNCTIONS.iter().find(|f| f.name.eq_ignore_ascii_case(name))
+}
+
+impl Function {
+    pub fn call(&self, args: &[DataValue], exe: &mut Executor) -> Result<DataValue> {
+        if args.len() < self.min_args || args.len() > self.max_args {
+            return Err(ExecErr::InvalidArgument(format!(
+                "{} expects {} argument(s), but got {}",
+                self.name,
+                if self.min_args == self.max_args {
+                    self.min_args.to_string()
+                } else {
+                    format!("{}..{}", self.min_args, self.max_args)
+                },
+                args.len()
+            )));
+        }
+        (self.body)(args, exe)
+    }
+}
+
+// RANDOM_TEXT, RANDOMBLOB이 만들 수 있는 최대 길이
+const MAX_RANDOM_LEN: i64 = 1_000_000;
+
+fn length_arg(name: &str, arg: &DataValue) -> Result<usize> {
+    match arg {
+        DataValue::Int(n) if (0..=MAX_RANDOM_LEN).contains(n) => Ok(*n as usize),
+        DataValue::Int(n) => Err(ExecErr::InvalidArgument(format!(
+            "{} expects a length between 0 and {}, but got {}",
+            name, MAX_RANDOM_LEN, n
+        ))),
+        other => Err(type_err(name, "an Int length", other)),
+    }
+}
+
+fn type_err(name: &str, expected: &str, found: &DataValue) -> ExecErr {
+    ExecErr::InvalidArgument(format!(
+        "{} expects {}, but got {}",
+        name,
+        expected,
+        found.type_name()
+    ))
+}
+
+fn random(_: &[DataValue], exe: &mut Executor) -> Result<DataValue> {
+    Ok(DataValue::Int(exe.rng.next_u64() as i64))
+}
+
+fn random_text(args: &[DataValue], exe: &mut Executor) -> Result<DataValue> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let len = length_arg("RANDOM_TEXT", &args[0])?;
+    let text = (0..len)
+        .map(|_| ALPHABET[exe.rng.next_below(ALPHABET.len() as u64) as usize] as char)
+        .collect();
+    Ok(DataValue::String(text))
+}
+
+// BLOB 타입이 없으므로 16진수 문자열로 돌려줌
+fn random_blob(args: &[DataValue], exe: &mut Executor) -> Result<DataValue> {
+    let len = length_arg("RANDOMBLOB", &args[0])?;
+    let hex = (0..len)
+        .map(|_| format!("{:02X}", exe.rng.next_below(256)))
+        .collect();
+    Ok(DataValue::String(hex))
+}
+
+fn upper(args: &[DataValue], _: &mut Executor) -> Result<DataValue> {
+    match &args[0] {
+        DataValue::Null => Ok(DataValue::Null),
+        DataValue::String(s) => Ok(DataValue::String(s.to_uppercase())),
+        other => Err(type_err("UPPER", "String", other)),
+    }
+}
+
+fn lower(args: &[DataValue], _: &mut Executor) -> Result<DataValue> {
+    match &args[0] {
+        DataValue::Null => Ok(DataValue::Null),
+        DataValue::String(s) => Ok(DataValue::String(s.to_lowercase())),
+        other => Err(type_err("LOWER", "String", other)),
+    }
+}
+
+// 바이트 수가 아니라 글자 수
+fn length(args: &[DataValue], _: &mut Executor) -> Result<DataValue> {
+    match &args[0] {
+        DataValue::Null => Ok(DataValue::Null),
+        DataValue::String(s) => Ok(DataValue::Int(s.chars().count() as i64)),
+        other => Err(type_err("LENGTH", "String", other)),
+    }
+}
+
+fn abs(args: &[DataValue], exe: &mut Executor) -> Result<DataValue> {
+    match &args[0] {
+        DataValue::Null => Ok(DataValue::Null),
+        DataValue::Int(n) => match n.checked_abs() {
+            Some(n) => Ok(DataValue::Int(n)),
+            None => exe.int_overflow(format!("ABS({})", n), (*n as f64).abs()),
+        },
+        DataValue::Float(f) => Ok(DataValue::Float(f.abs())),
+        other => Err(type_err("ABS", "a number", other)),
+    }
+}
+
+// ROUND(x[, 자릿수]) - 자릿수가 음수면 정수 부분에서 반올림
+fn round(args: &[DataValue], exe: &mut Executor) -> Result<DataValue> {
+    let digits = match args.get(1) {
+        None => 0,
+        Some(DataValue::Null) => return Ok(DataValue::Null),
+        Some(DataValue::Int(n)) => (*n).clamp(-18, 18) as i32,
+        Some(other) => return Err(type_err("ROUND", "Int digits", other)),
+    };
+    match &args[0] {
+        DataValue::Null => Ok(DataValue::Null),
+        DataValue::Int(n) if digits >= 0 => Ok(DataValue::Int(*n)),
+        DataValue::Int(n) => {
+            let scale = 10i64.pow(digits.unsigned_abs());
+            let rounded = (*n as f64 / scale as f64).round();
+            match (rounded as i64).checked_mul(scale) {
+                Some(n) => Ok(DataValue::Int(n)),
+                None => {
+                    exe.int_overflow(format!("ROUND({}, {})", n, digits), rounded * scale as f64)
+                }
+            }
+        }
+        DataValue::Float(f) => {
+            let scale = 10f64.powi(digits);
+            Ok(DataValue::Float((f * scale).round() / scale))
+        }
+        other => Err(type_err("ROUND", "a number", other)),
+    }
+}
+
+fn table_size(args: &[DataValue], exe: &mut Executor) -> Result<DataValue> {
+    match &args[0] {
+        DataValue::Null => Ok(DataValue::Null),
+        DataValue::String(name) => {
+            exe.with_table(name, |table| DataValue::Int(table.size_bytes() as i64))
+        }
+        other => Err(type_err("SQUIRREL_TABLE_SIZE", "a table name", other)),
+    }
+}
+
+// 지금은 테이블이 메모리에 있으므로 추정치가 아니라 정확한 행 수
+fn row_estimate(args: &[DataValue], exe: &mut Executor) -> Result<DataValue> {
+    match &args[0] {
+        DataValue::Null => Ok(DataValue::Null),
+        DataValue::String(name) => {
+            exe.with_table(name, |table| DataValue::Int(table.rows.len() as i64))
+        }
+        other => Err(type_err("SQUIRREL_ROW_ESTIMATE", "a table name", other)),
+    }
+}
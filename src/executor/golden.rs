@@ -0,0 +1,80 @@
+// EXPLAIN (PROFILE)가 보여 주는 연산자 트리를 tests/golden/explain.txt와 비교하는 테스트
+//
+// 실행 시간은 빼고 연산자 이름과 들여쓰기만 비교하므로, 조인 순서나 연산자가 바뀌면 실패합니다.
+// 의도한 변경이면 `SQUIRREL_UPDATE_GOLDEN=1 cargo test golden`으로 파일을 다시 만들고 diff를 확인합니다.
+
+use super::{Executor, QueryResult};
+use crate::query::{Lexer, Parser};
+use std::path::{Path, PathBuf};
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name)
+}
+
+fn setup() -> Executor {
+    let mut exe = Executor::new();
+    let result = exe.run(
+        "CREATE TABLE users (id INT, name TEXT, age INT, city TEXT);
+         INSERT INTO users VALUES (1, 'Alpha', 25, 'Seoul'), (2, 'Beta', 41, 'Busan'),
+             (3, 'Gamma', 33, 'Seoul');
+         CREATE TABLE items (id INT, name TEXT);
+         INSERT INTO items VALUES (1, 'Acorn'), (2, 'Walnut');
+         CREATE TABLE orders (user_id INT, item_id INT, total INT);
+         INSERT INTO orders VALUES (1, 1, 5), (1, 2, 20), (3, 2, 15);"
+            .into(),
+    );
+    assert!(matches!(result, QueryResult::Success));
+    exe
+}
+
+// 쿼리마다 `-- <쿼리>` 줄과 실행 시간을 뺀 연산자 트리, 그리고 빈 줄
+fn explain_all(exe: &mut Executor, src: &str) -> String {
+    let stmts = Parser::new(Lexer::new(src))
+        .and_then(|mut p| p.parse())
+        .unwrap_or_else(|e| panic!("explain.sql: {}", e));
+    let mut out = String::new();
+    for stmt in stmts {
+        out.push_str(&format!("-- {}\n", stmt));
+        match exe.run(format!("EXPLAIN (PROFILE) {}", stmt)) {
+            QueryResult::Rows { rows, .. } => {
+                for row in rows {
+                    let (operator, _) = row[0].rsplit_once(" (").unwrap();
+                    out.push_str(operator);
+                    out.push('\n');
+                }
+            }
+            QueryResult::Error(e) => panic!("{}: {}", stmt, e),
+            QueryResult::Success => panic!("{}: expected rows", stmt),
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[test]
+fn test_explain_golden() {
+    let src = std::fs::read_to_string(golden_path("explain.sql")).unwrap();
+    let actual = explain_all(&mut setup(), &src);
+    let path = golden_path("explain.txt");
+    if std::env::var_os("SQUIRREL_UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_default();
+    if actual != expected {
+        // 처음으로 달라진 쿼리만 보여 줌 (쿼리 수가 다르면 전체)
+        let (actual, expected) = actual
+            .split("\n\n")
+            .zip(expected.split("\n\n"))
+            .find(|(a, e)| a != e)
+            .unwrap_or((&actual, &expected));
+        panic!(
+            "EXPLAIN differs from tests/golden/explain.txt \
+             (set SQUIRREL_UPDATE_GOLDEN=1 if the change is intended)\n\
+             expected:\n{}\n\nactual:\n{}",
+            expected, actual
+        );
+    }
+}
@@ -117,64 +117,3543 @@ Delta의 성별이 누락되었으니, 이를 갱신해봅시다.
 2. 해석한 문자열을 match하여 run_create, run_insert ...와 같은
    하위 메서드로 전달하여 처리하기
 
-3. 각 메서드에서 Executor의 mock 속성을 조작하여 쿼리를 처리하기
+3. 각 메서드에서 Database의 mock 속성을 조작하여 쿼리를 처리하기
    - mock 속성은 임시로 데이터를 저장하는 용도입니다.
    - 나중에 storage 모듈을 이용하여 데이터를 조작해야 합니다.
 
 우선 목표는 CREATE와 INSERT를 처리하는 것입니다.
 */
 
-use crate::query::{Expr, Lexer, Parser, Stmt};
+pub mod aggregate;
+pub mod copy;
+pub mod error;
+pub mod eval;
+pub mod function;
+#[cfg(test)]
+mod golden;
+pub mod profile;
+pub mod random;
+pub mod set_op;
+pub mod window;
+pub mod xlsx;
+
+use crate::query::error::QueryErr;
+use crate::query::{
+    Expr, ForeignKey, Join, JoinKind, Lexer, Order, Parser, Source, Stmt, TriggerEvent,
+};
 use crate::storage::{DataType, DataValue};
-use std::collections::HashMap;
+use error::{ExecErr, Result};
+use eval::Scope;
+use profile::Profile;
+use random::Rng;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ColumnId(pub u64);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RowId(pub u64);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TableId(pub u64);
 
-
 pub enum QueryResult {
-    Rows(Vec<Vec<String>>),
+    Rows {
+        columns: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
     // Count(usize), TODO: COUNT 함수 구현 후 사용
     Success,
     Error(String),
 }
 
+// TABLESAMPLE SYSTEM에서 한 번에 포함하거나 건너뛰는 행의 수 (페이지 대용)
+const SAMPLE_BLOCK_ROWS: usize = 64;
+
+// 트리거가 다른 트리거를 실행할 수 있는 깊이 (서로를 부르는 트리거가 끝없이 도는 것을 막음)
+const MAX_TRIGGER_DEPTH: usize = 16;
+
+#[derive(Clone)]
+pub struct Table {
+    columns: Vec<(String, DataType)>, // col name, col type
+    rows: Vec<Vec<DataValue>>,
+    foreign_keys: Vec<ForeignKeyInfo>,
+    triggers: Vec<Trigger>,
+    // 행이 바뀔 때마다 늘어나는 번호 (COMMIT할 때 그 사이 다른 세션이 바꿨는지 확인)
+    version: u64,
+}
+
+impl Table {
+    fn column_names(&self) -> Vec<String> {
+        self.columns.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// 테이블의 값들이 차지하는 메모리 크기 (바이트)
+    pub fn size_bytes(&self) -> usize {
+        self.rows
+            .iter()
+            .flatten()
+            .map(|value| match value {
+                DataValue::String(s) => size_of::<DataValue>() + s.capacity(),
+                _ => size_of::<DataValue>(),
+            })
+            .sum()
+    }
+
+    fn info(&self, name: &str) -> TableInfo {
+        TableInfo {
+            name: name.to_string(),
+            columns: self.columns(),
+            foreign_keys: self.foreign_keys.clone(),
+            rows: self.rows.len(),
+            size_bytes: self.size_bytes(),
+        }
+    }
+
+    pub fn columns(&self) -> Vec<ColumnInfo> {
+        self.columns
+            .iter()
+            .map(|(name, data_type)| ColumnInfo {
+                name: name.clone(),
+                data_type: *data_type,
+            })
+            .collect()
+    }
+}
+
+/// 라이브러리로 쓰는 쪽에서 조회하는 테이블 정의
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableInfo {
+    pub name: String,
+    pub columns: Vec<ColumnInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+    pub rows: usize,
+    pub size_bytes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub data_type: DataType,
+}
+
+/// `columns`의 값은 NULL이 아니면 `table`의 `ref_columns`에 있는 값이어야 함
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignKeyInfo {
+    pub columns: Vec<String>,
+    pub table: String,
+    pub ref_columns: Vec<String>,
+}
+
+/// 테이블에 행을 넣거나 바꾼 뒤 행마다 실행하는 문장들
+#[derive(Clone)]
+struct Trigger {
+    name: String,
+    event: TriggerEvent,
+    body: Vec<Stmt>,
+}
+
+/// BEGIN으로 시작해서 아직 COMMIT하지 않은 변경 내용
+///
+/// 공유 테이블을 바꾸는 대신 세션 안의 사본에 쓰고, COMMIT할 때 한 번에 반영합니다.
+/// 임시 테이블과 PRAGMA는 트랜잭션과 상관없이 바로 바뀝니다.
+#[derive(Default)]
+struct Transaction {
+    //   table name
+    tables: HashMap<String, TxTable>,
+    // COMMIT할 때 보낼 알림 (channel, payload)
+    notifications: Vec<(String, String)>,
+    // 무언가를 바꾼 문장마다 그 문장을 실행하기 직전의 상태 (마지막 문장부터 되돌릴 수 있음)
+    savepoints: Vec<Savepoint>,
+    // 실행 중인 문장이 바꾼 테이블들의 바뀌기 전 상태
+    current: Savepoint,
+}
+
+/// 트랜잭션 안에서 바꾼 테이블의 사본
+#[derive(Clone)]
+struct TxTable {
+    // 사본을 만들 때 공유 테이블의 버전과 행 수 (트랜잭션 안에서 만든 테이블이면 None)
+    base: Option<(u64, usize)>,
+    table: Table,
+    // 있던 행을 바꿨으면 COMMIT할 때 행을 덧붙이는 대신 통째로 바꿈
+    rewritten: bool,
+}
+
+/// 트랜잭션 안에서 되돌아갈 지점
+///
+/// 문장이 처음 바꾸는 테이블마다 바꾸기 직전의 사본을 남깁니다.
+#[derive(Default)]
+struct Savepoint {
+    //   table name → 바뀌기 전 사본 (그 문장이 만든 테이블이면 None)
+    tables: HashMap<String, Option<TxTable>>,
+    notifications: usize,
+}
+
+impl Transaction {
+    fn begin_statement(&mut self) {
+        self.current = Savepoint {
+            tables: HashMap::new(),
+            notifications: self.notifications.len(),
+        };
+    }
+
+    /// 문장이 성공했고 무언가를 바꿨으면 되돌릴 수 있게 남기고, 실패했으면 문장 전으로 되돌림
+    fn end_statement(&mut self, ok: bool) {
+        let savepoint = std::mem::take(&mut self.current);
+        if !ok {
+            self.rollback_to(savepoint);
+        } else if !savepoint.tables.is_empty()
+            || savepoint.notifications != self.notifications.len()
+        {
+            self.savepoints.push(savepoint);
+        }
+    }
+
+    /// 테이블을 바꾸기 전에 호출 (문장 안에서 처음이면 지금 상태를 남김)
+    fn touch(&mut self, name: &str) {
+        let tables = &self.tables;
+        self.current
+            .tables
+            .entry(name.to_string())
+            .or_insert_with(|| tables.get(name).cloned());
+    }
+
+    /// 바꿀 테이블의 사본 (처음 바꾸는 공유 테이블이면 사본을 만듦)
+    fn copy(&mut self, name: &str, shared: &HashMap<String, Table>) -> Result<&mut TxTable> {
+        self.touch(name);
+        match self.tables.entry(name.to_string()) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let source = shared
+                    .get(name)
+                    .ok_or_else(|| ExecErr::TableNotFound(name.to_string()))?;
+                Ok(entry.insert(TxTable {
+                    base: Some((source.version, source.rows.len())),
+                    table: source.clone(),
+                    rewritten: false,
+                }))
+            }
+        }
+    }
+
+    fn rollback_to(&mut self, savepoint: Savepoint) {
+        for (name, before) in savepoint.tables {
+            match before {
+                Some(table) => self.tables.insert(name, table),
+                None => self.tables.remove(&name),
+            };
+        }
+        self.notifications.truncate(savepoint.notifications);
+    }
+}
+
+/// 실행 중간 결과 (출력 직전에 문자열로 변환)
+pub struct ResultSet {
+    columns: Vec<String>,
+    rows: Vec<Vec<DataValue>>,
+}
+
+impl From<ResultSet> for QueryResult {
+    fn from(set: ResultSet) -> Self {
+        QueryResult::Rows {
+            columns: set.columns,
+            rows: set
+                .rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+}
+
+/// 한 번 파싱해 두고 파라미터(`?`, `$1`) 값만 바꿔 가며 실행하는 쿼리
+///
+/// 값은 SQL 문자열에 이어 붙이지 않고 그대로 바인딩되므로 따옴표 등을 이스케이프할 필요가 없습니다.
+#[derive(Debug, Clone)]
+pub struct Prepared {
+    stmts: Vec<Stmt>,
+    params: usize,
+}
+
+impl Prepared {
+    pub fn new(src: &str) -> std::result::Result<Self, QueryErr> {
+        let mut parser = Parser::new(Lexer::new(src))?;
+        let stmts = parser.parse()?;
+        Ok(Self {
+            stmts,
+            params: parser.param_count() as usize,
+        })
+    }
+
+    /// 실행할 때 바인딩해야 하는 값의 수 (가장 큰 파라미터 번호)
+    pub fn param_count(&self) -> usize {
+        self.params
+    }
+}
+
+/// NOTIFY로 보낸 알림
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: String,
+}
+
+/// 여러 세션이 함께 쓰는 데이터베이스
+#[derive(Default)]
+pub struct Database {
+    //   table name
+    mock: RwLock<HashMap<String, Table>>,
+    //   channel name → LISTEN 중인 수신자들
+    listeners: Mutex<HashMap<String, Vec<Sender<Notification>>>>,
+}
+
+impl Database {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    // 다른 세션이 패닉으로 락을 오염시켜도 테이블 자체는 여전히 읽을 수 있음
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Table>> {
+        self.mock.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Table>> {
+        self.mock.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 채널의 알림을 받을 수신자를 만듦 (수신자를 버리면 자동으로 구독 해제)
+    pub fn listen(&self, channel: &str) -> Receiver<Notification> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribe(channel, tx);
+        rx
+    }
+
+    fn subscribe(&self, channel: &str, tx: Sender<Notification>) {
+        let mut listeners = self
+            .listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        listeners.entry(channel.to_string()).or_default().push(tx);
+    }
+
+    /// 채널을 LISTEN 중인 모든 수신자에게 알림을 보냄
+    pub fn notify(&self, channel: &str, payload: &str) {
+        let mut listeners = self
+            .listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(senders) = listeners.get_mut(channel) {
+            let notification = Notification {
+                channel: channel.to_string(),
+                payload: payload.to_string(),
+            };
+            // 이미 닫힌 수신자는 목록에서 뺌
+            senders.retain(|tx| tx.send(notification.clone()).is_ok());
+        }
+    }
+
+    /// 모든 테이블의 정의 (이름순, 세션의 임시 테이블은 빠짐)
+    pub fn tables(&self) -> Vec<TableInfo> {
+        let mut tables: Vec<_> = self
+            .read()
+            .iter()
+            .map(|(name, table)| table.info(name))
+            .collect();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        tables
+    }
+
+    pub fn table(&self, name: &str) -> Option<TableInfo> {
+        self.read().get(name).map(|table| table.info(name))
+    }
+}
+
+/// 연결 하나에 해당하는 세션
+///
+/// 테이블은 `Database`를 통해 다른 세션과 공유하고,
+/// 임시 테이블과 PRAGMA 설정(난수 시드 등)은 세션마다 따로 가집니다.
 pub struct Executor {
-    //          table name, column name,      column type
-    mock: HashMap<String, (Vec<DataType>, Vec<Vec<DataValue>>)>,
+    db: Arc<Database>,
+    //   table name
+    temp: HashMap<String, Table>,
+    rng: Rng,
+    // 실행 중인 서브쿼리를 감싼 바깥 쿼리들의 현재 행 (컬럼 이름, 값)
+    outer: Vec<(Vec<String>, Vec<DataValue>)>,
+    // LISTEN한 채널들과, 그 채널의 알림이 들어오는 곳
+    listening: HashSet<String>,
+    notify_tx: Sender<Notification>,
+    notify_rx: Receiver<Notification>,
+    // EXPLAIN (PROFILE) 중이면 연산자별 실행 시간을 기록
+    profile: Option<Profile>,
+    // 실행 중인 WITH 절의 결과 (이름, 결과), 뒤에 있을수록 안쪽 WITH
+    ctes: Vec<(String, ResultSet)>,
+    // BEGIN ~ COMMIT 사이면 아직 반영하지 않은 변경 내용
+    tx: Option<Transaction>,
+    // 꺼져 있으면 트랜잭션 밖에서 문장을 실행할 때 트랜잭션을 자동으로 시작
+    autocommit: bool,
+    // 켜져 있으면 문자열 -> 숫자 같은 암시적 변환을 하지 않고 오류로 처리 (PRAGMA strict)
+    strict: bool,
+    // 실행 중인 준비된 쿼리에 바인딩한 값 ($1이 0번)
+    params: Vec<DataValue>,
+    // 쿼리 결과를 한 번에 돌려줄 최대 행 수와, 마지막 결과 중 아직 돌려주지 않은 행
+    row_limit: Option<usize>,
+    cursor: std::vec::IntoIter<Vec<DataValue>>,
+    // 오류는 아니지만 알려 줄 만한 일 (마지막 `run` 동안 쌓인 것)
+    notices: Vec<String>,
+    // 실행 중인 트리거가 겹친 수
+    trigger_depth: usize,
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Executor {
     pub fn new() -> Self {
+        Self::connect(Database::new())
+    }
+
+    /// 같은 데이터베이스에 새 세션을 연결
+    pub fn connect(db: Arc<Database>) -> Self {
+        let (notify_tx, notify_rx) = mpsc::channel();
         Self {
-            mock: HashMap::new(),
+            db,
+            temp: HashMap::new(),
+            rng: Rng::from_entropy(),
+            outer: Vec::new(),
+            listening: HashSet::new(),
+            notify_tx,
+            notify_rx,
+            profile: None,
+            ctes: Vec::new(),
+            tx: None,
+            autocommit: true,
+            strict: false,
+            params: Vec::new(),
+            row_limit: None,
+            cursor: Vec::new().into_iter(),
+            notices: Vec::new(),
+            trigger_depth: 0,
         }
     }
 
+    /// 문장 하나라도 파싱에 실패하면 아무것도 실행하지 않고, 모든 파싱 오류를 한 줄에 하나씩 돌려줌
     pub fn run(&mut self, src: String) -> QueryResult {
         let lexer = Lexer::new(&src);
-        let parser = Parser::new(lexer);
-        let stmts = parser.unwrap().parse(); // TODO: 오류 처리
-        if let Err(e) = stmts {
-            return QueryResult::Error(e.to_string());
+        let (stmts, errors) = Parser::parse_all(lexer);
+        if errors.is_empty() {
+            self.run_stmts(stmts)
+        } else {
+            let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            QueryResult::Error(errors.join("\n"))
+        }
+    }
+
+    /// 준비된 쿼리를 `params`의 값으로 실행 (`params[0]`이 `?` 또는 `$1`)
+    pub fn run_prepared(&mut self, prepared: &Prepared, params: &[DataValue]) -> QueryResult {
+        if params.len() != prepared.params {
+            return QueryResult::Error(
+                ExecErr::InvalidArgument(format!(
+                    "expected {} parameter values, but got {}",
+                    prepared.params,
+                    params.len()
+                ))
+                .to_string(),
+            );
         }
-        let stmts = stmts.unwrap();
+        let outer = std::mem::replace(&mut self.params, params.to_vec());
+        let result = self.run_stmts(prepared.stmts.clone());
+        self.params = outer;
+        result
+    }
+
+    fn run_stmts(&mut self, stmts: Vec<Stmt>) -> QueryResult {
+        self.notices.clear();
+        let mut result = QueryResult::Success;
         for stmt in stmts {
-            match stmt {
-                _ => self.execute_simple(stmt),
+            self.cursor = Vec::new().into_iter();
+            result = match self.execute_undoable(stmt) {
+                Ok(result) => result,
+                Err(e) => return QueryResult::Error(e.to_string()),
+            };
+        }
+        result
+    }
+
+    /// 쿼리를 실행하면서 연산자별 실행 시간을 함께 기록
+    pub fn run_profiled(&mut self, src: String) -> (QueryResult, Profile) {
+        let outer = self.profile.replace(Profile::default());
+        let result = self.run(src);
+        let profile = std::mem::replace(&mut self.profile, outer).unwrap_or_default();
+        (result, profile)
+    }
+
+    /// `f`의 실행 시간을 `name` 연산자로 기록 (프로파일 중이 아니면 그냥 실행)
+    fn span<T>(&mut self, name: &str, f: impl FnOnce(&mut Self) -> T) -> T {
+        if let Some(profile) = &mut self.profile {
+            profile.enter(name);
+        }
+        let out = f(self);
+        if let Some(profile) = &mut self.profile {
+            profile.exit();
+        }
+        out
+    }
+
+    fn execute(&mut self, stmt: Stmt) -> Result<QueryResult> {
+        match stmt {
+            Stmt::Create {
+                table,
+                columns,
+                foreign_keys,
+                if_not_exists,
+                temporary,
+            } => self.run_create(&table, columns, foreign_keys, if_not_exists, temporary),
+            Stmt::CreateTrigger {
+                name,
+                event,
+                table,
+                body,
+                if_not_exists,
+            } => self.run_create_trigger(&name, event, &table, body, if_not_exists),
+            Stmt::InsertValues {
+                table,
+                columns,
+                values,
+            } => {
+                let values = self.eval_rows(values)?;
+                self.run_insert(&table, columns, values)
+            }
+            Stmt::InsertQuery {
+                table,
+                columns,
+                query,
+            } => {
+                let set = self.span("Insert", |exe| exe.run_query(*query))?;
+                self.run_insert(&table, columns, set.rows)
+            }
+            Stmt::Update {
+                table,
+                assigns,
+                from,
+                where_clause,
+            } => self.run_update(&table, assigns, from, where_clause),
+            stmt @ (Stmt::Select { .. }
+            | Stmt::Compound { .. }
+            | Stmt::With { .. }
+            | Stmt::Values(_)) => {
+                let mut set = self.run_query(stmt)?;
+                if let Some(limit) = self.row_limit
+                    && set.rows.len() > limit
+                {
+                    self.cursor = set.rows.split_off(limit).into_iter();
+                }
+                Ok(set.into())
+            }
+            Stmt::Pragma { name, value } => self.run_pragma(&name, value),
+            Stmt::Listen { channel } => {
+                if self.listening.insert(channel.to_string()) {
+                    self.db.subscribe(&channel, self.notify_tx.clone());
+                }
+                Ok(QueryResult::Success)
             }
+            // 트랜잭션 안에서 보낸 알림은 COMMIT할 때 전달
+            Stmt::Notify { channel, payload } => {
+                match &mut self.tx {
+                    Some(tx) => tx
+                        .notifications
+                        .push((channel.to_string(), payload.to_string())),
+                    None => self.db.notify(&channel, &payload),
+                }
+                Ok(QueryResult::Success)
+            }
+            Stmt::Begin => {
+                if self.tx.is_some() {
+                    return Err(ExecErr::InvalidArgument(
+                        "a transaction is already in progress".into(),
+                    ));
+                }
+                self.tx = Some(Transaction::default());
+                Ok(QueryResult::Success)
+            }
+            Stmt::Copy {
+                table,
+                path,
+                export,
+                format,
+            } => self.run_copy(&table, &path, export, &format),
+            Stmt::Commit => self.run_commit(),
+            Stmt::Rollback => match self.tx.take() {
+                Some(_) => Ok(QueryResult::Success),
+                None => Err(ExecErr::InvalidArgument(
+                    "no transaction is in progress".into(),
+                )),
+            },
+            Stmt::Explain { stmt, json } => {
+                let outer = self.profile.replace(Profile::default());
+                let result = self.execute(*stmt);
+                let profile = std::mem::replace(&mut self.profile, outer).unwrap_or_default();
+                self.cursor = Vec::new().into_iter();
+                result?;
+                let rows = if json {
+                    vec![vec![profile.to_json()]]
+                } else {
+                    profile
+                        .to_text()
+                        .into_iter()
+                        .map(|line| vec![line])
+                        .collect()
+                };
+                Ok(QueryResult::Rows {
+                    columns: vec!["QUERY PLAN".into()],
+                    rows,
+                })
+            }
+            stmt => Err(ExecErr::Unsupported(format!("{:?}", stmt))),
+        }
+    }
+
+    /// autocommit이 꺼져 있으면 트랜잭션을 자동으로 시작하고,
+    /// 트랜잭션 안에서 무언가를 바꾼 문장은 `undo_last`로 되돌릴 수 있게 실행 전 상태를 남김
+    /// (실패한 문장은 그 문장 전으로 되돌림)
+    fn execute_undoable(&mut self, stmt: Stmt) -> Result<QueryResult> {
+        let control = matches!(
+            stmt,
+            Stmt::Begin | Stmt::Commit | Stmt::Rollback | Stmt::Pragma { .. }
+        );
+        if !self.autocommit && !control && self.tx.is_none() {
+            self.tx = Some(Transaction::default());
+        }
+        if let Some(tx) = &mut self.tx {
+            tx.begin_statement();
+        }
+        let result = self.execute(stmt);
+        if let Some(tx) = &mut self.tx {
+            tx.end_statement(result.is_ok());
         }
-        QueryResult::Success
+        result
     }
 
-    pub fn execute_simple(&self, stmt: Stmt) {
-        if let Stmt::Create { table, columns, .. } = stmt {
-            println!("Creating table: {}", table);
-        } else if let Stmt::InsertValues { table, values, .. } = stmt {
-            println!("Inserting data into: {}", table);
+    /// 트랜잭션 안에서 마지막으로 무언가를 바꾼 문장을 되돌림 (되돌릴 문장이 없으면 false)
+    pub fn undo_last(&mut self) -> bool {
+        let Some(tx) = &mut self.tx else {
+            return false;
+        };
+        match tx.savepoints.pop() {
+            Some(savepoint) => {
+                tx.rollback_to(savepoint);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.tx.as_ref().is_some_and(|tx| !tx.savepoints.is_empty())
+    }
+
+    pub fn autocommit(&self) -> bool {
+        self.autocommit
+    }
+
+    /// BEGIN으로 시작한 트랜잭션이 열려 있는지
+    pub fn in_transaction(&self) -> bool {
+        self.tx.is_some()
+    }
+
+    /// 쿼리 결과를 `limit`행까지만 돌려주고, 나머지는 `fetch_more`로 꺼내도록 남겨 둠 (None이면 제한 없음)
+    pub fn set_row_limit(&mut self, limit: Option<usize>) {
+        self.row_limit = limit;
+    }
+
+    /// 마지막 쿼리 결과 중 아직 돌려주지 않은 행을 `n`행까지 꺼냄
+    pub fn fetch_more(&mut self, n: usize) -> Vec<Vec<String>> {
+        self.cursor
+            .by_ref()
+            .take(n)
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect()
+    }
+
+    pub fn remaining_rows(&self) -> usize {
+        self.cursor.len()
+    }
+
+    /// 마지막으로 실행한 쿼리가 남긴 알림(NOTICE)을 꺼냄 (예: 암묵적 형 변환)
+    pub fn take_notices(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.notices)
+    }
+
+    pub fn database(&self) -> &Arc<Database> {
+        &self.db
+    }
+
+    /// LISTEN한 채널로 지금까지 들어온 알림을 꺼냄 (기다리지 않음)
+    pub fn notifications(&self) -> impl Iterator<Item = Notification> + '_ {
+        self.notify_rx.try_iter()
+    }
+
+    /// 이 세션에서 보이는 테이블 (임시 테이블과 트랜잭션 안에서 만들거나 바꾼 테이블 포함)
+    pub fn tables(&self) -> Vec<TableInfo> {
+        let mut tables: BTreeMap<&str, TableInfo> = BTreeMap::new();
+        let shared = self.db.read();
+        let tx_tables = self.tx.iter().flat_map(|tx| &tx.tables);
+        let visible = shared
+            .iter()
+            .chain(tx_tables.map(|(name, copy)| (name, &copy.table)))
+            .chain(&self.temp);
+        // 같은 이름이면 나중 것(트랜잭션의 사본, 임시 테이블)이 우선
+        for (name, table) in visible {
+            tables.insert(name, table.info(name));
+        }
+        tables.into_values().collect()
+    }
+
+    /// 이 세션에서 이름으로 찾은 테이블 (같은 이름이면 임시 테이블이 우선)
+    pub fn table(&self, name: &str) -> Option<TableInfo> {
+        self.with_table(name, |table| table.info(name)).ok()
+    }
+
+    /// 테이블의 컬럼 정의 (같은 이름이면 임시 테이블이 우선)
+    fn schema(&self, name: &str) -> Result<Vec<(String, DataType)>> {
+        self.with_table(name, |table| table.columns.clone())
+    }
+
+    /// 이름으로 찾은 테이블에 `f`를 적용
+    /// (같은 이름이면 임시 테이블, 트랜잭션 안의 사본, 공유 테이블 순서로 우선)
+    fn with_table<T>(&self, name: &str, f: impl FnOnce(&Table) -> T) -> Result<T> {
+        match self.temp.get(name).or_else(|| self.tx_table(name)) {
+            Some(table) => Ok(f(table)),
+            None => self
+                .db
+                .read()
+                .get(name)
+                .map(f)
+                .ok_or_else(|| ExecErr::TableNotFound(name.to_string())),
+        }
+    }
+
+    fn tx_table(&self, name: &str) -> Option<&Table> {
+        self.tx.as_ref()?.tables.get(name).map(|copy| &copy.table)
+    }
+
+    /// 트랜잭션의 변경 내용을 공유 테이블에 한 번에 반영
+    ///
+    /// 그 사이 다른 세션이 같은 이름의 테이블을 만들었거나 지웠으면,
+    /// 또는 이 트랜잭션이 있던 행을 바꾼 테이블을 다른 세션도 바꿨으면 아무것도 반영하지 않음
+    fn run_commit(&mut self) -> Result<QueryResult> {
+        let tx = self
+            .tx
+            .take()
+            .ok_or_else(|| ExecErr::InvalidArgument("no transaction is in progress".into()))?;
+        let mut shared = self.db.write();
+        for (name, copy) in &tx.tables {
+            match (copy.base, shared.get(name)) {
+                (None, Some(_)) => return Err(ExecErr::TableExists(name.clone())),
+                (Some(_), None) => return Err(ExecErr::TableNotFound(name.clone())),
+                (Some((version, _)), Some(table)) if copy.rewritten && table.version != version => {
+                    return Err(ExecErr::WriteConflict(name.clone()));
+                }
+                _ => {}
+            }
+        }
+        for (name, copy) in tx.tables {
+            let Some((_, rows)) = copy.base else {
+                shared.insert(name, copy.table);
+                continue;
+            };
+            let target = shared.get_mut(&name).expect("checked above");
+            if copy.rewritten {
+                target.rows = copy.table.rows;
+            } else {
+                // 다른 세션이 그 사이 넣은 행은 그대로 두고, 이 트랜잭션이 넣은 행만 덧붙임
+                target.rows.extend(copy.table.rows.into_iter().skip(rows));
+            }
+            target.version += 1;
+        }
+        drop(shared);
+        for (channel, payload) in tx.notifications {
+            self.db.notify(&channel, &payload);
+        }
+        Ok(QueryResult::Success)
+    }
+
+    fn run_create(
+        &mut self,
+        table: &str,
+        columns: Vec<(Box<str>, Box<str>)>,
+        foreign_keys: Vec<ForeignKey>,
+        if_not_exists: bool,
+        temporary: bool,
+    ) -> Result<QueryResult> {
+        let exists = if temporary {
+            self.temp.contains_key(table)
+        } else {
+            self.tx_table(table).is_some() || self.db.read().contains_key(table)
+        };
+        if exists {
+            return if if_not_exists {
+                self.notices
+                    .push(format!("table '{}' already exists, skipping", table));
+                Ok(QueryResult::Success)
+            } else {
+                Err(ExecErr::TableExists(table.to_string()))
+            };
+        }
+        let columns = columns
+            .into_iter()
+            .map(|(name, ty)| {
+                let ty =
+                    DataType::from_sql(&ty).ok_or_else(|| ExecErr::UnknownType(ty.to_string()))?;
+                Ok((name.to_string(), ty))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let foreign_keys = foreign_keys
+            .into_iter()
+            .map(|fk| self.check_foreign_key(table, &columns, temporary, fk))
+            .collect::<Result<Vec<_>>>()?;
+        let new = Table {
+            columns,
+            rows: Vec::new(),
+            foreign_keys,
+            triggers: Vec::new(),
+            version: 0,
+        };
+        if temporary {
+            self.temp.insert(table.to_string(), new);
+        } else if let Some(tx) = &mut self.tx {
+            tx.touch(table);
+            tx.tables.insert(
+                table.to_string(),
+                TxTable {
+                    base: None,
+                    table: new,
+                    rewritten: false,
+                },
+            );
         } else {
-            println!("Unsupported statement");
+            self.db.write().insert(table.to_string(), new);
+        }
+        Ok(QueryResult::Success)
+    }
+
+    /// 테이블에 트리거를 붙임 (트리거 이름은 데이터베이스 전체에서 하나여야 함)
+    fn run_create_trigger(
+        &mut self,
+        name: &str,
+        event: TriggerEvent,
+        table: &str,
+        body: Vec<Stmt>,
+        if_not_exists: bool,
+    ) -> Result<QueryResult> {
+        // 트랜잭션은 행의 변경만 반영하므로 트리거를 함께 반영할 수 없음
+        if self.tx.is_some() && !self.temp.contains_key(table) {
+            return Err(ExecErr::Unsupported(
+                "CREATE TRIGGER inside a transaction".into(),
+            ));
+        }
+        let mut shared = self.db.write();
+        let exists = shared
+            .values()
+            .chain(self.temp.values())
+            .any(|t| t.triggers.iter().any(|trigger| trigger.name == name));
+        if exists {
+            drop(shared);
+            return if if_not_exists {
+                self.notices
+                    .push(format!("trigger '{}' already exists, skipping", name));
+                Ok(QueryResult::Success)
+            } else {
+                Err(ExecErr::TriggerExists(name.to_string()))
+            };
         }
+        let target = match self.temp.get_mut(table) {
+            Some(target) => target,
+            None => shared
+                .get_mut(table)
+                .ok_or_else(|| ExecErr::TableNotFound(table.to_string()))?,
+        };
+        target.triggers.push(Trigger {
+            name: name.to_string(),
+            event,
+            body,
+        });
+        Ok(QueryResult::Success)
+    }
+
+    fn triggers(&self, table: &str, event: TriggerEvent) -> Result<Vec<Trigger>> {
+        self.with_table(table, |t| {
+            t.triggers
+                .iter()
+                .filter(|trigger| trigger.event == event)
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// 바뀐 행마다 트리거의 문장들을 실행 (`changes`는 바뀌기 전 행과 바뀐 뒤 행)
+    ///
+    /// 트리거 안에서는 `NEW.<col>`로 바뀐 뒤 행을, UPDATE면 `OLD.<col>`로 바뀌기 전 행을 읽습니다.
+    /// 트리거가 실패했을 때 바뀐 행을 되돌리려면 `atomically` 안에서 불러야 합니다.
+    fn fire_triggers(
+        &mut self,
+        table: &str,
+        triggers: &[Trigger],
+        changes: Vec<(Option<Vec<DataValue>>, Vec<DataValue>)>,
+    ) -> Result<()> {
+        if triggers.is_empty() {
+            return Ok(());
+        }
+        if self.trigger_depth >= MAX_TRIGGER_DEPTH {
+            return Err(ExecErr::InvalidArgument(format!(
+                "triggers on '{}' are nested more than {} levels deep",
+                table, MAX_TRIGGER_DEPTH
+            )));
+        }
+        let columns = self.schema(table)?;
+        self.trigger_depth += 1;
+        let result = changes.into_iter().try_for_each(|(old, new)| {
+            let mut names = Vec::new();
+            let mut row = Vec::new();
+            for (qualifier, values) in [("NEW", Some(new)), ("OLD", old)] {
+                let Some(values) = values else {
+                    continue;
+                };
+                // NEW.id, new.id 어느 쪽으로 써도 찾을 수 있음
+                for qualifier in [qualifier.to_string(), qualifier.to_lowercase()] {
+                    names.extend(columns.iter().map(|(c, _)| format!("{}.{}", qualifier, c)));
+                    row.extend(values.iter().cloned());
+                }
+            }
+            self.outer.push((names, row));
+            let result = triggers
+                .iter()
+                .flat_map(|trigger| &trigger.body)
+                .try_for_each(|stmt| self.execute(stmt.clone()).map(drop));
+            self.outer.pop();
+            result
+        });
+        self.trigger_depth -= 1;
+        // 트리거 안의 SELECT 결과는 돌려주지 않음
+        self.cursor = Vec::new().into_iter();
+        result
+    }
+
+    /// `f`가 바꾼 것을 모두 반영하거나, 실패하면 하나도 반영하지 않음 (트리거가 있는 문장에 사용)
+    ///
+    /// 트랜잭션 밖이면 `f`를 트랜잭션 하나로 실행하고, 트랜잭션 안이면 실패한 문장을
+    /// `execute_undoable`이 되돌립니다. 임시 테이블은 트랜잭션과 상관없으므로 따로 복사해 둡니다.
+    fn atomically<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let temp = self.temp.clone();
+        let implicit = self.tx.is_none();
+        if implicit {
+            self.tx = Some(Transaction::default());
+        }
+        let mut result = f(self);
+        if implicit {
+            result = match result {
+                Ok(value) => self.run_commit().map(|_| value),
+                Err(e) => {
+                    self.tx = None;
+                    Err(e)
+                }
+            };
+        }
+        if result.is_err() {
+            self.temp = temp;
+        }
+        result
+    }
+
+    /// 외래 키 정의가 올바른지 확인 (컬럼이 있고, 개수와 타입이 같아야 함)
+    fn check_foreign_key(
+        &self,
+        table: &str,
+        columns: &[(String, DataType)],
+        temporary: bool,
+        fk: ForeignKey,
+    ) -> Result<ForeignKeyInfo> {
+        // 자기 자신을 참조할 수도 있음
+        let ref_schema = if fk.table.as_ref() == table {
+            columns.to_vec()
+        } else if !temporary && self.temp.contains_key(fk.table.as_ref()) {
+            return Err(ExecErr::InvalidArgument(format!(
+                "table '{}' cannot reference temporary table '{}'",
+                table, fk.table
+            )));
+        } else {
+            self.schema(&fk.table)?
+        };
+        if fk.columns.len() != fk.ref_columns.len() {
+            return Err(ExecErr::ColumnCountMismatch {
+                expected: fk.ref_columns.len(),
+                found: fk.columns.len(),
+            });
+        }
+        let find = |schema: &[(String, DataType)], col: &str| {
+            schema
+                .iter()
+                .find(|(name, _)| name == col)
+                .map(|(_, ty)| *ty)
+                .ok_or_else(|| ExecErr::ColumnNotFound(col.to_string()))
+        };
+        for (col, ref_col) in fk.columns.iter().zip(&fk.ref_columns) {
+            let (ty, ref_ty) = (find(columns, col)?, find(&ref_schema, ref_col)?);
+            if ty != ref_ty {
+                return Err(ExecErr::TypeMismatch {
+                    expected: ref_ty.as_str().into(),
+                    found: ty.as_str().into(),
+                });
+            }
+        }
+        Ok(ForeignKeyInfo {
+            columns: fk.columns.iter().map(|c| c.to_string()).collect(),
+            table: fk.table.to_string(),
+            ref_columns: fk.ref_columns.iter().map(|c| c.to_string()).collect(),
+        })
+    }
+
+    /// 새 행들이 참조하는 행이 모두 있는지 확인 (같이 넣는 행도 참조할 수 있음)
+    fn check_references(&self, table: &str, rows: &[Vec<DataValue>]) -> Result<()> {
+        let (schema, foreign_keys) =
+            self.with_table(table, |t| (t.columns.clone(), t.foreign_keys.clone()))?;
+        let position = |schema: &[(String, DataType)], col: &str| {
+            schema
+                .iter()
+                .position(|(name, _)| name == col)
+                .ok_or_else(|| ExecErr::ColumnNotFound(col.to_string()))
+        };
+        for fk in foreign_keys {
+            let cols = fk
+                .columns
+                .iter()
+                .map(|c| position(&schema, c))
+                .collect::<Result<Vec<_>>>()?;
+            let ref_schema = self.schema(&fk.table)?;
+            let ref_cols = fk
+                .ref_columns
+                .iter()
+                .map(|c| position(&ref_schema, c))
+                .collect::<Result<Vec<_>>>()?;
+            let key = |row: &[DataValue], cols: &[usize]| -> Vec<DataValue> {
+                cols.iter().map(|&i| row[i].clone()).collect()
+            };
+            let mut parents: Vec<Vec<DataValue>> = self.with_table(&fk.table, |t| {
+                t.rows.iter().map(|r| key(r, &ref_cols)).collect()
+            })?;
+            if fk.table == table {
+                parents.extend(rows.iter().map(|r| key(r, &ref_cols)));
+            }
+            for row in rows {
+                let child = key(row, &cols);
+                // NULL이 섞인 키는 검사하지 않음
+                if child.contains(&DataValue::Null) || parents.contains(&child) {
+                    continue;
+                }
+                return Err(ExecErr::ForeignKeyViolation {
+                    table: table.to_string(),
+                    references: fk.table,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// `VALUES`의 식들을 행마다 계산
+    fn eval_rows(&mut self, values: Vec<Vec<Expr>>) -> Result<Vec<Vec<DataValue>>> {
+        values
+            .iter()
+            .map(|exprs| {
+                exprs
+                    .iter()
+                    .map(|expr| self.eval(expr, &Scope::default()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// `VALUES (...), ...`를 쿼리로 실행 (컬럼 이름은 column1, column2, ...)
+    fn run_values(&mut self, values: Vec<Vec<Expr>>) -> Result<ResultSet> {
+        let width = values.first().map_or(0, Vec::len);
+        if let Some(row) = values.iter().find(|row| row.len() != width) {
+            return Err(ExecErr::ColumnCountMismatch {
+                expected: width,
+                found: row.len(),
+            });
+        }
+        Ok(ResultSet {
+            columns: (1..=width).map(|i| format!("column{}", i)).collect(),
+            rows: self.eval_rows(values)?,
+        })
+    }
+
+    fn run_insert(
+        &mut self,
+        table: &str,
+        columns: Vec<Box<str>>,
+        values: Vec<Vec<DataValue>>,
+    ) -> Result<QueryResult> {
+        let schema = self.schema(table)?;
+        // 컬럼을 명시하지 않으면 모든 컬럼에 순서대로 넣음
+        let targets = if columns.is_empty() {
+            (0..schema.len()).collect()
+        } else {
+            columns
+                .iter()
+                .map(|col| {
+                    schema
+                        .iter()
+                        .position(|(name, _)| name.as_str() == col.as_ref())
+                        .ok_or_else(|| ExecErr::ColumnNotFound(col.to_string()))
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+        let mut rows = Vec::with_capacity(values.len());
+        // 값의 타입을 바꿔서 넣은 컬럼 (컬럼마다 알림 한 번)
+        let mut converted = vec![None; schema.len()];
+        for values in values {
+            if values.len() != targets.len() {
+                return Err(ExecErr::ColumnCountMismatch {
+                    expected: targets.len(),
+                    found: values.len(),
+                });
+            }
+            let mut row = vec![DataValue::Null; schema.len()];
+            for (&idx, value) in targets.iter().zip(values) {
+                let from = value.data_type();
+                row[idx] = coerce(value, schema[idx].1, self.strict)?;
+                if let Some(from) = from
+                    && row[idx].data_type() != Some(from)
+                {
+                    converted[idx].get_or_insert(from);
+                }
+            }
+            rows.push(row);
+        }
+        let result = self.append_rows(table, rows)?;
+        self.conversion_notices(&schema, &converted);
+        Ok(result)
+    }
+
+    /// 값의 타입을 바꿔서 넣은 컬럼마다 알림을 남김
+    fn conversion_notices(
+        &mut self,
+        schema: &[(String, DataType)],
+        converted: &[Option<DataType>],
+    ) {
+        for ((name, ty), from) in schema.iter().zip(converted) {
+            if let Some(from) = from {
+                self.conversion_notice(*from, *ty, &format!("for column '{}'", name));
+            }
+        }
+    }
+
+    /// 느슨한 모드에서 암시적으로 형 변환을 했다는 알림 (타입은 SQL에 쓰는 이름으로)
+    fn conversion_notice(&mut self, from: DataType, to: DataType, place: &str) {
+        self.notice(format!(
+            "implicit conversion from {} to {} {}",
+            from.sql_name(),
+            to.sql_name(),
+            place
+        ));
+    }
+
+    /// 같은 알림은 한 번만 남김
+    fn notice(&mut self, msg: String) {
+        if !self.notices.contains(&msg) {
+            self.notices.push(msg);
+        }
+    }
+
+    /// 참조 무결성을 확인한 뒤 테이블 끝에 행들을 추가
+    fn append_rows(&mut self, table: &str, rows: Vec<Vec<DataValue>>) -> Result<QueryResult> {
+        self.check_references(table, &rows)?;
+        let triggers = self.triggers(table, TriggerEvent::Insert)?;
+        if triggers.is_empty() {
+            self.extend_rows(table, rows)?;
+        } else {
+            let inserted = rows.iter().map(|row| (None, row.clone())).collect();
+            self.atomically(|exe| {
+                exe.extend_rows(table, rows)?;
+                exe.fire_triggers(table, &triggers, inserted)
+            })?;
+        }
+        Ok(QueryResult::Success)
+    }
+
+    fn extend_rows(&mut self, table: &str, rows: Vec<Vec<DataValue>>) -> Result<()> {
+        if let Some(target) = self.temp.get_mut(table) {
+            target.rows.extend(rows);
+            return Ok(());
+        }
+        let mut shared = self.db.write();
+        let target = match &mut self.tx {
+            Some(tx) => &mut tx.copy(table, &shared)?.table,
+            None => shared
+                .get_mut(table)
+                .ok_or_else(|| ExecErr::TableNotFound(table.to_string()))?,
+        };
+        target.rows.extend(rows);
+        target.version += 1;
+        Ok(())
+    }
+
+    /// WHERE를 만족하는 행의 컬럼 값을 바꿈
+    ///
+    /// FROM이 있으면 WHERE를 만족하는 FROM의 행과 짝지어 값을 계산합니다.
+    /// 짝이 없는 행은 그대로 두고, 짝이 둘 이상이면 어느 값을 쓸지 알 수 없으므로 오류입니다.
+    fn run_update(
+        &mut self,
+        table: &str,
+        assigns: Vec<(Box<str>, Expr)>,
+        from: Option<(Source, Option<Box<str>>)>,
+        where_clause: Option<Expr>,
+    ) -> Result<QueryResult> {
+        let triggers = self.triggers(table, TriggerEvent::Update)?;
+        let update = |exe: &mut Self| {
+            exe.update_rows(
+                table,
+                &assigns,
+                from.as_ref(),
+                where_clause.as_ref(),
+                &triggers,
+            )
+        };
+        if triggers.is_empty() {
+            update(self)
+        } else {
+            self.atomically(update)
+        }
+    }
+
+    fn update_rows(
+        &mut self,
+        table: &str,
+        assigns: &[(Box<str>, Expr)],
+        from: Option<&(Source, Option<Box<str>>)>,
+        where_clause: Option<&Expr>,
+        triggers: &[Trigger],
+    ) -> Result<QueryResult> {
+        loop {
+            let (schema, rows) = self.with_table(table, |t| (t.columns.clone(), t.rows.clone()))?;
+            let targets = assigns
+                .iter()
+                .map(|(col, _)| {
+                    schema
+                        .iter()
+                        .position(|(name, _)| name.as_str() == col.as_ref())
+                        .ok_or_else(|| ExecErr::ColumnNotFound(col.to_string()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            self.check_referenced(table, &targets, &schema)?;
+
+            let mut names: Vec<String> = schema
+                .iter()
+                .map(|(name, _)| format!("{}.{}", table, name))
+                .collect();
+            let others = match from {
+                Some((source, alias)) => {
+                    let (from_names, from_rows) = self.scan(source, alias.as_deref(), None)?;
+                    names.extend(from_names);
+                    from_rows
+                }
+                None => vec![Vec::new()],
+            };
+            let mut updated = Vec::new();
+            let mut converted = vec![None; schema.len()];
+            for (idx, row) in rows.iter().enumerate() {
+                let mut matched = None;
+                for other in &others {
+                    let joined = [row.as_slice(), other].concat();
+                    let keep = match where_clause {
+                        Some(cond) => {
+                            eval::is_truthy(&self.eval(cond, &Scope::new(&names, &joined))?)?
+                        }
+                        None => true,
+                    };
+                    if keep && matched.replace(joined).is_some() {
+                        return Err(ExecErr::InvalidArgument(format!(
+                            "more than one FROM row matches row {} of '{}'",
+                            idx + 1,
+                            table
+                        )));
+                    }
+                }
+                let Some(joined) = matched else {
+                    continue;
+                };
+                let mut new_row = row.clone();
+                let scope = Scope::new(&names, &joined);
+                for (&col, (_, expr)) in targets.iter().zip(assigns) {
+                    let value = self.eval(expr, &scope)?;
+                    let from = value.data_type();
+                    new_row[col] = coerce(value, schema[col].1, self.strict)?;
+                    if let Some(from) = from
+                        && new_row[col].data_type() != Some(from)
+                    {
+                        converted[col].get_or_insert(from);
+                    }
+                }
+                updated.push((idx, new_row));
+            }
+            let new_rows: Vec<_> = updated.iter().map(|(_, row)| row.clone()).collect();
+            self.check_references(table, &new_rows)?;
+
+            let mut shared = self.db.write();
+            let target = match (self.temp.get_mut(table), &mut self.tx) {
+                (Some(target), _) => target,
+                (None, Some(tx)) => {
+                    let copy = tx.copy(table, &shared)?;
+                    copy.rewritten |= !updated.is_empty();
+                    &mut copy.table
+                }
+                (None, None) => shared
+                    .get_mut(table)
+                    .ok_or_else(|| ExecErr::TableNotFound(table.to_string()))?,
+            };
+            // 행을 읽은 뒤 다른 세션이 바꿨으면 덮어쓰지 않고 바뀐 값으로 다시 계산
+            if target.columns != schema || target.rows.get(..rows.len()) != Some(&rows[..]) {
+                continue;
+            }
+            let mut changes = Vec::new();
+            for (idx, row) in updated {
+                if !triggers.is_empty() {
+                    changes.push((Some(rows[idx].clone()), row.clone()));
+                }
+                target.rows[idx] = row;
+            }
+            target.version += 1;
+            drop(shared);
+            self.conversion_notices(&schema, &converted);
+            self.fire_triggers(table, triggers, changes)?;
+            return Ok(QueryResult::Success);
+        }
+    }
+
+    /// 다른 테이블의 외래 키가 참조하는 컬럼이면 바꿀 수 없음 (자식 행이 가리키는 값이 사라지므로)
+    fn check_referenced(
+        &self,
+        table: &str,
+        targets: &[usize],
+        schema: &[(String, DataType)],
+    ) -> Result<()> {
+        let shared = self.db.read();
+        let referencing = self
+            .temp
+            .iter()
+            .chain(shared.iter())
+            .flat_map(|(name, t)| t.foreign_keys.iter().map(move |fk| (name, fk)))
+            .find(|(_, fk)| {
+                fk.table == table
+                    && targets
+                        .iter()
+                        .any(|&col| fk.ref_columns.contains(&schema[col].0))
+            });
+        match referencing {
+            Some((name, _)) => Err(ExecErr::Unsupported(format!(
+                "UPDATE of a column of '{}' referenced by a foreign key of '{}'",
+                table, name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// 파일에서 테이블로 행을 읽어 오거나(FROM), 테이블의 모든 행을 파일로 씀(TO)
+    fn run_copy(
+        &mut self,
+        table: &str,
+        path: &str,
+        export: bool,
+        format: &str,
+    ) -> Result<QueryResult> {
+        let io_err = |e: std::io::Error| ExecErr::Io(format!("'{}': {}", path, e));
+        match (format, export) {
+            ("ndjson" | "jsonl", true) => {
+                let text =
+                    self.with_table(table, |t| copy::write_ndjson(&t.column_names(), &t.rows))?;
+                std::fs::write(path, text).map_err(io_err)?;
+                Ok(QueryResult::Success)
+            }
+            ("ndjson" | "jsonl", false) => {
+                let schema = self.schema(table)?;
+                let text = std::fs::read_to_string(path).map_err(io_err)?;
+                let rows = copy::read_ndjson(&text, &schema, self.strict)?;
+                self.append_rows(table, rows)
+            }
+            // 엑셀 파일은 내보내기만 지원
+            ("xlsx", true) => {
+                let bytes = self.with_table(table, |t| {
+                    xlsx::write_xlsx(table, &t.column_names(), &t.rows)
+                })?;
+                std::fs::write(path, bytes).map_err(io_err)?;
+                Ok(QueryResult::Success)
+            }
+            (format, export) => Err(ExecErr::Unsupported(format!(
+                "COPY {} with format '{}'",
+                if export { "TO" } else { "FROM" },
+                format
+            ))),
+        }
+    }
+
+    fn run_query(&mut self, stmt: Stmt) -> Result<ResultSet> {
+        match stmt {
+            Stmt::With { ctes, body } => {
+                let depth = self.ctes.len();
+                let result = self.run_with(ctes, *body);
+                self.ctes.truncate(depth);
+                result
+            }
+            Stmt::Compound {
+                op,
+                all,
+                left,
+                right,
+                order_by,
+                limit,
+                offset,
+            } => self.span(op.name(), |exe| {
+                let left = exe.run_query(*left)?;
+                let right = exe.run_query(*right)?;
+                if left.columns.len() != right.columns.len() {
+                    return Err(ExecErr::ColumnCountMismatch {
+                        expected: left.columns.len(),
+                        found: right.columns.len(),
+                    });
+                }
+                let mut rows = set_op::combine(op, all, left.rows, right.rows);
+                if let Some(order_by) = order_by {
+                    exe.span("Sort", |exe| {
+                        exe.sort_result(&left.columns, &mut rows, order_by)
+                    })?;
+                }
+                let rows = rows
+                    .into_iter()
+                    .skip(offset.unwrap_or(0) as usize)
+                    .take(limit.map_or(usize::MAX, |n| n as usize))
+                    .collect();
+                Ok(ResultSet {
+                    columns: left.columns,
+                    rows,
+                })
+            }),
+            Stmt::Values(values) => self.span("Values", |exe| exe.run_values(values)),
+            stmt => self.span("Select", |exe| match exe.count_rows(&stmt)? {
+                Some(set) => Ok(set),
+                None => exe.run_select(stmt),
+            }),
+        }
+    }
+
+    /// WITH 절의 쿼리를 순서대로 한 번씩 실행해 둔 뒤 본문을 실행
+    fn run_with(&mut self, ctes: Vec<(Box<str>, Stmt)>, body: Stmt) -> Result<ResultSet> {
+        for (name, stmt) in ctes {
+            let set = self.span(&format!("With {}", name), |exe| exe.run_query(stmt))?;
+            self.ctes.push((name.to_string(), set));
+        }
+        self.run_query(body)
+    }
+
+    /// `SELECT COUNT(*) FROM <table>`은 행을 읽지 않고 테이블의 행 수로 바로 답함
+    fn count_rows(&self, stmt: &Stmt) -> Result<Option<ResultSet>> {
+        let Stmt::Select {
+            table: Source::Table(table),
+            sample: None,
+            joins,
+            columns,
+            where_clause: None,
+            group_by: None,
+            having: None,
+            order_by: None,
+            limit,
+            offset,
+            ..
+        } = stmt
+        else {
+            return Ok(None);
+        };
+        let [call @ Expr::FunctionCall { name, args, .. }] = columns.as_slice() else {
+            return Ok(None);
+        };
+        if !joins.is_empty()
+            || !name.eq_ignore_ascii_case("COUNT")
+            || !args.is_empty()
+            || self.ctes.iter().any(|(name, _)| name == table.as_ref())
+        {
+            return Ok(None);
+        }
+        let count = self.with_table(table, |t| t.rows.len())?;
+        let rows = std::iter::once(vec![DataValue::Int(count as i64)])
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.map_or(usize::MAX, |n| n as usize))
+            .collect();
+        Ok(Some(ResultSet {
+            columns: vec![column_name(call)],
+            rows,
+        }))
+    }
+
+    /// 복합 쿼리의 결과를 정렬 (결과 컬럼의 위치나 이름으로만 정렬할 수 있음)
+    fn sort_result(
+        &mut self,
+        columns: &[String],
+        rows: &mut Vec<Vec<DataValue>>,
+        order_by: Vec<(Expr, Order)>,
+    ) -> Result<()> {
+        let orders: Vec<Order> = order_by.iter().map(|(_, order)| *order).collect();
+        let mut keyed = Vec::with_capacity(rows.len());
+        for row in rows.drain(..) {
+            let keys = order_by
+                .iter()
+                .map(|(expr, _)| match expr {
+                    Expr::Int(n) if *n >= 1 && *n as usize <= row.len() => {
+                        Ok(row[*n as usize - 1].clone())
+                    }
+                    Expr::Int(n) => Err(ExecErr::InvalidArgument(format!(
+                        "ORDER BY position {} is out of range",
+                        n
+                    ))),
+                    expr => self.eval(expr, &Scope::new(columns, &row)),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            keyed.push((keys, row));
+        }
+        eval::sort_rows(&mut keyed, |(a, _), (b, _)| {
+            eval::compare_keys(a, b, &orders)
+        })?;
+        rows.extend(keyed.into_iter().map(|(_, row)| row));
+        Ok(())
+    }
+
+    fn run_select(&mut self, stmt: Stmt) -> Result<ResultSet> {
+        let Stmt::Select {
+            table,
+            alias,
+            sample,
+            joins,
+            columns,
+            distinct,
+            where_clause,
+            group_by,
+            order_by,
+            limit,
+            offset,
+            ..
+        } = stmt
+        else {
+            return Err(ExecErr::Unsupported(format!("{:?}", stmt)));
+        };
+        let (mut names, mut rows) = self.span(&scan_name(&table), |exe| {
+            exe.scan(&table, alias.as_deref(), sample)
+        })?;
+        for join in joins {
+            let (right_names, right_rows) = self.span(&scan_name(&join.table), |exe| {
+                exe.scan(&join.table, join.alias.as_deref(), None)
+            })?;
+            rows = self.span("Join", |exe| {
+                exe.join(&mut names, rows, right_names, right_rows, &join)
+            })?;
+        }
+        // 정렬이나 집계가 없으면 LIMIT 만큼 찾은 뒤 더 읽지 않아도 됨
+        let stop_after = (order_by.is_none()
+            && group_by.is_none()
+            && distinct.is_none()
+            && !columns.iter().any(aggregate::contains_aggregate)
+            && !columns.iter().any(window::contains_window))
+        .then(|| limit.map(|n| (n + offset.unwrap_or(0)) as usize))
+        .flatten();
+        let mut rows = match where_clause {
+            Some(cond) => self.span("Filter", |exe| exe.filter(&names, rows, &cond, stop_after))?,
+            None => rows,
+        };
+        // '*'는 모든 컬럼을 선택
+        let columns = if columns.is_empty() {
+            names
+                .iter()
+                .map(|name| Expr::Ident(name.as_str().into()))
+                .collect()
+        } else {
+            columns
+        };
+        // ORDER BY 식은 숨은 컬럼으로 함께 계산한 뒤 정렬이 끝나면 잘라냄
+        let visible = columns.len();
+        let mut all_columns = columns.clone();
+        let mut sort_keys = Vec::new();
+        for (expr, order) in order_by.unwrap_or_default() {
+            let idx = match expr {
+                // ORDER BY 2 는 두 번째 결과 컬럼을 뜻함
+                Expr::Int(n) if n >= 1 && n as usize <= visible => n as usize - 1,
+                Expr::Int(n) => {
+                    return Err(ExecErr::InvalidArgument(format!(
+                        "ORDER BY position {} is out of range",
+                        n
+                    )));
+                }
+                expr => {
+                    all_columns.push(expr);
+                    all_columns.len() - 1
+                }
+            };
+            sort_keys.push((idx, order));
+        }
+        // DISTINCT ON 키도 숨은 컬럼으로 계산
+        let distinct_rows = distinct.as_ref().is_some_and(Vec::is_empty);
+        let distinct_on: Option<Vec<usize>> = match distinct {
+            Some(keys) if !keys.is_empty() => Some(
+                keys.into_iter()
+                    .map(|expr| {
+                        all_columns.push(expr);
+                        all_columns.len() - 1
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+        if all_columns.iter().any(window::contains_window) {
+            if group_by.is_some() || all_columns.iter().any(aggregate::contains_aggregate) {
+                return Err(ExecErr::Unsupported(
+                    "window functions cannot be combined with GROUP BY or aggregates".into(),
+                ));
+            }
+            all_columns = self.span("Window", |exe| {
+                exe.eval_windows(&mut names, &mut rows, &all_columns)
+            })?;
+        }
+        let projected = if let Some(sets) = group_by {
+            self.span("Group", |exe| {
+                exe.eval_grouping(&names, &rows, &all_columns, &sets)
+            })?
+        } else if all_columns.iter().any(aggregate::contains_aggregate) {
+            vec![self.span("Aggregate", |exe| {
+                exe.eval_aggregates(&names, &rows, &all_columns)
+            })?]
+        } else {
+            self.span("Project", |exe| exe.project(&names, &rows, &all_columns))?
+        };
+        let mut rows = if distinct_rows {
+            let mut seen = HashSet::with_capacity(projected.len());
+            projected
+                .into_iter()
+                .filter(|row| seen.insert(row[..visible].to_vec()))
+                .collect()
+        } else {
+            projected
+        };
+        if !sort_keys.is_empty() {
+            self.span("Sort", |_| {
+                eval::sort_rows(&mut rows, |a, b| {
+                    for &(idx, order) in &sort_keys {
+                        let ord = eval::sort_compare(&a[idx], &b[idx], order)?;
+                        if ord.is_ne() {
+                            return Ok(ord);
+                        }
+                    }
+                    Ok(std::cmp::Ordering::Equal)
+                })
+            })?;
+        }
+        // 정렬한 뒤 키마다 첫 번째 행만 남김
+        if let Some(keys) = distinct_on {
+            let mut seen = HashSet::with_hasher(self.rng.hash_state());
+            rows.retain(|row| {
+                seen.insert(keys.iter().map(|&i| row[i].clone()).collect::<Vec<_>>())
+            });
+        }
+        let rows = rows
+            .into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.map_or(usize::MAX, |n| n as usize))
+            .map(|mut row| {
+                row.truncate(visible);
+                row
+            })
+            .collect();
+        Ok(ResultSet {
+            columns: columns.iter().map(column_name).collect(),
+            rows,
+        })
+    }
+
+    /// 중첩 루프 조인: 모든 행 쌍 중 ON 조건을 만족하는 것을 남기고,
+    /// 외부 조인이면 짝이 없는 행을 NULL로 채워 추가함
+    fn join(
+        &mut self,
+        names: &mut Vec<String>,
+        left_rows: Vec<Vec<DataValue>>,
+        right_names: Vec<String>,
+        right_rows: Vec<Vec<DataValue>>,
+        join: &Join,
+    ) -> Result<Vec<Vec<DataValue>>> {
+        let (left_width, right_width) = (names.len(), right_names.len());
+        names.extend(right_names);
+        let keep_left = matches!(join.kind, JoinKind::Left | JoinKind::Full);
+        let keep_right = matches!(join.kind, JoinKind::Right | JoinKind::Full);
+        let mut right_matched = vec![false; right_rows.len()];
+        let mut out = Vec::new();
+        for left in &left_rows {
+            let mut matched = false;
+            for (right, right_matched) in right_rows.iter().zip(&mut right_matched) {
+                let row = [left.as_slice(), right.as_slice()].concat();
+                let value = self.eval(&join.on, &Scope::new(names, &row))?;
+                if eval::is_truthy(&value)? {
+                    matched = true;
+                    *right_matched = true;
+                    out.push(row);
+                }
+            }
+            if keep_left && !matched {
+                let mut row = left.clone();
+                row.resize(left_width + right_width, DataValue::Null);
+                out.push(row);
+            }
+        }
+        if keep_right {
+            for (right, _) in right_rows.iter().zip(&right_matched).filter(|(_, m)| !**m) {
+                let mut row = vec![DataValue::Null; left_width];
+                row.extend_from_slice(right);
+                out.push(row);
+            }
+        }
+        Ok(out)
+    }
+
+    fn filter(
+        &mut self,
+        names: &[String],
+        rows: Vec<Vec<DataValue>>,
+        cond: &Expr,
+        stop_after: Option<usize>,
+    ) -> Result<Vec<Vec<DataValue>>> {
+        let mut out = Vec::with_capacity(rows.len());
+        for row in rows {
+            if stop_after.is_some_and(|n| out.len() >= n) {
+                break;
+            }
+            let value = self.eval(cond, &Scope::new(names, &row))?;
+            if eval::is_truthy(&value)? {
+                out.push(row);
+            }
+        }
+        Ok(out)
+    }
+
+    fn project(
+        &mut self,
+        names: &[String],
+        rows: &[Vec<DataValue>],
+        columns: &[Expr],
+    ) -> Result<Vec<Vec<DataValue>>> {
+        let constants = columns
+            .iter()
+            .map(|expr| {
+                eval::is_constant(expr)
+                    .then(|| self.eval(expr, &Scope::default()))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut out = Vec::with_capacity(rows.len());
+        for row in rows {
+            let scope = Scope::new(names, row);
+            let projected = columns
+                .iter()
+                .zip(&constants)
+                .map(|(expr, constant)| match constant {
+                    Some(value) => Ok(value.clone()),
+                    None => self.eval(expr, &scope),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            out.push(projected);
+        }
+        Ok(out)
+    }
+
+    /// 테이블이나 서브쿼리의 행을 읽음 (TABLESAMPLE이 있으면 읽는 도중에 표본을 뽑음)
+    ///
+    /// 컬럼 이름은 `<별칭 또는 테이블>.<컬럼>` 형태로 돌려줌
+    fn scan(
+        &mut self,
+        source: &Source,
+        alias: Option<&str>,
+        sample: Option<(Box<str>, Expr)>,
+    ) -> Result<(Vec<String>, Vec<Vec<DataValue>>)> {
+        let sample = match sample {
+            Some((method, percent)) => match self.eval(&percent, &Scope::default())? {
+                DataValue::Int(n) if (0..=100).contains(&n) => Some((method, n as f64)),
+                DataValue::Float(f) if (0.0..=100.0).contains(&f) => Some((method, f)),
+                other => {
+                    return Err(ExecErr::InvalidArgument(format!(
+                        "TABLESAMPLE percent must be between 0 and 100, but got {}",
+                        other
+                    )));
+                }
+            },
+            None => None,
+        };
+        let qualify = |qualifier: &str, names: Vec<String>| {
+            names
+                .into_iter()
+                .map(|name| format!("{}.{}", qualifier, name))
+                .collect()
+        };
+        match source {
+            // 같은 이름이면 WITH 절의 결과가 테이블보다 우선
+            Source::Table(table)
+                if let Some((_, set)) = self
+                    .ctes
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| name == table.as_ref()) =>
+            {
+                let names = qualify(alias.unwrap_or(table), set.columns.clone());
+                Ok((names, sample_rows(&mut self.rng, &set.rows, &sample)))
+            }
+            Source::Table(table) => {
+                let shared = self.db.read();
+                let source = self
+                    .temp
+                    .get(table.as_ref())
+                    .or_else(|| Some(&self.tx.as_ref()?.tables.get(table.as_ref())?.table))
+                    .or_else(|| shared.get(table.as_ref()))
+                    .ok_or_else(|| ExecErr::TableNotFound(table.to_string()))?;
+                let names = qualify(alias.unwrap_or(table), source.column_names());
+                Ok((names, sample_rows(&mut self.rng, &source.rows, &sample)))
+            }
+            // 서브쿼리는 먼저 끝까지 실행해서 결과를 임시 테이블처럼 사용
+            Source::Subquery(stmt) => {
+                let set = self.run_query(stmt.as_ref().clone())?;
+                let names = match alias {
+                    Some(alias) => qualify(alias, set.columns),
+                    None => set.columns,
+                };
+                Ok((names, sample_rows(&mut self.rng, &set.rows, &sample)))
+            }
+        }
+    }
+
+    /// `PRAGMA <name> [= <value>]` - 값이 없으면 현재 값을 돌려줌
+    ///
+    /// - `seed`: 난수 시드
+    /// - `autocommit`: 끄면 트랜잭션을 자동으로 시작
+    /// - `strict`: 느슨한 모드(기본값)에서는 숫자 모양의 TEXT를 숫자 컬럼에 넣거나
+    ///   숫자와 비교할 때 숫자로 바꾸고, INT 연산이 넘치면 FLOAT로 계산한 뒤 알림(NOTICE)을 남김.
+    ///   켜면 이런 변환과 넘침, FLOAT로 정확히 나타낼 수 없는 INT를 FLOAT 컬럼에 넣는 것이 오류
+    fn run_pragma(&mut self, name: &str, value: Option<Expr>) -> Result<QueryResult> {
+        let value = value
+            .map(|expr| self.eval(&expr, &Scope::default()))
+            .transpose()?;
+        match (name.to_lowercase().as_str(), value) {
+            ("seed", None) => Ok(QueryResult::Rows {
+                columns: vec!["seed".into()],
+                rows: vec![vec![self.rng.seed().to_string()]],
+            }),
+            ("seed", Some(DataValue::Int(seed))) if seed >= 0 => {
+                self.rng.reseed(seed as u64);
+                Ok(QueryResult::Success)
+            }
+            ("seed", Some(other)) => Err(ExecErr::TypeMismatch {
+                expected: "non-negative Int".into(),
+                found: other.type_name().into(),
+            }),
+            ("autocommit", None) => Ok(QueryResult::Rows {
+                columns: vec!["autocommit".into()],
+                rows: vec![vec![DataValue::Bool(self.autocommit).to_string()]],
+            }),
+            // 다시 켜면 진행 중인 트랜잭션을 COMMIT
+            ("autocommit", Some(DataValue::Bool(on))) => {
+                self.autocommit = on;
+                match self.tx {
+                    Some(_) if on => {
+                        self.notices
+                            .push("committed the transaction in progress".into());
+                        self.run_commit()
+                    }
+                    _ => Ok(QueryResult::Success),
+                }
+            }
+            ("strict", None) => Ok(QueryResult::Rows {
+                columns: vec!["strict".into()],
+                rows: vec![vec![DataValue::Bool(self.strict).to_string()]],
+            }),
+            ("strict", Some(DataValue::Bool(on))) => {
+                self.strict = on;
+                Ok(QueryResult::Success)
+            }
+            ("autocommit" | "strict", Some(other)) => Err(ExecErr::TypeMismatch {
+                expected: "Bool".into(),
+                found: other.type_name().into(),
+            }),
+            (name, _) => Err(ExecErr::InvalidArgument(format!(
+                "unknown pragma '{}'",
+                name
+            ))),
+        }
+    }
+}
+
+fn sample_rows(
+    rng: &mut Rng,
+    rows: &[Vec<DataValue>],
+    sample: &Option<(Box<str>, f64)>,
+) -> Vec<Vec<DataValue>> {
+    match sample {
+        None => rows.to_vec(),
+        // 행마다 독립적으로 포함 여부를 결정
+        Some((method, percent)) if method.as_ref() == "BERNOULLI" => rows
+            .iter()
+            .filter(|_| rng.next_f64() * 100.0 < *percent)
+            .cloned()
+            .collect(),
+        // 블록 단위로 포함 여부를 결정하고, 빠진 블록은 아예 읽지 않음
+        Some((_, percent)) => rows
+            .chunks(SAMPLE_BLOCK_ROWS)
+            .filter(|_| rng.next_f64() * 100.0 < *percent)
+            .flatten()
+            .cloned()
+            .collect(),
+    }
+}
+
+/// 컬럼 타입에 맞게 값을 변환
+///
+/// INT -> FLOAT는 항상 허용하고, 느슨한 모드(기본값)에서는 숫자 모양의 TEXT도 INT, FLOAT로 바꿉니다.
+/// 엄격한 모드에서는 FLOAT로 정확히 나타낼 수 없는 큰 INT도 오류입니다.
+fn coerce(value: DataValue, ty: DataType, strict: bool) -> Result<DataValue> {
+    match (value, ty) {
+        (DataValue::Int(n), DataType::Float) if strict && n as f64 as i128 != n as i128 => {
+            Err(ExecErr::InvalidCast {
+                value: n.to_string(),
+                ty: ty.as_str().into(),
+            })
+        }
+        (DataValue::Int(n), DataType::Float) => Ok(DataValue::Float(n as f64)),
+        (value @ DataValue::String(_), DataType::Int | DataType::Float) if !strict => {
+            let found = value.type_name().into();
+            eval::cast(value, ty).map_err(|_| ExecErr::TypeMismatch {
+                expected: ty.as_str().into(),
+                found,
+            })
+        }
+        (value, ty) if value.verify(ty) => Ok(value),
+        (value, ty) => Err(ExecErr::TypeMismatch {
+            expected: ty.as_str().into(),
+            found: value.type_name().into(),
+        }),
+    }
+}
+
+fn scan_name(source: &Source) -> String {
+    match source {
+        Source::Table(name) => format!("Scan {}", name),
+        Source::Subquery(_) => "Subquery".into(),
+    }
+}
+
+fn column_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Ident(name) => name.rsplit('.').next().unwrap_or_default().to_string(),
+        Expr::FunctionCall { name, .. } => name.to_lowercase(),
+        Expr::Window { func, .. } => column_name(func),
+        _ => "?column?".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(result: QueryResult) -> Vec<Vec<String>> {
+        match result {
+            QueryResult::Rows { rows, .. } => rows,
+            QueryResult::Success => panic!("Expected rows, got success"),
+            QueryResult::Error(e) => panic!("Expected rows, got error: {}", e),
+        }
+    }
+
+    fn setup() -> Executor {
+        let mut exe = Executor::new();
+        let result = exe.run(
+            "CREATE TABLE friends(name TEXT, male BOOL, age INT);
+             INSERT INTO friends VALUES('Alpha', TRUE, 18), ('Beta', FALSE, 20);
+             INSERT INTO friends (name, age) VALUES('Delta', 31);"
+                .into(),
+        );
+        assert!(matches!(result, QueryResult::Success));
+        exe
+    }
+
+    #[test]
+    fn test_create_insert_select() {
+        let mut exe = setup();
+        let result = rows(exe.run("SELECT name, male FROM friends;".into()));
+        assert_eq!(
+            result,
+            vec![
+                vec!["Alpha".to_string(), "yes".to_string()],
+                vec!["Beta".to_string(), "no".to_string()],
+                vec!["Delta".to_string(), "null".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_type_mismatch() {
+        let mut exe = setup();
+        let result = exe.run("INSERT INTO friends VALUES(1, TRUE, 18);".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_random_is_reproducible_with_seed() {
+        let mut exe = setup();
+        let query =
+            "PRAGMA seed = 42; SELECT RANDOM(), RANDOM() % 10, RANDOM_TEXT(8) FROM friends;";
+        let first = rows(exe.run(query.into()));
+        let second = rows(exe.run(query.into()));
+        assert_eq!(first, second);
+        // 휘발성 함수는 행마다 새로 계산됨
+        assert_ne!(first[0][0], first[1][0]);
+        assert_eq!(first[0][2].len(), 8);
+
+        for query in [
+            "SELECT RANDOMBLOB(100000000000) FROM friends;",
+            "SELECT RANDOM_TEXT(-1) FROM friends;",
+        ] {
+            match exe.run(query.into()) {
+                QueryResult::Error(e) => assert!(e.contains("length between"), "{}", e),
+                _ => panic!("Expected error for {}", query),
+            }
+        }
+    }
+
+    #[test]
+    fn test_table_sample() {
+        let mut exe = Executor::new();
+        exe.run("CREATE TABLE big(n INT);".into());
+        let values = (0..1000)
+            .map(|n| format!("({})", n))
+            .collect::<Vec<_>>()
+            .join(", ");
+        exe.run(format!("INSERT INTO big VALUES {};", values));
+
+        let all = rows(exe.run("SELECT * FROM big TABLESAMPLE BERNOULLI (100);".into()));
+        assert_eq!(all.len(), 1000);
+        let none = rows(exe.run("SELECT * FROM big TABLESAMPLE SYSTEM (0);".into()));
+        assert!(none.is_empty());
+
+        let query = "PRAGMA seed = 7; SELECT * FROM big TABLESAMPLE BERNOULLI (10);";
+        let first = rows(exe.run(query.into()));
+        assert_eq!(first, rows(exe.run(query.into())));
+        assert!(first.len() > 50 && first.len() < 150);
+
+        let result = exe.run("SELECT * FROM big TABLESAMPLE BERNOULLI (150);".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_aggregates() {
+        let mut exe = setup();
+        let result = rows(exe.run(
+            "SELECT COUNT(*), COUNT(male), SUM(age), AVG(age), MIN(name), MAX(age) FROM friends;"
+                .into(),
+        ));
+        assert_eq!(result, vec![vec!["3", "2", "69", "23", "Alpha", "31"]]);
+
+        let result = exe.run("SELECT name FROM friends WHERE COUNT(*) > 1;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_approx_aggregates() {
+        let mut exe = Executor::new();
+        exe.run("CREATE TABLE big(n INT);".into());
+        let values = (0..6000)
+            .map(|n| format!("({})", n % 2000 + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        exe.run(format!("INSERT INTO big VALUES {};", values));
+
+        let result = rows(
+            exe.run(
+                "PRAGMA seed = 1; SELECT APPROX_COUNT_DISTINCT(n), APPROX_PERCENTILE(n, 0.5) FROM big;"
+                    .into(),
+            ),
+        );
+        let distinct: f64 = result[0][0].parse().unwrap();
+        assert!((distinct - 2000.0).abs() < 2000.0 * 0.05);
+        let median: f64 = result[0][1].parse().unwrap();
+        assert!((median - 1000.0).abs() < 2000.0 * 0.1);
+
+        // 해시 키도 시드를 따르므로 같은 시드면 같은 추정값, 시드가 바뀌면 추정값도 바뀜
+        let estimate = |exe: &mut Executor, seed: u64| {
+            rows(exe.run(format!(
+                "PRAGMA seed = {}; SELECT APPROX_COUNT_DISTINCT(n) FROM big;",
+                seed
+            )))
+        };
+        assert_eq!(estimate(&mut exe, 1)[0][0], result[0][0]);
+        assert!((2..6).any(|seed| estimate(&mut exe, seed)[0][0] != result[0][0]));
+
+        // 표본보다 적은 값은 정확하게 계산됨
+        exe.run("CREATE TABLE small(n INT); INSERT INTO small VALUES (1), (2), (3), (4);".into());
+        let result =
+            rows(exe.run(
+                "SELECT APPROX_COUNT_DISTINCT(n), APPROX_PERCENTILE(n, 0.5) FROM small;".into(),
+            ));
+        assert_eq!(result, vec![vec!["4", "2"]]);
+    }
+
+    #[test]
+    fn test_group_by_rollup() {
+        let mut exe = Executor::new();
+        exe.run(
+            "CREATE TABLE sales(region TEXT, item TEXT, amount INT);
+             INSERT INTO sales VALUES ('east', 'pen', 10), ('east', 'ink', 5), ('west', 'pen', 7);"
+                .into(),
+        );
+        let result = rows(exe.run("SELECT region, SUM(amount) FROM sales GROUP BY region;".into()));
+        assert_eq!(result, vec![vec!["east", "15"], vec!["west", "7"]]);
+
+        let result = rows(
+            exe.run(
+                "SELECT region, item, SUM(amount), GROUPING(region, item) FROM sales
+             GROUP BY ROLLUP (region, item);"
+                    .into(),
+            ),
+        );
+        assert_eq!(
+            result,
+            vec![
+                vec!["east", "pen", "10", "0"],
+                vec!["east", "ink", "5", "0"],
+                vec!["west", "pen", "7", "0"],
+                vec!["east", "null", "15", "1"],
+                vec!["west", "null", "7", "1"],
+                vec!["null", "null", "22", "3"],
+            ]
+        );
+
+        let result = rows(
+            exe.run("SELECT item, COUNT(*) FROM sales GROUP BY GROUPING SETS ((item), ());".into()),
+        );
+        assert_eq!(
+            result,
+            vec![vec!["pen", "2"], vec!["ink", "1"], vec!["null", "3"]]
+        );
+
+        let result = rows(exe.run("SELECT region FROM sales GROUP BY CUBE (region, item);".into()));
+        assert_eq!(result.len(), 3 + 2 + 2 + 1);
+    }
+
+    #[test]
+    fn test_string_aggregates() {
+        let mut exe = setup();
+        let result = rows(exe.run(
+            "SELECT GROUP_CONCAT(name), STRING_AGG(name, ' / ' ORDER BY age DESC) FROM friends;"
+                .into(),
+        ));
+        assert_eq!(
+            result,
+            vec![vec!["Alpha,Beta,Delta", "Delta / Beta / Alpha"]]
+        );
+
+        let result = rows(exe.run(
+            "SELECT male, GROUP_CONCAT(name, ';' ORDER BY name DESC) FROM friends GROUP BY male;"
+                .into(),
+        ));
+        assert_eq!(
+            result,
+            vec![
+                vec!["yes", "Alpha"],
+                vec!["no", "Beta"],
+                vec!["null", "Delta"]
+            ]
+        );
+
+        let result = exe.run("SELECT RANDOM_TEXT(3 ORDER BY name) FROM friends;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_select_where() {
+        let mut exe = setup();
+        let result = rows(exe.run("SELECT name, age FROM friends WHERE male = FALSE;".into()));
+        assert_eq!(result, vec![vec!["Beta", "20"]]);
+
+        let result = rows(exe.run("SELECT name FROM friends WHERE age < 30 AND age > 18;".into()));
+        assert_eq!(result, vec![vec!["Beta"]]);
+
+        let result = rows(
+            exe.run("SELECT male, COUNT(*) FROM friends WHERE age >= 20 GROUP BY male;".into()),
+        );
+        assert_eq!(result, vec![vec!["no", "1"], vec!["null", "1"]]);
+
+        // 조건식은 Bool이어야 함
+        let result = exe.run("SELECT name FROM friends WHERE age;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_select_order_by() {
+        let mut exe = setup();
+        exe.run("INSERT INTO friends VALUES ('Gamma', FALSE, 20);".into());
+        let result = rows(exe.run("SELECT name FROM friends ORDER BY age DESC, name;".into()));
+        assert_eq!(
+            result,
+            vec![vec!["Delta"], vec!["Beta"], vec!["Gamma"], vec!["Alpha"]]
+        );
+
+        // NULL은 오름차순에서 마지막
+        let result = rows(exe.run("SELECT name, male FROM friends ORDER BY male, 1 DESC;".into()));
+        assert_eq!(
+            result,
+            vec![
+                vec!["Gamma", "no"],
+                vec!["Beta", "no"],
+                vec!["Alpha", "yes"],
+                vec!["Delta", "null"],
+            ]
+        );
+
+        let result = rows(
+            exe.run("SELECT age FROM friends GROUP BY age ORDER BY COUNT(*) DESC, age;".into()),
+        );
+        assert_eq!(result, vec![vec!["20"], vec!["18"], vec!["31"]]);
+
+        let result = exe.run("SELECT name FROM friends ORDER BY 3;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_select_limit_offset() {
+        let mut exe = setup();
+        let result = rows(exe.run("SELECT name FROM friends ORDER BY age LIMIT 2;".into()));
+        assert_eq!(result, vec![vec!["Alpha"], vec!["Beta"]]);
+
+        let result =
+            rows(exe.run("SELECT name FROM friends ORDER BY age LIMIT 2 OFFSET 2;".into()));
+        assert_eq!(result, vec![vec!["Delta"]]);
+
+        let result = rows(exe.run("SELECT name FROM friends LIMIT 0;".into()));
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_inner_join() {
+        let mut exe = setup();
+        exe.run(
+            "CREATE TABLE pets(owner TEXT, name TEXT);
+             INSERT INTO pets VALUES('Alpha', 'Rex'), ('Beta', 'Tom'), ('Alpha', 'Kit');"
+                .into(),
+        );
+        let result = rows(
+            exe.run(
+                "SELECT f.name, p.name FROM friends f JOIN pets AS p ON f.name = p.owner
+             ORDER BY p.name;"
+                    .into(),
+            ),
+        );
+        assert_eq!(
+            result,
+            vec![
+                vec!["Alpha", "Kit"],
+                vec!["Alpha", "Rex"],
+                vec!["Beta", "Tom"],
+            ]
+        );
+
+        let result = rows(
+            exe.run(
+                "SELECT age, owner FROM friends INNER JOIN pets ON friends.name = pets.owner
+             WHERE pets.name = 'Tom';"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["20", "Beta"]]);
+
+        let result = exe.run("SELECT name FROM friends JOIN pets ON owner = name;".into());
+        assert!(matches!(result, QueryResult::Error(e) if e.contains("ambiguous")));
+    }
+
+    #[test]
+    fn test_schema_qualified_names() {
+        let mut exe = setup();
+        exe.run(
+            "CREATE TABLE zoo.pets(owner TEXT, name TEXT);
+             INSERT INTO zoo.pets (owner, name) VALUES('Beta', 'Tom');"
+                .into(),
+        );
+        assert!(exe.database().table("zoo.pets").is_some());
+        assert!(matches!(
+            exe.run("SELECT * FROM pets;".into()),
+            QueryResult::Error(_)
+        ));
+        let result = rows(
+            exe.run(
+                "SELECT zoo.pets.name, pets.owner, age FROM friends JOIN zoo.pets
+                 ON friends.name = zoo.pets.owner;"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["Tom", "Beta", "20"]]);
+    }
+
+    #[test]
+    fn test_update() {
+        let mut exe = setup();
+        let result =
+            exe.run("UPDATE friends SET male = TRUE, age = age + 1 WHERE name = 'Delta';".into());
+        assert!(matches!(result, QueryResult::Success));
+        assert_eq!(
+            rows(exe.run("SELECT male, age FROM friends WHERE name = 'Delta';".into())),
+            vec![vec!["yes", "32"]]
+        );
+
+        exe.run(
+            "CREATE TABLE ages(who TEXT, years INT);
+             INSERT INTO ages VALUES('Alpha', 19), ('Beta', 21), ('Nobody', 99);"
+                .into(),
+        );
+        let result = exe
+            .run("UPDATE friends SET age = a.years FROM ages a WHERE friends.name = a.who;".into());
+        assert!(matches!(result, QueryResult::Success));
+        assert_eq!(
+            rows(exe.run("SELECT name, age FROM friends;".into())),
+            vec![vec!["Alpha", "19"], vec!["Beta", "21"], vec!["Delta", "32"]]
+        );
+
+        // 한 행에 FROM의 행이 여럿 짝지어지면 아무것도 바꾸지 않음
+        exe.run("INSERT INTO ages VALUES('Alpha', 50);".into());
+        let result = exe.run("UPDATE friends SET age = years FROM ages WHERE name = who;".into());
+        assert!(matches!(result, QueryResult::Error(e) if e.contains("more than one")));
+        assert_eq!(
+            rows(exe.run("SELECT age FROM friends WHERE name = 'Alpha';".into())),
+            vec![vec!["19"]]
+        );
+        assert!(matches!(
+            exe.run("UPDATE friends SET age = 'old';".into()),
+            QueryResult::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_values_query() {
+        let mut exe = setup();
+        let QueryResult::Rows { columns, .. } = exe.run("VALUES (1, 'a'), (2, 'b');".into()) else {
+            panic!("Expected rows");
+        };
+        assert_eq!(columns, vec!["column1", "column2"]);
+        assert_eq!(
+            rows(exe.run(
+                "SELECT column2 FROM (VALUES (1, 'a'), (2, 'b')) v ORDER BY column1 DESC;".into()
+            )),
+            vec![vec!["b"], vec!["a"]]
+        );
+        assert!(matches!(
+            exe.run("VALUES (1, 'a'), (2);".into()),
+            QueryResult::Error(_)
+        ));
+
+        let result = exe.run(
+            "INSERT INTO friends (name, age) SELECT name, age + 10 FROM friends WHERE male
+             UNION ALL VALUES ('Gamma', 40);"
+                .into(),
+        );
+        assert!(matches!(result, QueryResult::Success));
+        assert_eq!(
+            rows(exe.run("SELECT name, age FROM friends WHERE male IS NULL ORDER BY age;".into())),
+            vec![
+                vec!["Alpha", "28"],
+                vec!["Delta", "31"],
+                vec!["Gamma", "40"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distinct_on() {
+        let mut exe = setup();
+        exe.run("INSERT INTO friends VALUES('Gamma', TRUE, 25), ('Alpha', FALSE, 18);".into());
+        assert_eq!(
+            rows(exe.run("SELECT DISTINCT male FROM friends ORDER BY male;".into())).len(),
+            3
+        );
+        // 성별마다 가장 나이 많은 사람
+        assert_eq!(
+            rows(exe.run(
+                "SELECT DISTINCT ON (male) name, age FROM friends ORDER BY male, age DESC;".into()
+            )),
+            vec![vec!["Beta", "20"], vec!["Gamma", "25"], vec!["Delta", "31"]]
+        );
+        // 키가 결과 컬럼에 없어도 되고, ORDER BY가 없으면 처음 읽은 행이 남음
+        assert_eq!(
+            rows(exe.run("SELECT DISTINCT ON (age % 2) name FROM friends;".into())),
+            vec![vec!["Alpha"], vec!["Delta"]]
+        );
+    }
+
+    #[test]
+    fn test_float_ordering() {
+        let mut exe = Executor::new();
+        exe.run(
+            "CREATE TABLE m(x FLOAT);
+             INSERT INTO m VALUES(0.0 / 0.0), (1.5), (-0.0), (NULL), (0.0), (-2.0), (0.0 / 0.0);"
+                .into(),
+        );
+        // NaN은 숫자 중 가장 크고 (NULL보다는 앞), -0과 0은 같은 값
+        assert_eq!(
+            rows(exe.run("SELECT x FROM m ORDER BY x;".into())),
+            vec![
+                vec!["-2"],
+                vec!["-0"],
+                vec!["0"],
+                vec!["1.5"],
+                vec!["NaN"],
+                vec!["NaN"],
+                vec!["null"]
+            ]
+        );
+        assert_eq!(
+            rows(exe.run("SELECT x, COUNT(*) FROM m WHERE x > 1 OR x = 0 GROUP BY x;".into())),
+            vec![vec!["NaN", "2"], vec!["1.5", "1"], vec!["-0", "2"]]
+        );
+        assert_eq!(
+            rows(exe.run("SELECT DISTINCT x FROM m ORDER BY x DESC;".into())).len(),
+            5
+        );
+        assert_eq!(
+            rows(exe.run("SELECT x FROM m UNION SELECT x FROM m;".into())).len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_nulls_first_last() {
+        let mut exe = setup();
+        let query = |order: &str| format!("SELECT name FROM friends ORDER BY male {};", order);
+        let names = |result: Vec<Vec<String>>| result.concat().join(",");
+        // 기본값: NULL은 가장 큰 값처럼
+        assert_eq!(names(rows(exe.run(query("")))), "Beta,Alpha,Delta");
+        assert_eq!(names(rows(exe.run(query("DESC")))), "Delta,Alpha,Beta");
+        assert_eq!(
+            names(rows(exe.run(query("NULLS FIRST")))),
+            "Delta,Beta,Alpha"
+        );
+        assert_eq!(
+            names(rows(exe.run(query("DESC NULLS LAST")))),
+            "Alpha,Beta,Delta"
+        );
+        assert_eq!(
+            names(rows(exe.run(
+                "SELECT STRING_AGG(name, ',' ORDER BY male NULLS FIRST) FROM friends;".into()
+            ))),
+            "Delta,Beta,Alpha"
+        );
+        assert_eq!(
+            names(rows(exe.run(
+                "SELECT ROW_NUMBER() OVER (ORDER BY male DESC NULLS LAST) FROM friends;".into()
+            ))),
+            "1,2,3"
+        );
+    }
+
+    #[test]
+    fn test_outer_join() {
+        let mut exe = setup();
+        exe.run(
+            "CREATE TABLE pets(owner TEXT, name TEXT);
+             INSERT INTO pets VALUES('Alpha', 'Rex'), ('Omega', 'Tom');"
+                .into(),
+        );
+        let query = |kind: &str| {
+            format!(
+                "SELECT f.name, p.name FROM friends f {} JOIN pets p ON f.name = p.owner;",
+                kind
+            )
+        };
+        let result = rows(exe.run(query("LEFT")));
+        assert_eq!(
+            result,
+            vec![
+                vec!["Alpha", "Rex"],
+                vec!["Beta", "null"],
+                vec!["Delta", "null"],
+            ]
+        );
+
+        let result = rows(exe.run(query("RIGHT OUTER")));
+        assert_eq!(result, vec![vec!["Alpha", "Rex"], vec!["null", "Tom"]]);
+
+        let result = rows(exe.run(query("FULL")));
+        assert_eq!(
+            result,
+            vec![
+                vec!["Alpha", "Rex"],
+                vec!["Beta", "null"],
+                vec!["Delta", "null"],
+                vec!["null", "Tom"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cross_join() {
+        let mut exe = setup();
+        exe.run(
+            "CREATE TABLE sizes(size TEXT);
+             INSERT INTO sizes VALUES('S'), ('L');"
+                .into(),
+        );
+        let result = rows(exe.run(
+            "SELECT name, size FROM friends, sizes WHERE age < 30 ORDER BY name, size;".into(),
+        ));
+        assert_eq!(
+            result,
+            vec![
+                vec!["Alpha", "L"],
+                vec!["Alpha", "S"],
+                vec!["Beta", "L"],
+                vec!["Beta", "S"],
+            ]
+        );
+
+        let result = rows(exe.run("SELECT COUNT(*) FROM friends CROSS JOIN sizes;".into()));
+        assert_eq!(result, vec![vec!["6"]]);
+    }
+
+    #[test]
+    fn test_derived_table() {
+        let mut exe = setup();
+        let result = rows(
+            exe.run(
+                "SELECT sub.name FROM (SELECT name, age FROM friends WHERE age > 18) AS sub
+             WHERE age < 30;"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["Beta"]]);
+
+        let result = rows(
+            exe.run(
+                "SELECT f.name, c.count FROM friends f
+             JOIN (SELECT age, COUNT(*) FROM friends GROUP BY age) c ON f.age = c.age
+             ORDER BY f.name;"
+                    .into(),
+            ),
+        );
+        assert_eq!(
+            result,
+            vec![vec!["Alpha", "1"], vec!["Beta", "1"], vec!["Delta", "1"]]
+        );
+    }
+
+    #[test]
+    fn test_scalar_subquery() {
+        let mut exe = setup();
+        let result = rows(
+            exe.run("SELECT name FROM friends WHERE age > (SELECT AVG(age) FROM friends);".into()),
+        );
+        assert_eq!(result, vec![vec!["Delta"]]);
+
+        let result = rows(exe.run(
+            "SELECT name, age - (SELECT MIN(age) FROM friends) FROM friends ORDER BY 2;".into(),
+        ));
+        assert_eq!(
+            result,
+            vec![vec!["Alpha", "0"], vec!["Beta", "2"], vec!["Delta", "13"]]
+        );
+
+        let result = rows(exe.run(
+            "SELECT name, (SELECT age FROM friends WHERE age > 99) FROM friends LIMIT 1;".into(),
+        ));
+        assert_eq!(result, vec![vec!["Alpha", "null"]]);
+
+        let result =
+            exe.run("SELECT name FROM friends WHERE age = (SELECT age FROM friends);".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_exists() {
+        let mut exe = setup();
+        exe.run(
+            "CREATE TABLE pets(owner TEXT, name TEXT);
+             INSERT INTO pets VALUES('Alpha', 'Rex'), ('Alpha', 'Kit');"
+                .into(),
+        );
+        let result = rows(exe.run(
+            "SELECT name FROM friends f WHERE EXISTS (SELECT * FROM pets WHERE owner = f.name);"
+                .into(),
+        ));
+        assert_eq!(result, vec![vec!["Alpha"]]);
+
+        let result = rows(exe.run(
+            "SELECT name FROM friends WHERE NOT EXISTS (SELECT * FROM pets WHERE owner = friends.name);"
+                .into(),
+        ));
+        assert_eq!(result, vec![vec!["Beta"], vec!["Delta"]]);
+
+        // 첫 행을 찾으면 멈추므로 뒤쪽 행의 0으로 나누기는 평가되지 않음
+        let result = rows(
+            exe.run(
+                "SELECT name FROM friends
+             WHERE EXISTS (SELECT * FROM friends WHERE age / (age - 31) < 0);"
+                    .into(),
+            ),
+        );
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn test_between() {
+        let mut exe = setup();
+        let result = rows(exe.run("SELECT name FROM friends WHERE age BETWEEN 18 AND 20;".into()));
+        assert_eq!(result, vec![vec!["Alpha"], vec!["Beta"]]);
+
+        let result =
+            rows(exe.run("SELECT name FROM friends WHERE age NOT BETWEEN 19 AND 40;".into()));
+        assert_eq!(result, vec![vec!["Alpha"]]);
+
+        let result = rows(
+            exe.run("SELECT name, male BETWEEN FALSE AND FALSE FROM friends ORDER BY name;".into()),
+        );
+        assert_eq!(
+            result,
+            vec![
+                vec!["Alpha", "no"],
+                vec!["Beta", "yes"],
+                vec!["Delta", "null"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sessions_share_database() {
+        let mut first = setup();
+        let mut second = Executor::connect(first.database().clone());
+        let result = second.run("INSERT INTO friends VALUES('Gamma', FALSE, 25);".into());
+        assert!(matches!(result, QueryResult::Success));
+        let result = rows(first.run("SELECT COUNT(*) FROM friends;".into()));
+        assert_eq!(result, vec![vec!["4"]]);
+
+        // 임시 테이블과 시드는 세션마다 따로
+        first.run("CREATE TEMP TABLE scratch(n INT); PRAGMA seed = 1;".into());
+        let result = second.run("SELECT * FROM scratch;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+        second.run("PRAGMA seed = 2;".into());
+        let result = rows(first.run("PRAGMA seed;".into()));
+        assert_eq!(result, vec![vec!["1"]]);
+
+        // 같은 이름이면 임시 테이블이 공유 테이블을 가림
+        first.run("CREATE TEMP TABLE friends(name TEXT);".into());
+        let result = rows(first.run("SELECT COUNT(*) FROM friends;".into()));
+        assert_eq!(result, vec![vec!["0"]]);
+        let result = rows(second.run("SELECT COUNT(*) FROM friends;".into()));
+        assert_eq!(result, vec![vec!["4"]]);
+    }
+
+    #[test]
+    fn test_is_null() {
+        let mut exe = setup();
+        let result = rows(exe.run("SELECT name FROM friends WHERE male IS NULL;".into()));
+        assert_eq!(result, vec![vec!["Delta"]]);
+
+        let result = rows(exe.run("SELECT name FROM friends WHERE male IS NOT NULL;".into()));
+        assert_eq!(result, vec![vec!["Alpha"], vec!["Beta"]]);
+
+        // '= NULL'은 항상 NULL이라 아무 행도 고르지 않음
+        let result = rows(exe.run("SELECT name FROM friends WHERE male = NULL;".into()));
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_cast() {
+        let mut exe = setup();
+        let result = rows(
+            exe.run(
+                "SELECT CAST(age AS TEXT), CAST(age AS FLOAT) / 4, CAST(male AS INT)
+             FROM friends WHERE name = 'Alpha';"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["18", "4.5", "1"]]);
+
+        let result = rows(
+            exe.run(
+                "SELECT CAST(' 42 ' AS INT) + 1, CAST(-2.7 AS INT), CAST('yes' AS BOOL),
+             CAST(TRUE AS TEXT), CAST(NULL AS INT)
+             FROM friends LIMIT 1;"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["43", "-2", "yes", "true", "null"]]);
+
+        for bad in [
+            "CAST('abc' AS INT)",
+            "CAST('maybe' AS BOOL)",
+            "CAST(CAST('1e300' AS FLOAT) AS INT)",
+        ] {
+            let result = exe.run(format!("SELECT {} FROM friends;", bad));
+            assert!(
+                matches!(&result, QueryResult::Error(e) if e.contains("Cannot cast")),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_scalar_functions() {
+        let mut exe = setup();
+        let result = rows(exe.run(
+            "SELECT UPPER(name), lower(name), LENGTH(name) FROM friends WHERE age = 18;".into(),
+        ));
+        assert_eq!(result, vec![vec!["ALPHA", "alpha", "5"]]);
+
+        let result = rows(exe.run(
+            "SELECT ABS(-3), ABS(-2.5), ROUND(2.567, 2), ROUND(2.5), ROUND(1250, -2), ROUND(NULL)
+             FROM friends LIMIT 1;"
+                .into(),
+        ));
+        assert_eq!(result, vec![vec!["3", "2.5", "2.57", "3", "1300", "null"]]);
+
+        let result = rows(exe.run("SELECT LENGTH(UPPER(NULL)) FROM friends LIMIT 1;".into()));
+        assert_eq!(result, vec![vec!["null"]]);
+
+        let result = exe.run("SELECT UPPER(age) FROM friends;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+        let result = exe.run("SELECT ROUND() FROM friends;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_union() {
+        let mut exe = setup();
+        let result = rows(
+            exe.run(
+                "SELECT name FROM friends WHERE age < 20
+             UNION ALL SELECT name FROM friends
+             UNION ALL SELECT 'Omega' FROM friends WHERE age = 18;"
+                    .into(),
+            ),
+        );
+        assert_eq!(
+            result,
+            vec![
+                vec!["Alpha"],
+                vec!["Alpha"],
+                vec!["Beta"],
+                vec!["Delta"],
+                vec!["Omega"],
+            ]
+        );
+
+        let result = rows(exe.run(
+            "SELECT age FROM friends UNION SELECT age + 2 FROM friends ORDER BY age DESC LIMIT 3;"
+                .into(),
+        ));
+        assert_eq!(result, vec![vec!["33"], vec!["31"], vec!["22"]]);
+
+        let result =
+            exe.run("SELECT name, age FROM friends UNION SELECT name FROM friends;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_intersect_except() {
+        let mut exe = setup();
+        let result =
+            rows(exe.run("SELECT age + 2 FROM friends INTERSECT SELECT age FROM friends;".into()));
+        assert_eq!(result, vec![vec!["20"]]);
+
+        let result = rows(exe.run(
+            "SELECT age FROM friends EXCEPT SELECT age - 2 FROM friends ORDER BY 1 DESC;".into(),
+        ));
+        assert_eq!(result, vec![vec!["31"], vec!["20"]]);
+
+        // 오른쪽에 있는 개수만큼만 빠짐
+        let result = rows(
+            exe.run(
+                "SELECT male FROM friends UNION ALL SELECT male FROM friends
+             EXCEPT ALL SELECT male FROM friends WHERE age < 25;"
+                    .into(),
+            ),
+        );
+        assert_eq!(
+            result,
+            vec![vec!["null"], vec!["yes"], vec!["no"], vec!["null"]]
+        );
+
+        // INTERSECT가 EXCEPT보다 먼저 묶임
+        let result = rows(
+            exe.run(
+                "SELECT age FROM friends EXCEPT SELECT 18 FROM friends
+             INTERSECT SELECT age FROM friends WHERE age < 20;"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["20"], vec!["31"]]);
+    }
+
+    #[test]
+    fn test_schema_introspection() {
+        let mut exe = setup();
+        let result = exe.run(
+            "CREATE TABLE pets(owner TEXT, weight FLOAT);
+             CREATE TEMP TABLE scratch(x INT);"
+                .into(),
+        );
+        assert!(matches!(result, QueryResult::Success));
+
+        let tables = exe.database().tables();
+        let names: Vec<_> = tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["friends", "pets"]);
+        assert_eq!(
+            tables[1].columns,
+            vec![
+                ColumnInfo {
+                    name: "owner".into(),
+                    data_type: DataType::String,
+                },
+                ColumnInfo {
+                    name: "weight".into(),
+                    data_type: DataType::Float,
+                },
+            ]
+        );
+        assert_eq!(exe.database().table("friends").unwrap().columns.len(), 3);
+        assert_eq!(exe.database().table("scratch"), None);
+    }
+
+    #[test]
+    fn test_listen_notify() {
+        let mut first = setup();
+        let mut second = Executor::connect(first.database().clone());
+        let receiver = first.database().listen("orders");
+
+        let result = second.run("LISTEN orders; LISTEN orders;".into());
+        assert!(matches!(result, QueryResult::Success));
+        first.run("NOTIFY orders, 'new'; NOTIFY other, 'ignored'; NOTIFY orders;".into());
+
+        let expected = vec![
+            Notification {
+                channel: "orders".into(),
+                payload: "new".into(),
+            },
+            Notification {
+                channel: "orders".into(),
+                payload: "".into(),
+            },
+        ];
+        // 같은 채널을 두 번 LISTEN해도 한 번만 받음
+        assert_eq!(second.notifications().collect::<Vec<_>>(), expected);
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(first.notifications().count(), 0);
+    }
+
+    #[test]
+    fn test_table_size_functions() {
+        let mut exe = setup();
+        let result = rows(
+            exe.run(
+                "SELECT SQUIRREL_ROW_ESTIMATE('friends'), SQUIRREL_TABLE_SIZE('friends') > 0
+             FROM friends LIMIT 1;"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["3", "yes"]]);
+
+        let result = exe.run("SELECT SQUIRREL_ROW_ESTIMATE('nope') FROM friends;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+
+        let info = exe.database().table("friends").unwrap();
+        assert_eq!(info.rows, 3);
+        assert!(info.size_bytes >= 9 * size_of::<DataValue>());
+    }
+
+    #[test]
+    fn test_count_from_metadata() {
+        let mut exe = setup();
+        let stmt = Parser::new(Lexer::new("SELECT COUNT(*) FROM friends;"))
+            .and_then(|mut p| p.parse())
+            .unwrap()
+            .remove(0);
+        assert!(exe.count_rows(&stmt).unwrap().is_some());
+        assert_eq!(
+            rows(exe.run("SELECT COUNT(*) FROM friends;".into())),
+            vec![vec!["3"]]
+        );
+        assert_eq!(
+            rows(exe.run("SELECT COUNT(*) FROM friends LIMIT 1 OFFSET 1;".into())),
+            Vec::<Vec<String>>::new()
+        );
+
+        // 조건이 있거나 특정 컬럼을 세면 평소처럼 행을 읽음
+        let stmt = Parser::new(Lexer::new("SELECT COUNT(male) FROM friends;"))
+            .and_then(|mut p| p.parse())
+            .unwrap()
+            .remove(0);
+        assert!(exe.count_rows(&stmt).unwrap().is_none());
+        assert_eq!(
+            rows(exe.run("SELECT COUNT(*) FROM friends WHERE age > 18;".into())),
+            vec![vec!["2"]]
+        );
+    }
+
+    #[test]
+    fn test_explain_profile() {
+        let mut exe = setup();
+        let result = rows(exe.run(
+            "EXPLAIN (PROFILE) SELECT name FROM friends WHERE age > 18 ORDER BY name;".into(),
+        ));
+        let operators: Vec<_> = result
+            .iter()
+            .map(|row| row[0].split(" (").next().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            operators,
+            vec![
+                "Select",
+                "  Scan friends",
+                "  Filter",
+                "  Project",
+                "  Sort"
+            ]
+        );
+
+        let result = rows(
+            exe.run(
+                "EXPLAIN (PROFILE, FORMAT JSON) SELECT 1 FROM friends UNION SELECT 2 FROM friends;"
+                    .into(),
+            ),
+        );
+        assert!(result[0][0].contains(r#"{"name":"Union"}"#));
+
+        // 프로파일이 끝나면 기록을 멈춤
+        let (result, profile) = exe.run_profiled("SELECT COUNT(*) FROM friends;".into());
+        assert!(matches!(result, QueryResult::Rows { .. }));
+        assert_eq!(profile.spans.len(), 1);
+        assert!(exe.profile.is_none());
+    }
+
+    #[test]
+    fn test_foreign_key() {
+        let mut exe = setup();
+        let result = exe.run(
+            "CREATE TABLE pets(name TEXT, owner TEXT REFERENCES friends (name),
+                 parent TEXT, FOREIGN KEY (parent) REFERENCES pets (name));
+             INSERT INTO pets VALUES ('Rex', 'Alpha', NULL), ('Tom', NULL, 'Rex');
+             INSERT INTO pets VALUES ('Kit', 'Beta', 'Kat'), ('Kat', 'Beta', NULL);"
+                .into(),
+        );
+        assert!(matches!(result, QueryResult::Success));
+
+        let result = exe.run("INSERT INTO pets VALUES ('Bob', 'Gamma', NULL);".into());
+        let expected = ExecErr::ForeignKeyViolation {
+            table: "pets".into(),
+            references: "friends".into(),
+        };
+        assert!(matches!(result, QueryResult::Error(msg) if msg == expected.to_string()));
+        let result = exe.run("INSERT INTO pets VALUES ('Bob', 'Beta', 'Nobody');".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+        assert_eq!(
+            rows(exe.run("SELECT COUNT(*) FROM pets;".into())),
+            vec![vec!["4"]]
+        );
+
+        // UPDATE도 참조 무결성을 지킴
+        for sql in [
+            "UPDATE pets SET owner = 'Gamma' WHERE name = 'Rex';",
+            "UPDATE friends SET name = 'Alef' WHERE name = 'Alpha';",
+        ] {
+            assert!(
+                matches!(exe.run(sql.into()), QueryResult::Error(_)),
+                "{}",
+                sql
+            );
+        }
+
+        let info = exe.database().table("pets").unwrap();
+        assert_eq!(info.foreign_keys.len(), 2);
+        assert_eq!(info.foreign_keys[0].table, "friends");
+
+        // 참조하는 컬럼이 없거나 타입이 다르면 만들 수 없음
+        for sql in [
+            "CREATE TABLE a(x TEXT REFERENCES friends (nope));",
+            "CREATE TABLE b(x INT REFERENCES friends (name));",
+            "CREATE TABLE c(x TEXT REFERENCES nowhere (name));",
+        ] {
+            assert!(
+                matches!(exe.run(sql.into()), QueryResult::Error(_)),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn test_trigger() {
+        let mut exe = setup();
+        let result = exe.run(
+            "CREATE TABLE log(event TEXT, name TEXT, age INT);
+             CREATE TRIGGER log_insert AFTER INSERT ON friends BEGIN
+                 INSERT INTO log VALUES ('insert', NEW.name, new.age);
+             END;
+             CREATE TRIGGER log_update AFTER UPDATE ON friends BEGIN
+                 INSERT INTO log VALUES ('update', OLD.name, NEW.age - OLD.age);
+                 NOTIFY friends, 'updated';
+             END;
+             LISTEN friends;
+             INSERT INTO friends VALUES ('Gamma', TRUE, 40), ('Omega', NULL, NULL);
+             UPDATE friends SET age = age + 1 WHERE age > 19;"
+                .into(),
+        );
+        assert!(matches!(result, QueryResult::Success));
+        assert_eq!(
+            rows(exe.run("SELECT * FROM log;".into())),
+            vec![
+                vec!["insert", "Gamma", "40"],
+                vec!["insert", "Omega", "null"],
+                vec!["update", "Beta", "1"],
+                vec!["update", "Delta", "1"],
+                vec!["update", "Gamma", "1"],
+            ]
+        );
+        assert_eq!(exe.notifications().count(), 3);
+
+        // 트리거 이름은 하나뿐이고, 없는 테이블에는 붙일 수 없음
+        let result =
+            exe.run("CREATE TRIGGER log_insert AFTER INSERT ON log BEGIN NOTIFY ch; END;".into());
+        let expected = ExecErr::TriggerExists("log_insert".into());
+        assert!(matches!(result, QueryResult::Error(msg) if msg == expected.to_string()));
+        let result = exe.run(
+            "CREATE TRIGGER IF NOT EXISTS log_insert AFTER INSERT ON log BEGIN NOTIFY ch; END;"
+                .into(),
+        );
+        assert!(matches!(result, QueryResult::Success));
+        let result =
+            exe.run("CREATE TRIGGER t AFTER INSERT ON nowhere BEGIN NOTIFY ch; END;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+
+        // 자기 테이블에 다시 넣는 트리거는 정해진 깊이에서 멈춤
+        let result = exe.run(
+            "CREATE TABLE loop(n INT);
+             CREATE TRIGGER again AFTER INSERT ON loop BEGIN
+                 INSERT INTO loop VALUES (NEW.n + 1);
+             END;
+             INSERT INTO loop VALUES (0);"
+                .into(),
+        );
+        assert!(matches!(result, QueryResult::Error(msg) if msg.contains("nested")));
+        let count = |exe: &mut Executor, sql: &str| rows(exe.run(sql.into()))[0][0].clone();
+        assert_eq!(count(&mut exe, "SELECT COUNT(*) FROM loop;"), "0");
+
+        // 트리거가 실패하면 트리거가 바꾼 것까지 문장 전체가 반영되지 않음
+        for (table, temp) in [("b", ""), ("tb", "TEMP")] {
+            let result = exe.run(format!(
+                "CREATE {temp} TABLE {table}(n INT);
+                 CREATE TRIGGER broken_{table} AFTER INSERT ON {table} BEGIN
+                     INSERT INTO log VALUES ('broken', NULL, NEW.n);
+                     INSERT INTO nope VALUES (1);
+                 END;"
+            ));
+            assert!(matches!(result, QueryResult::Success));
+            for sql in [
+                "INSERT INTO {} VALUES (1);",
+                "BEGIN; INSERT INTO {} VALUES (1);",
+            ] {
+                let result = exe.run(sql.replace("{}", table));
+                assert!(matches!(result, QueryResult::Error(msg) if msg.contains("nope")));
+                exe.run("ROLLBACK;".into());
+                assert_eq!(
+                    count(&mut exe, &format!("SELECT COUNT(*) FROM {};", table)),
+                    "0"
+                );
+                let sql = "SELECT COUNT(*) FROM log WHERE event = 'broken';";
+                assert_eq!(count(&mut exe, sql), "0");
+            }
+        }
+        let result = exe.run("UPDATE friends SET age = 0; SELECT age FROM friends;".into());
+        assert!(rows(result).iter().all(|row| row[0] == "0"));
+        exe.run(
+            "CREATE TRIGGER broken_update AFTER UPDATE ON friends BEGIN
+                 INSERT INTO nope VALUES (1);
+             END;
+             UPDATE friends SET age = 1;"
+                .into(),
+        );
+        assert_eq!(count(&mut exe, "SELECT MAX(age) FROM friends;"), "0");
+
+        // 다른 세션의 INSERT에서도 실행됨
+        let mut other = Executor::connect(exe.database().clone());
+        let result = other.run("INSERT INTO friends (name) VALUES ('Zeta');".into());
+        assert!(matches!(result, QueryResult::Success));
+        assert_eq!(
+            rows(exe.run("SELECT COUNT(*) FROM log WHERE name = 'Zeta';".into())),
+            vec![vec!["1"]]
+        );
+    }
+
+    #[test]
+    fn test_with() {
+        let mut exe = setup();
+        let result = rows(
+            exe.run(
+                "WITH young AS (SELECT name, age FROM friends WHERE age < 25),
+                  named AS (SELECT name FROM young WHERE age < 20)
+             SELECT y.name, y.age FROM young y JOIN named n ON y.name = n.name;"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["Alpha", "18"]]);
+
+        // WITH의 이름이 같은 이름의 테이블을 가림
+        let result = rows(
+            exe.run(
+                "WITH friends AS (SELECT name FROM friends WHERE age > 30)
+             SELECT COUNT(*) FROM friends;"
+                    .into(),
+            ),
+        );
+        assert_eq!(result, vec![vec!["1"]]);
+
+        // WITH 절은 그 쿼리 안에서만 보임
+        assert!(exe.ctes.is_empty());
+        let result = exe.run("SELECT * FROM young;".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_window() {
+        let mut exe = setup();
+        exe.run("INSERT INTO friends VALUES('Gamma', TRUE, 20);".into());
+        let result = rows(
+            exe.run(
+                "SELECT name,
+                    ROW_NUMBER() OVER (PARTITION BY male ORDER BY age DESC),
+                    RANK() OVER (ORDER BY age),
+                    DENSE_RANK() OVER (ORDER BY age),
+                    SUM(age) OVER (ORDER BY age),
+                    COUNT(*) OVER (PARTITION BY male)
+                 FROM friends ORDER BY name;"
+                    .into(),
+            ),
+        );
+        assert_eq!(
+            result,
+            vec![
+                vec!["Alpha", "2", "1", "1", "18", "2"],
+                vec!["Beta", "1", "2", "2", "58", "1"],
+                vec!["Delta", "1", "4", "3", "89", "1"],
+                vec!["Gamma", "1", "2", "2", "58", "2"],
+            ]
+        );
+
+        for sql in [
+            "SELECT name FROM friends WHERE ROW_NUMBER() OVER () = 1;",
+            "SELECT COUNT(*), RANK() OVER () FROM friends;",
+            "SELECT ROW_NUMBER(age) OVER () FROM friends;",
+            "SELECT UPPER(name) OVER () FROM friends;",
+        ] {
+            assert!(
+                matches!(exe.run(sql.into()), QueryResult::Error(_)),
+                "{}",
+                sql
+            );
+        }
+        // 집계 함수를 윈도우로 쓸 때도 인자 수를 확인함 (너무 적거나 많은 경우)
+        for sql in [
+            "SELECT SUM() OVER () FROM friends;",
+            "SELECT APPROX_PERCENTILE(age) OVER () FROM friends;",
+            "SELECT SUM(age, age) OVER () FROM friends;",
+            "SELECT STRING_AGG(name, ',', name) OVER (ORDER BY age) FROM friends;",
+        ] {
+            assert!(
+                matches!(exe.run(sql.into()), QueryResult::Error(e) if e.contains("expects")),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut first = setup();
+        let mut second = Executor::connect(first.database().clone());
+        let receiver = first.database().listen("friends");
+        let count = |exe: &mut Executor| rows(exe.run("SELECT COUNT(*) FROM friends;".into()));
+
+        first.run(
+            "BEGIN;
+             INSERT INTO friends VALUES('Gamma', FALSE, 25);
+             CREATE TABLE pets(name TEXT, owner TEXT REFERENCES friends (name));
+             INSERT INTO pets VALUES('Tom', 'Gamma');
+             NOTIFY friends, 'Gamma';"
+                .into(),
+        );
+        // COMMIT 전에는 이 세션에서만 보임
+        assert_eq!(count(&mut first), vec![vec!["4"]]);
+        assert_eq!(count(&mut second), vec![vec!["3"]]);
+        assert!(matches!(
+            second.run("SELECT * FROM pets;".into()),
+            QueryResult::Error(_)
+        ));
+        assert_eq!(receiver.try_iter().count(), 0);
+
+        // 그 사이 다른 세션이 넣은 행도 남아야 함
+        second.run("INSERT INTO friends VALUES('Epsilon', TRUE, 40);".into());
+        assert!(matches!(first.run("COMMIT;".into()), QueryResult::Success));
+        assert_eq!(count(&mut second), vec![vec!["5"]]);
+        let result = rows(second.run("SELECT owner FROM pets;".into()));
+        assert_eq!(result, vec![vec!["Gamma"]]);
+        assert_eq!(receiver.try_iter().count(), 1);
+
+        // ROLLBACK하면 아무것도 남지 않음
+        first.run("BEGIN TRANSACTION; INSERT INTO friends VALUES('Zeta', TRUE, 50);".into());
+        assert!(matches!(
+            first.run("ROLLBACK;".into()),
+            QueryResult::Success
+        ));
+        assert_eq!(count(&mut first), vec![vec!["5"]]);
+
+        // 같은 이름의 테이블이 먼저 만들어졌으면 COMMIT 실패
+        first.run("BEGIN; CREATE TABLE toys(name TEXT);".into());
+        second.run("CREATE TABLE toys(id INT);".into());
+        assert!(matches!(first.run("COMMIT;".into()), QueryResult::Error(_)));
+        let info = first.database().table("toys").unwrap();
+        assert_eq!(info.columns[0].name, "id");
+
+        for sql in ["COMMIT;", "ROLLBACK;", "BEGIN; BEGIN;"] {
+            assert!(
+                matches!(first.run(sql.into()), QueryResult::Error(_)),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn test_copy_ndjson() {
+        let path =
+            std::env::temp_dir().join(format!("squirrel-copy-{}.ndjson", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut exe = setup();
+        let result = exe.run(format!("COPY friends TO '{}' (FORMAT ndjson);", path));
+        assert!(matches!(result, QueryResult::Success));
+        let text = std::fs::read_to_string(path).unwrap();
+        assert_eq!(
+            text.lines().last(),
+            Some(r#"{"name":"Delta","male":null,"age":31}"#)
+        );
+
+        exe.run("CREATE TABLE copied(age INT, name TEXT);".into());
+        let result = exe.run(format!("COPY copied FROM '{}' (FORMAT ndjson);", path));
+        assert!(matches!(result, QueryResult::Error(_)));
+        exe.run("CREATE TABLE copied2(age INT, name TEXT, male BOOL, note TEXT);".into());
+        let result = exe.run(format!("COPY copied2 FROM '{}' (FORMAT ndjson);", path));
+        assert!(matches!(result, QueryResult::Success));
+        let result =
+            rows(exe.run("SELECT name, age, note FROM copied2 WHERE male IS NULL;".into()));
+        assert_eq!(result, vec![vec!["Delta", "31", "null"]]);
+        std::fs::remove_file(path).unwrap();
+
+        let result = exe.run(format!("COPY copied2 FROM '{}' (FORMAT ndjson);", path));
+        assert!(matches!(result, QueryResult::Error(_)));
+        let result = exe.run("COPY copied2 TO 'x.csv' (FORMAT csv);".into());
+        assert!(matches!(result, QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_autocommit_undo() {
+        let mut first = setup();
+        let second = Executor::connect(first.database().clone());
+        let count = |exe: &mut Executor| rows(exe.run("SELECT COUNT(*) FROM friends;".into()));
+
+        first.run("PRAGMA autocommit = FALSE;".into());
+        assert_eq!(
+            rows(first.run("PRAGMA autocommit;".into())),
+            vec![vec!["no"]]
+        );
+        first.run(
+            "INSERT INTO friends VALUES('Gamma', FALSE, 25);
+             CREATE TABLE pets(name TEXT);
+             SELECT * FROM pets;
+             INSERT INTO friends VALUES('Epsilon', TRUE, 40), ('Zeta', TRUE, 50);"
+                .into(),
+        );
+        assert_eq!(count(&mut first), vec![vec!["6"]]);
+        assert_eq!(second.database().table("friends").unwrap().rows, 3);
+
+        // SELECT는 아무것도 바꾸지 않았으므로 건너뜀
+        assert!(first.undo_last());
+        assert_eq!(count(&mut first), vec![vec!["4"]]);
+        assert!(first.undo_last());
+        assert!(matches!(
+            first.run("SELECT * FROM pets;".into()),
+            QueryResult::Error(_)
+        ));
+        assert!(first.can_undo());
+
+        // 다시 켜면 남은 변경이 COMMIT됨
+        first.run("PRAGMA autocommit = TRUE;".into());
+        assert!(!first.can_undo());
+        assert!(!first.undo_last());
+        assert_eq!(second.database().table("friends").unwrap().rows, 4);
+    }
+
+    #[test]
+    fn test_update_in_transaction() {
+        let mut first = setup();
+        let mut second = Executor::connect(first.database().clone());
+        let ages = |exe: &mut Executor| rows(exe.run("SELECT age FROM friends;".into()));
+        let col = |values: [&str; 3]| values.map(|v| vec![v.to_string()]).to_vec();
+
+        first.run(
+            "PRAGMA autocommit = FALSE;
+             UPDATE friends SET age = age + 1;
+             UPDATE friends SET age = 0 WHERE name = 'Beta';"
+                .into(),
+        );
+        assert_eq!(ages(&mut first), col(["19", "0", "32"]));
+        assert_eq!(ages(&mut second), col(["18", "20", "31"]));
+
+        // 마지막 UPDATE만 되돌림
+        assert!(first.undo_last());
+        assert_eq!(ages(&mut first), col(["19", "21", "32"]));
+        assert!(matches!(first.run("COMMIT;".into()), QueryResult::Success));
+        assert_eq!(ages(&mut second), col(["19", "21", "32"]));
+
+        // 같은 테이블을 다른 세션이 먼저 바꿨으면 COMMIT 실패
+        first.run("UPDATE friends SET age = 1;".into());
+        second.run("UPDATE friends SET age = 2 WHERE name = 'Alpha';".into());
+        match first.run("COMMIT;".into()) {
+            QueryResult::Error(e) => assert!(e.contains("Write conflict"), "{}", e),
+            _ => panic!("Expected a write conflict"),
+        }
+        assert_eq!(ages(&mut first), col(["2", "21", "32"]));
+    }
+
+    #[test]
+    fn test_row_limit() {
+        let mut exe = setup();
+        exe.set_row_limit(Some(2));
+        let result = rows(exe.run("SELECT name FROM friends;".into()));
+        assert_eq!(result, vec![vec!["Alpha"], vec!["Beta"]]);
+        assert_eq!(exe.remaining_rows(), 1);
+        assert_eq!(exe.fetch_more(10), vec![vec!["Delta"]]);
+        assert_eq!(exe.remaining_rows(), 0);
+
+        // 다음 문장을 실행하면 남은 행은 버림
+        exe.run("SELECT name FROM friends;".into());
+        exe.run("INSERT INTO friends (name) VALUES('Eta');".into());
+        assert_eq!(exe.remaining_rows(), 0);
+        exe.run("EXPLAIN (PROFILE) SELECT name FROM friends;".into());
+        assert_eq!(exe.remaining_rows(), 0);
+    }
+
+    #[test]
+    fn test_notices() {
+        let mut exe = setup();
+        exe.run(
+            "CREATE TABLE IF NOT EXISTS friends(name TEXT);
+             CREATE TABLE scores(score FLOAT, bonus FLOAT);
+             INSERT INTO scores VALUES(1, 2.5), (2, 3);"
+                .into(),
+        );
+        assert_eq!(
+            exe.take_notices(),
+            vec![
+                "table 'friends' already exists, skipping",
+                "implicit conversion from INTEGER to FLOAT for column 'score'",
+                "implicit conversion from INTEGER to FLOAT for column 'bonus'",
+            ]
+        );
+        assert!(exe.take_notices().is_empty());
+
+        // 실행할 때마다 새로 쌓음
+        exe.run("INSERT INTO scores VALUES(1, 2);".into());
+        exe.run("INSERT INTO scores VALUES(1.5, 2.5);".into());
+        assert!(exe.take_notices().is_empty());
+    }
+
+    #[test]
+    fn test_strict() {
+        let mut exe = setup();
+        exe.run("CREATE TABLE scores(score FLOAT);".into());
+        assert_eq!(rows(exe.run("PRAGMA strict;".into())), vec![vec!["no"]]);
+
+        // 느슨한 모드: 숫자 모양의 문자열은 바꾸고 알림을 남김
+        let result = exe.run("INSERT INTO friends VALUES('Gamma', FALSE, '40');".into());
+        assert!(matches!(result, QueryResult::Success));
+        assert_eq!(
+            exe.take_notices(),
+            vec!["implicit conversion from TEXT to INTEGER for column 'age'"]
+        );
+        assert_eq!(
+            rows(exe.run("SELECT name FROM friends WHERE age = '40' OR age < '19';".into())),
+            vec![vec!["Alpha"], vec!["Gamma"]]
+        );
+        // 비교할 때도 같은 형식의 알림
+        assert_eq!(
+            exe.take_notices(),
+            vec!["implicit conversion from TEXT to INTEGER in a comparison"]
+        );
+        assert!(matches!(
+            exe.run("INSERT INTO friends VALUES('Omega', FALSE, 'old');".into()),
+            QueryResult::Error(_)
+        ));
+        let big = "INSERT INTO scores VALUES(9007199254740993);";
+        assert!(matches!(exe.run(big.into()), QueryResult::Success));
+
+        // 엄격한 모드: 모두 오류
+        exe.run("PRAGMA strict = on;".into());
+        assert_eq!(rows(exe.run("PRAGMA strict;".into())), vec![vec!["yes"]]);
+        for query in [
+            "INSERT INTO friends VALUES('Omega', FALSE, '40');",
+            "SELECT name FROM friends WHERE age = '40';",
+            "UPDATE friends SET age = '41';",
+            big,
+        ] {
+            assert!(
+                matches!(exe.run(query.into()), QueryResult::Error(_)),
+                "{}",
+                query
+            );
+        }
+        assert!(matches!(
+            exe.run("INSERT INTO scores VALUES(3);".into()),
+            QueryResult::Success
+        ));
+        assert!(matches!(
+            exe.run("PRAGMA strict = 1;".into()),
+            QueryResult::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_integer_overflow() {
+        let mut exe = setup();
+        let max = i64::MAX;
+        // 느슨한 모드: FLOAT로 바꿔서 계산하고 알림을 남김
+        let result = rows(exe.run(format!(
+            "SELECT {0} + 1, {0} * 2 - 1, -({0}) - 1, ABS(-({0}) - 1), 7 % -1 FROM friends LIMIT 1;",
+            max
+        )));
+        assert_eq!(
+            result,
+            vec![vec![
+                "9223372036854776000",
+                "18446744073709552000",
+                "-9223372036854775808",
+                "9223372036854776000",
+                "0"
+            ]]
+        );
+        assert_eq!(
+            exe.take_notices(),
+            vec![
+                "integer overflow, the result was promoted to FLOAT and is approximate above 2^53"
+            ]
+        );
+        // 2^63 근처의 FLOAT는 2048 간격이라 서로 다른 결과가 같은 값으로 반올림됨
+        assert_eq!(
+            rows(exe.run(format!(
+                "SELECT ({0} + 1) = ({0} + 1000), ({0} + 1) = ({0} + 3000) FROM friends LIMIT 1;",
+                max
+            ))),
+            vec![vec!["yes", "no"]]
+        );
+        exe.take_notices();
+        // SUM이 넘칠 때도 스칼라 연산과 같은 알림을 남김
+        exe.run(format!(
+            "INSERT INTO friends VALUES('Max', TRUE, {0}), ('Min', TRUE, {0});",
+            max
+        ));
+        exe.take_notices();
+        assert_eq!(
+            rows(exe.run("SELECT SUM(age) FROM friends WHERE male;".into())),
+            vec![vec!["18446744073709552000"]]
+        );
+        assert_eq!(
+            exe.take_notices(),
+            vec![
+                "integer overflow, the result was promoted to FLOAT and is approximate above 2^53"
+            ]
+        );
+
+        // 엄격한 모드: 오류
+        exe.run("PRAGMA strict = on;".into());
+        for query in [
+            format!("SELECT {} + 1 FROM friends;", max),
+            format!("SELECT -(-({}) - 1) FROM friends;", max),
+            format!("SELECT ABS(-({}) - 1) FROM friends;", max),
+            "SELECT SUM(age) FROM friends WHERE male;".into(),
+            "SELECT SUM(age) OVER () FROM friends;".into(),
+        ] {
+            assert!(
+                matches!(exe.run(query.clone()), QueryResult::Error(e) if e.contains("overflow")),
+                "{}",
+                query
+            );
+        }
+        assert_eq!(
+            rows(exe.run(format!("SELECT {} - 1 FROM friends LIMIT 1;", max))),
+            vec![vec!["9223372036854775806"]]
+        );
+    }
+
+    #[test]
+    fn test_prepared() {
+        let mut exe = setup();
+        let insert = Prepared::new("INSERT INTO friends VALUES(?, $3, ?);").unwrap();
+        assert_eq!(insert.param_count(), 4);
+        let insert = Prepared::new("INSERT INTO friends VALUES(?, ?, ?);").unwrap();
+        // 따옴표가 들어 있어도 값 그대로 들어감
+        let name = DataValue::String("O'Brien'); DROP TABLE friends; --".into());
+        let result = exe.run_prepared(
+            &insert,
+            &[name.clone(), DataValue::Bool(true), DataValue::Int(40)],
+        );
+        assert!(matches!(result, QueryResult::Success));
+
+        let select =
+            Prepared::new("SELECT name FROM friends WHERE age > $1 AND male = $2;").unwrap();
+        assert_eq!(
+            rows(exe.run_prepared(&select, &[DataValue::Int(30), DataValue::Bool(true)])),
+            vec![vec![name.to_string()]]
+        );
+        assert_eq!(
+            rows(exe.run_prepared(&select, &[DataValue::Int(10), DataValue::Bool(true)])).len(),
+            2
+        );
+        assert!(matches!(
+            exe.run_prepared(&select, &[DataValue::Int(10)]),
+            QueryResult::Error(_)
+        ));
+        // 준비하지 않고 실행하면 바인딩된 값이 없음
+        assert!(matches!(exe.run("SELECT ?;".into()), QueryResult::Error(_)));
+    }
+
+    #[test]
+    fn test_parse_errors_in_script() {
+        let mut exe = setup();
+        let result = exe.run(
+            "INSERT INTO friends VALUES ('Gamma', TRUE, 40);\nSELECT (age FROM friends;\nSELECT name FROM;"
+                .into(),
+        );
+        let QueryResult::Error(msg) = result else {
+            panic!("expected an error");
+        };
+        let lines: Vec<&str> = msg.lines().collect();
+        assert_eq!(lines.len(), 2, "{}", msg);
+        assert!(lines[0].starts_with("line 2, column 13:"), "{}", msg);
+        assert!(lines[1].starts_with("line 3, column 17:"), "{}", msg);
+        // 파싱 오류가 있으면 올바른 문장도 실행하지 않음
+        assert_eq!(
+            rows(exe.run("SELECT COUNT(*) FROM friends;".into())),
+            vec![vec!["3".to_string()]]
+        );
     }
 }
@@ -0,0 +1,180 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// 연산자 하나가 실행된 구간
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub name: String,
+    pub depth: usize,
+    pub start: Duration,
+    pub end: Duration,
+}
+
+impl Span {
+    pub fn elapsed(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// EXPLAIN (PROFILE)로 모은 연산자별 실행 시간
+///
+/// 구간은 시작한 순서대로 저장되고, `depth`로 어느 연산자 안에서 실행됐는지 나타냅니다.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    origin: Instant,
+    pub spans: Vec<Span>,
+    // 아직 끝나지 않은 구간의 인덱스
+    open: Vec<usize>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            origin: Instant::now(),
+            spans: Vec::new(),
+            open: Vec::new(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn enter(&mut self, name: &str) {
+        let now = self.origin.elapsed();
+        self.open.push(self.spans.len());
+        self.spans.push(Span {
+            name: name.to_string(),
+            depth: self.open.len() - 1,
+            start: now,
+            end: now,
+        });
+    }
+
+    pub fn exit(&mut self) {
+        if let Some(idx) = self.open.pop() {
+            self.spans[idx].end = self.origin.elapsed();
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.spans.iter().map(|s| s.end).max().unwrap_or_default()
+    }
+
+    /// 들여쓴 트리 형태 (한 줄에 연산자 하나)
+    pub fn to_text(&self) -> Vec<String> {
+        self.spans
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}{} ({:.3} ms)",
+                    "  ".repeat(s.depth),
+                    s.name,
+                    s.elapsed().as_secs_f64() * 1000.0
+                )
+            })
+            .collect()
+    }
+
+    /// speedscope(https://www.speedscope.app)에서 바로 열 수 있는 evented 형식의 JSON
+    pub fn to_json(&self) -> String {
+        let mut names: Vec<&str> = Vec::new();
+        let mut frames = Vec::with_capacity(self.spans.len());
+        for span in &self.spans {
+            let idx = match names.iter().position(|n| *n == span.name) {
+                Some(idx) => idx,
+                None => {
+                    names.push(&span.name);
+                    names.len() - 1
+                }
+            };
+            frames.push(idx);
+        }
+
+        // 구간은 시작한 순서대로 있으므로, 새 구간을 열기 전에 같은 깊이 이상의 구간을 먼저 닫음
+        let mut events = Vec::with_capacity(self.spans.len() * 2);
+        let mut stack: Vec<(&Span, usize)> = Vec::new();
+        for (span, frame) in self.spans.iter().zip(frames) {
+            while stack
+                .last()
+                .is_some_and(|(open, _)| open.depth >= span.depth)
+            {
+                let (open, frame) = stack.pop().unwrap();
+                events.push(('C', frame, open.end));
+            }
+            events.push(('O', frame, span.start));
+            stack.push((span, frame));
+        }
+        while let Some((open, frame)) = stack.pop() {
+            events.push(('C', frame, open.end));
+        }
+
+        let mut out = String::from(
+            r#"{"$schema":"https://www.speedscope.app/file-format-schema.json","shared":{"frames":["#,
+        );
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(out, r#"{{"name":"{}"}}"#, escape_json(name)).unwrap();
+        }
+        write!(
+            out,
+            r#"]}},"profiles":[{{"type":"evented","name":"query","unit":"microseconds","startValue":0,"endValue":{},"events":["#,
+            self.total().as_micros()
+        )
+        .unwrap();
+        for (i, (kind, frame, at)) in events.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write!(
+                out,
+                r#"{{"type":"{}","frame":{},"at":{}}}"#,
+                kind,
+                frame,
+                at.as_micros()
+            )
+            .unwrap();
+        }
+        out.push_str("]}]}");
+        out
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_spans() {
+        let mut profile = Profile::default();
+        profile.enter("Select");
+        profile.enter("Scan \"t\"");
+        profile.exit();
+        profile.exit();
+
+        assert_eq!(profile.spans.len(), 2);
+        assert_eq!(profile.spans[1].depth, 1);
+        assert!(profile.spans[0].end >= profile.spans[1].end);
+        assert!(profile.to_text()[1].starts_with("  Scan \"t\" ("));
+
+        let json = profile.to_json();
+        assert!(json.contains(r#"{"name":"Scan \"t\""}"#));
+        // 안쪽 구간이 바깥 구간보다 먼저 닫혀야 함
+        let close_inner = json.find(r#""type":"C","frame":1"#).unwrap();
+        let close_outer = json.find(r#""type":"C","frame":0"#).unwrap();
+        assert!(close_inner < close_outer);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::storage::fnv1a;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// SplitMix64 기반의 난수 생성기
+///
+/// 같은 시드로 만들면 항상 같은 수열과 해시 키(`hash_state`)를 돌려주므로,
+/// `PRAGMA seed = N`으로 버그 재현이나 퍼징 결과를 그대로 다시 실행할 수 있습니다.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// 시드를 지정하지 않은 경우 시간과 프로세스별 해시 키로 시드를 만듦
+    pub fn from_entropy() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(nanos);
+        Self::new(hasher.finish())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    /// 시드로 정해지는 해시 키 (실행기의 HashMap은 `RandomState` 대신 이것을 씀)
+    pub fn hash_state(&self) -> SeededState {
+        SeededState(mix64(self.seed))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix64(self.state)
+    }
+
+    /// [0, bound) 범위의 정수
+    pub fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// [0, 1) 범위의 실수
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// 시드로 키를 정하는 `BuildHasher`
+#[derive(Debug, Clone, Copy)]
+pub struct SeededState(u64);
+
+impl SeededState {
+    pub fn key(&self) -> u64 {
+        self.0
+    }
+}
+
+impl BuildHasher for SeededState {
+    type Hasher = SeededHasher;
+
+    fn build_hasher(&self) -> SeededHasher {
+        SeededHasher(self.0)
+    }
+}
+
+/// 키에서 시작하는 FNV-1a에 `mix64`를 씌운 해시
+pub struct SeededHasher(u64);
+
+impl Hasher for SeededHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 = fnv1a(self.0, bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        mix64(self.0)
+    }
+}
+
+/// SplitMix64의 마지막 단계 (입력의 모든 비트가 출력의 모든 비트에 고르게 퍼지도록 섞음)
+pub fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..16 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_reseed_restarts_sequence() {
+        let mut rng = Rng::new(7);
+        let first = rng.next_u64();
+        rng.next_u64();
+        rng.reseed(7);
+        assert_eq!(rng.next_u64(), first);
+        assert_eq!(rng.seed(), 7);
+    }
+
+    #[test]
+    fn test_hash_state_follows_seed() {
+        let hash = |seed: u64| Rng::new(seed).hash_state().hash_one("acorn");
+        assert_eq!(hash(42), hash(42));
+        assert_ne!(hash(42), hash(43));
+    }
+
+    #[test]
+    fn test_f64_range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            let f = rng.next_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::query::SetOp;
+use crate::storage::DataValue;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+
+/// 두 결과를 집합 연산으로 합침 (`all`이 아니면 중복 행은 하나만 남김)
+pub fn combine(
+    op: SetOp,
+    all: bool,
+    left: Vec<Vec<DataValue>>,
+    right: Vec<Vec<DataValue>>,
+) -> Vec<Vec<DataValue>> {
+    match op {
+        SetOp::Union if all => left.into_iter().chain(right).collect(),
+        SetOp::Union => dedup(left.into_iter().chain(right)),
+        SetOp::Intersect | SetOp::Except => {
+            // 오른쪽 행의 개수를 세어 두고, 왼쪽 행을 하나씩 맞춰 봄
+            let mut counts: HashMap<&[DataValue], Cell<usize>> =
+                HashMap::with_capacity(right.len());
+            for row in &right {
+                let n = counts.entry(row).or_default();
+                n.set(n.get() + 1);
+            }
+            let left = if all { left } else { dedup(left.into_iter()) };
+            let intersect = op == SetOp::Intersect;
+            left.into_iter()
+                .filter(|row| {
+                    let matched = match counts.get(row.as_slice()) {
+                        // ALL이면 오른쪽에 있는 개수만큼만 짝을 지음
+                        Some(n) if n.get() > 0 => {
+                            if all {
+                                n.set(n.get() - 1);
+                            }
+                            true
+                        }
+                        _ => false,
+                    };
+                    matched == intersect
+                })
+                .collect()
+        }
+    }
+}
+
+/// 처음 나온 순서를 유지하면서 중복 행을 제거
+fn dedup(rows: impl Iterator<Item = Vec<DataValue>>) -> Vec<Vec<DataValue>> {
+    let rows: Vec<_> = rows.collect();
+    let mut seen = HashSet::with_capacity(rows.len());
+    let keep: Vec<bool> = rows.iter().map(|row| seen.insert(row.as_slice())).collect();
+    rows.into_iter()
+        .zip(keep)
+        .filter_map(|(row, keep)| keep.then_some(row))
+        .collect()
+}
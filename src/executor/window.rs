@@ -0,0 +1,230 @@
+use super::Executor;
+use super::aggregate;
+use super::error::{ExecErr, Result};
+use super::eval::{self, Scope};
+use crate::query::{Expr, Order};
+use crate::storage::DataValue;
+use std::collections::HashMap;
+
+// 집계 함수가 아닌 윈도우 전용 함수 (인자 없음)
+const RANKING: &[&str] = &["ROW_NUMBER", "RANK", "DENSE_RANK"];
+
+pub fn contains_window(expr: &Expr) -> bool {
+    match expr {
+        Expr::Window { .. } => true,
+        Expr::FunctionCall { args, .. } => args.iter().any(contains_window),
+        Expr::Unary { right, .. } => contains_window(right),
+        Expr::Binary { left, right, .. } => contains_window(left) || contains_window(right),
+        Expr::Between {
+            expr, low, high, ..
+        } => contains_window(expr) || contains_window(low) || contains_window(high),
+        Expr::IsNull { expr, .. } | Expr::Cast { expr, .. } => contains_window(expr),
+        _ => false,
+    }
+}
+
+// 파티션 안의 행 하나: (정렬 키, 인자, 원래 행 번호)
+type Member = (Vec<DataValue>, Vec<DataValue>, usize);
+
+struct Call {
+    name: Box<str>,
+    args: Vec<Expr>,
+    partition_by: Vec<Expr>,
+    order_by: Vec<(Expr, Order)>,
+}
+
+/// 윈도우 함수 호출을 `#win<n>` 컬럼 참조로 바꾸고, 호출 목록을 `calls`에 모음
+fn extract(expr: &Expr, calls: &mut Vec<Call>) -> Result<Expr> {
+    Ok(match expr {
+        Expr::Window {
+            func,
+            partition_by,
+            order_by,
+        } => {
+            let Expr::FunctionCall {
+                name,
+                args,
+                order_by: None,
+            } = func.as_ref()
+            else {
+                return Err(ExecErr::InvalidArgument(
+                    "OVER must follow a plain function call".into(),
+                ));
+            };
+            let ranking = RANKING.iter().any(|f| f.eq_ignore_ascii_case(name));
+            if ranking && !args.is_empty() {
+                return Err(ExecErr::InvalidArgument(format!(
+                    "{} takes no arguments",
+                    name.to_uppercase()
+                )));
+            }
+            if !ranking {
+                match aggregate::lookup(name) {
+                    Some(agg) => agg.check_args(args.len())?,
+                    None => {
+                        return Err(ExecErr::UnknownFunction(format!("{} (as a window)", name)));
+                    }
+                }
+            }
+            if args
+                .iter()
+                .chain(partition_by)
+                .chain(order_by.iter().map(|(e, _)| e))
+                .any(|e| contains_window(e) || aggregate::contains_aggregate(e))
+            {
+                return Err(ExecErr::InvalidArgument(format!(
+                    "window function {} cannot contain aggregate or window calls",
+                    name.to_uppercase()
+                )));
+            }
+            calls.push(Call {
+                name: name.clone(),
+                args: args.clone(),
+                partition_by: partition_by.clone(),
+                order_by: order_by.clone(),
+            });
+            Expr::Ident(format!("#win{}", calls.len() - 1).into())
+        }
+        Expr::FunctionCall {
+            name,
+            args,
+            order_by,
+        } => Expr::FunctionCall {
+            name: name.clone(),
+            args: args
+                .iter()
+                .map(|arg| extract(arg, calls))
+                .collect::<Result<_>>()?,
+            order_by: order_by.clone(),
+        },
+        Expr::Unary { op, right } => Expr::Unary {
+            op: op.clone(),
+            right: extract(right, calls)?.boxed(),
+        },
+        Expr::Binary { op, left, right } => Expr::Binary {
+            op: op.clone(),
+            left: extract(left, calls)?.boxed(),
+            right: extract(right, calls)?.boxed(),
+        },
+        Expr::Between {
+            expr,
+            low,
+            high,
+            negated,
+        } => Expr::Between {
+            expr: extract(expr, calls)?.boxed(),
+            low: extract(low, calls)?.boxed(),
+            high: extract(high, calls)?.boxed(),
+            negated: *negated,
+        },
+        Expr::IsNull { expr, negated } => Expr::IsNull {
+            expr: extract(expr, calls)?.boxed(),
+            negated: *negated,
+        },
+        Expr::Cast { expr, ty } => Expr::Cast {
+            expr: extract(expr, calls)?.boxed(),
+            ty: ty.clone(),
+        },
+        expr => expr.clone(),
+    })
+}
+
+impl Executor {
+    /// 윈도우 함수의 결과를 `#win<n>` 컬럼으로 각 행에 덧붙이고,
+    /// 윈도우 함수 호출을 그 컬럼 참조로 바꾼 식을 돌려줌
+    pub(super) fn eval_windows(
+        &mut self,
+        names: &mut Vec<String>,
+        rows: &mut [Vec<DataValue>],
+        columns: &[Expr],
+    ) -> Result<Vec<Expr>> {
+        let mut calls = Vec::new();
+        let columns = columns
+            .iter()
+            .map(|expr| extract(expr, &mut calls))
+            .collect::<Result<Vec<_>>>()?;
+        let mut results = Vec::with_capacity(calls.len());
+        for call in &calls {
+            results.push(self.eval_window(names, rows, call)?);
+        }
+        names.extend((0..calls.len()).map(|i| format!("#win{}", i)));
+        for (i, row) in rows.iter_mut().enumerate() {
+            row.extend(results.iter().map(|values| values[i].clone()));
+        }
+        Ok(columns)
+    }
+
+    /// 윈도우 함수 하나를 계산해서 행 순서대로 값을 돌려줌
+    ///
+    /// ORDER BY가 있으면 파티션의 처음부터 현재 행과 정렬 값이 같은 행(peer)까지가 범위이고,
+    /// 없으면 파티션 전체가 범위입니다.
+    fn eval_window(
+        &mut self,
+        names: &[String],
+        rows: &[Vec<DataValue>],
+        call: &Call,
+    ) -> Result<Vec<DataValue>> {
+        // 파티션은 처음 나온 순서대로 (키 -> 파티션 번호)
+        let mut index: HashMap<Vec<DataValue>, usize, _> =
+            HashMap::with_hasher(self.rng.hash_state());
+        let mut partitions: Vec<Vec<Member>> = Vec::new();
+        for (idx, row) in rows.iter().enumerate() {
+            let scope = Scope::new(names, row);
+            let eval_all = |exe: &mut Self, exprs: &mut dyn Iterator<Item = &Expr>| {
+                exprs
+                    .map(|expr| exe.eval(expr, &scope))
+                    .collect::<Result<Vec<_>>>()
+            };
+            let key = eval_all(self, &mut call.partition_by.iter())?;
+            let sort_key = eval_all(self, &mut call.order_by.iter().map(|(e, _)| e))?;
+            let args = eval_all(self, &mut call.args.iter())?;
+            let part = *index.entry(key).or_insert_with(|| {
+                partitions.push(Vec::new());
+                partitions.len() - 1
+            });
+            partitions[part].push((sort_key, args, idx));
+        }
+
+        let orders: Vec<Order> = call.order_by.iter().map(|(_, order)| *order).collect();
+        let name = call.name.to_uppercase();
+        let mut out = vec![DataValue::Null; rows.len()];
+        for mut part in partitions {
+            eval::sort_rows(&mut part, |(a, ..), (b, ..)| {
+                eval::compare_keys(a, b, &orders)
+            })?;
+            let mut acc = aggregate::lookup(&name).map(|agg| agg.accumulator(&mut self.rng));
+            let (mut start, mut dense) = (0, 0);
+            while start < part.len() {
+                // 정렬 값이 같은 행들은 같은 순위, 같은 누적값을 가짐
+                let mut end = start + 1;
+                while end < part.len()
+                    && (orders.is_empty()
+                        || eval::compare_keys(&part[start].0, &part[end].0, &orders)?.is_eq())
+                {
+                    end += 1;
+                }
+                dense += 1;
+                if let Some(acc) = &mut acc {
+                    for (_, args, _) in &part[start..end] {
+                        acc.update(args, self)?;
+                    }
+                }
+                let value = |offset: usize| -> Result<DataValue> {
+                    Ok(match name.as_str() {
+                        "ROW_NUMBER" => DataValue::Int((start + offset + 1) as i64),
+                        "RANK" => DataValue::Int(start as i64 + 1),
+                        "DENSE_RANK" => DataValue::Int(dense),
+                        _ => acc
+                            .as_ref()
+                            .map_or(Ok(DataValue::Null), |acc| acc.finish())?,
+                    })
+                };
+                for (offset, (.., idx)) in part[start..end].iter().enumerate() {
+                    out[*idx] = value(offset)?;
+                }
+                start = end;
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,178 @@
+use crate::storage::DataValue;
+use std::fmt::Write;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#;
+
+/// 시트 하나짜리 엑셀 파일(.xlsx)을 만듦 (첫 행은 컬럼 이름, NULL은 빈 칸)
+///
+/// xlsx는 XML 파일들을 묶은 zip이므로, 압축하지 않은(stored) zip으로 직접 씁니다.
+pub fn write_xlsx(sheet: &str, columns: &[String], rows: &[Vec<DataValue>]) -> Vec<u8> {
+    // 시트 이름은 31자까지
+    let sheet: String = sheet.chars().take(31).collect();
+    let workbook = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        escape_xml(&sheet)
+    );
+    let header: Vec<DataValue> = columns
+        .iter()
+        .map(|c| DataValue::String(c.clone()))
+        .collect();
+    let mut data = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    for (r, row) in std::iter::once(&header).chain(rows).enumerate() {
+        write!(data, r#"<row r="{}">"#, r + 1).unwrap();
+        for (c, value) in row.iter().enumerate() {
+            let cell = format!("{}{}", column_letter(c), r + 1);
+            match value {
+                DataValue::Null => continue,
+                DataValue::Int(n) => write!(data, r#"<c r="{}"><v>{}</v></c>"#, cell, n),
+                DataValue::Float(f) if f.is_finite() => {
+                    write!(data, r#"<c r="{}"><v>{}</v></c>"#, cell, f)
+                }
+                DataValue::Bool(b) => {
+                    write!(data, r#"<c r="{}" t="b"><v>{}</v></c>"#, cell, *b as u8)
+                }
+                // NaN과 무한대는 엑셀 숫자로 나타낼 수 없으므로 문자열로 씀
+                value => write!(
+                    data,
+                    r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    cell,
+                    escape_xml(&value.to_string())
+                ),
+            }
+            .unwrap();
+        }
+        data.push_str("</row>");
+    }
+    data.push_str("</sheetData></worksheet>");
+
+    zip_stored(&[
+        ("[Content_Types].xml", CONTENT_TYPES.as_bytes()),
+        ("_rels/.rels", ROOT_RELS.as_bytes()),
+        ("xl/workbook.xml", workbook.as_bytes()),
+        ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes()),
+        ("xl/worksheets/sheet1.xml", data.as_bytes()),
+    ])
+}
+
+/// 0 → A, 25 → Z, 26 → AA, ...
+fn column_letter(mut idx: usize) -> String {
+    let mut out = Vec::new();
+    loop {
+        out.push(b'A' + (idx % 26) as u8);
+        if idx < 26 {
+            break;
+        }
+        idx = idx / 26 - 1;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap()
+}
+
+// XML 1.0에 쓸 수 없는 제어 문자는 버림
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// 압축하지 않고 파일들을 zip으로 묶음
+fn zip_stored(files: &[(&str, &[u8])]) -> Vec<u8> {
+    // 1980-01-01 00:00 (zip의 가장 이른 날짜)
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = (1 << 5) | 1;
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in files {
+        let offset = out.len() as u32;
+        let crc = crc32fast::hash(data);
+        let mut header = Vec::new();
+        header.extend(20u16.to_le_bytes()); // version needed
+        header.extend(0u16.to_le_bytes()); // flags
+        header.extend(0u16.to_le_bytes()); // method: stored
+        header.extend(DOS_TIME.to_le_bytes());
+        header.extend(DOS_DATE.to_le_bytes());
+        header.extend(crc.to_le_bytes());
+        header.extend((data.len() as u32).to_le_bytes()); // compressed size
+        header.extend((data.len() as u32).to_le_bytes()); // uncompressed size
+        header.extend((name.len() as u16).to_le_bytes());
+        header.extend(0u16.to_le_bytes()); // extra field length
+
+        out.extend(0x04034b50u32.to_le_bytes());
+        out.extend(&header);
+        out.extend(name.as_bytes());
+        out.extend(*data);
+
+        central.extend(0x02014b50u32.to_le_bytes());
+        central.extend(20u16.to_le_bytes()); // version made by
+        central.extend(&header);
+        central.extend(0u16.to_le_bytes()); // comment length
+        central.extend(0u16.to_le_bytes()); // disk number
+        central.extend(0u16.to_le_bytes()); // internal attributes
+        central.extend(0u32.to_le_bytes()); // external attributes
+        central.extend(offset.to_le_bytes());
+        central.extend(name.as_bytes());
+    }
+    let central_offset = out.len() as u32;
+    out.extend(&central);
+    out.extend(0x06054b50u32.to_le_bytes());
+    out.extend(0u16.to_le_bytes()); // this disk
+    out.extend(0u16.to_le_bytes()); // disk with the central directory
+    out.extend((files.len() as u16).to_le_bytes());
+    out.extend((files.len() as u16).to_le_bytes());
+    out.extend((central.len() as u32).to_le_bytes());
+    out.extend(central_offset.to_le_bytes());
+    out.extend(0u16.to_le_bytes()); // comment length
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_letter() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(26), "AA");
+        assert_eq!(column_letter(701), "ZZ");
+        assert_eq!(column_letter(702), "AAA");
+    }
+
+    #[test]
+    fn test_write_xlsx() {
+        let columns = vec!["name".to_string(), "age".to_string()];
+        let rows = vec![
+            vec![DataValue::String("<Alpha & co>".into()), DataValue::Int(18)],
+            vec![DataValue::Null, DataValue::Float(f64::NAN)],
+        ];
+        let bytes = write_xlsx("friends", &columns, &rows);
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+        // 압축하지 않았으므로 시트 XML이 그대로 들어 있음
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.contains(r#"<c r="A2" t="inlineStr"><is><t xml:space="preserve">&lt;Alpha &amp; co&gt;</t></is></c>"#));
+        assert!(text.contains(r#"<c r="B2"><v>18</v></c>"#));
+        assert!(text.contains(r#"<row r="3"><c r="B3" t="inlineStr">"#));
+        assert!(text.contains(r#"<sheet name="friends""#));
+    }
+}
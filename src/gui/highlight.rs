@@ -0,0 +1,196 @@
+use crate::query::Lexer;
+use crate::query::lexer::Token;
+use eframe::egui;
+use egui::text::LayoutJob;
+use egui::{Color32, FontId, TextFormat};
+use std::collections::HashMap;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Plain,
+    Keyword,
+    Literal,
+    Text,
+    Param,
+    Punctuation,
+    Comment,
+}
+
+impl Kind {
+    fn color(self) -> Color32 {
+        match self {
+            Kind::Plain => Color32::from_gray(220),
+            Kind::Keyword => Color32::from_rgb(255, 100, 100),
+            Kind::Literal => Color32::from_rgb(87, 165, 171),
+            Kind::Text => Color32::from_rgb(109, 147, 226),
+            Kind::Param => Color32::from_rgb(230, 180, 80),
+            Kind::Punctuation => Color32::LIGHT_GRAY,
+            Kind::Comment => Color32::from_gray(120),
+        }
+    }
+}
+
+/// 에디터의 SQL을 색칠하는 레이아웃을 만듦
+///
+/// 줄마다 색칠한 결과를 기억해 두고, 텍스트가 바뀌면 새로 생기거나 바뀐 줄만 다시 렉싱합니다.
+/// 텍스트가 그대로면 지난 프레임의 레이아웃을 그대로 돌려줍니다.
+#[derive(Default)]
+pub struct Highlighter {
+    // 마지막으로 색칠한 (텍스트, 줄바꿈 폭, 레이아웃)
+    last: Option<(String, f32, LayoutJob)>,
+    //   line → (바이트 범위, 종류)
+    lines: HashMap<String, Vec<(Range<usize>, Kind)>>,
+}
+
+impl Highlighter {
+    pub fn layout(&mut self, text: &str, font_id: &FontId, wrap_width: f32) -> LayoutJob {
+        if let Some((last, width, job)) = &self.last
+            && last == text
+            && *width == wrap_width
+        {
+            return job.clone();
+        }
+        let mut old = std::mem::take(&mut self.lines);
+        let mut job = LayoutJob::default();
+        for line in text.split_inclusive('\n') {
+            let content = line.trim_end_matches('\n');
+            if !self.lines.contains_key(content) {
+                let spans = old.remove(content).unwrap_or_else(|| line_spans(content));
+                self.lines.insert(content.to_string(), spans);
+            }
+            for (range, kind) in &self.lines[content] {
+                job.append(
+                    &content[range.clone()],
+                    0.,
+                    TextFormat::simple(font_id.clone(), kind.color()),
+                );
+            }
+            if content.len() < line.len() {
+                job.append(
+                    "\n",
+                    0.,
+                    TextFormat::simple(font_id.clone(), Kind::Plain.color()),
+                );
+            }
+        }
+        job.wrap.max_width = wrap_width;
+        self.last = Some((text.to_string(), wrap_width, job.clone()));
+        job
+    }
+}
+
+/// 줄 하나를 이 크레이트의 렉서로 읽어 토큰마다 색칠할 범위를 구함
+///
+/// 여러 줄에 걸친 문자열처럼 줄 안에서 렉싱이 실패하면 나머지는 그냥 문자열 색으로 칠합니다.
+fn line_spans(line: &str) -> Vec<(Range<usize>, Kind)> {
+    // 렉서는 문자 단위로 읽으므로 문자 위치 → 바이트 위치
+    let offsets: Vec<usize> = line
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([line.len()])
+        .collect();
+    let total = offsets.len() - 1;
+    let mut spans = Vec::new();
+    let mut lexer = Lexer::new(line);
+    let mut pos = 0;
+    loop {
+        let rest = &line[pos..];
+        let start = pos + rest.len() - rest.trim_start().len();
+        if start > pos {
+            spans.push((pos..start, Kind::Plain));
+        }
+        if start == line.len() {
+            break;
+        }
+        if line[start..].starts_with("--") {
+            spans.push((start..line.len(), Kind::Comment));
+            break;
+        }
+        let kind = match lexer.next() {
+            Ok(Token::Eof) => break,
+            Ok(token) => kind_of(&token),
+            Err(_) => {
+                spans.push((start..line.len(), Kind::Text));
+                break;
+            }
+        };
+        pos = offsets[total - lexer.remaining()];
+        spans.push((start..pos, kind));
+    }
+    spans
+}
+
+fn kind_of(token: &Token) -> Kind {
+    match token {
+        Token::Ident(_) => Kind::Plain,
+        Token::Null | Token::Bool(_) | Token::Int(_) | Token::Float(_) => Kind::Literal,
+        Token::Text(_) => Kind::Text,
+        Token::Param(_) => Kind::Param,
+        Token::Dot
+        | Token::Comma
+        | Token::Semicolon
+        | Token::LParen
+        | Token::RParen
+        | Token::OpEq
+        | Token::OpGt
+        | Token::OpLt
+        | Token::OpGe
+        | Token::OpLe
+        | Token::OpAdd
+        | Token::OpSub
+        | Token::OpMul
+        | Token::OpDiv
+        | Token::OpMod => Kind::Punctuation,
+        _ => Kind::Keyword,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_spans() {
+        let line = "SELECT naïve, 'a b' FROM t WHERE x = $1 -- 설명";
+        let spans: Vec<(&str, Kind)> = line_spans(line)
+            .into_iter()
+            .filter(|(_, kind)| *kind != Kind::Plain)
+            .map(|(range, kind)| (&line[range], kind))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("SELECT", Kind::Keyword),
+                (",", Kind::Punctuation),
+                ("'a b'", Kind::Text),
+                ("FROM", Kind::Keyword),
+                ("WHERE", Kind::Keyword),
+                ("=", Kind::Punctuation),
+                ("$1", Kind::Param),
+                ("-- 설명", Kind::Comment),
+            ]
+        );
+        // 범위가 줄 전체를 빈틈없이 덮음
+        let spans = line_spans(line);
+        assert_eq!(spans.first().unwrap().0.start, 0);
+        assert_eq!(spans.last().unwrap().0.end, line.len());
+        assert!(spans.windows(2).all(|w| w[0].0.end == w[1].0.start));
+
+        assert_eq!(line_spans("'unterminated"), vec![(0..13, Kind::Text)]);
+    }
+
+    #[test]
+    fn test_layout_reuses_lines() {
+        let font = FontId::monospace(14.);
+        let mut highlighter = Highlighter::default();
+        let job = highlighter.layout("SELECT 1;\nSELECT 2;\n", &font, 100.);
+        assert_eq!(job.text, "SELECT 1;\nSELECT 2;\n");
+        assert_eq!(highlighter.lines.len(), 2);
+        let job = highlighter.layout("SELECT 1;\nSELECT 3;", &font, 100.);
+        assert_eq!(job.text, "SELECT 1;\nSELECT 3;");
+        // 없어진 줄은 잊음
+        assert!(highlighter.lines.contains_key("SELECT 3;"));
+        assert!(!highlighter.lines.contains_key("SELECT 2;"));
+    }
+}
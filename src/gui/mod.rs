@@ -1,37 +1,256 @@
-use crate::executor::{Executor, QueryResult};
+use crate::config::Config;
+use crate::executor::profile::Profile;
+use crate::executor::{ColumnInfo, Executor, QueryResult};
+use crate::query::lexer::Token;
+use crate::query::{Lexer, format_sql};
+use crate::repl::sql_literal;
 use eframe::{App, egui};
 use egui::Color32;
-use egui_extras;
-use egui_extras::syntax_highlighting::CodeTheme;
+use egui_extras::{Column, TableBuilder};
+use highlight::Highlighter;
+use snapshot::Snapshots;
+use state::GuiState;
+use std::path::PathBuf;
+
+mod highlight;
+mod snapshot;
+mod state;
 
 pub struct Application {
     exe: Executor,
-    query: String,
+    // 쿼리 탭마다의 내용과 지금 고른 탭
+    tabs: Vec<String>,
+    tab: usize,
+    highlighter: Highlighter,
     result: Option<QueryResult>,
+    // Profile 버튼으로 실행했을 때의 연산자별 실행 시간
+    profile: Option<Profile>,
+    // 에디터 밖에서 붙여넣은 표를 넣을 테이블과, 넣기 전 미리보기
+    paste_target: String,
+    paste: Option<Paste>,
+    editor_focused: bool,
+    // 이름을 붙여 고정한 결과 (나중에 다시 실행한 결과와 비교)
+    snapshots: Snapshots,
+    // 지금 보이는 결과를 만든 쿼리와, 그 쿼리가 남긴 알림(NOTICE)
+    result_query: String,
+    notices: Vec<String>,
+    // 결과 표에서 키보드로 고른 셀 (행, 열)
+    grid_cursor: (usize, usize),
+    // 결과를 한 번에 가져오는 행 수 (나머지는 실행기에 남겨 두고 Load more로 가져옴)
+    row_limit: usize,
+    // 창의 위치와 크기, 열어 둔 데이터베이스 (종료할 때 state_path에 저장)
+    window: Option<[f32; 4]>,
+    database: Option<PathBuf>,
+    state_path: Option<PathBuf>,
+}
+
+/// 붙여넣은 TSV/CSV를 테이블의 행으로 넣기 전에 보여주는 미리보기
+struct Paste {
+    table: String,
+    columns: Vec<ColumnInfo>,
+    // 셀마다 SQL 값 또는 오류 메시지 (컬럼 수가 맞지 않는 행은 행 전체가 오류)
+    rows: Vec<Result<Vec<Result<String, String>>, String>>,
+}
+
+impl Paste {
+    /// 첫 행이 컬럼 이름이면 그 순서대로, 아니면 테이블의 컬럼 순서대로 넣음
+    fn new(table: String, schema: Vec<ColumnInfo>, text: &str) -> Result<Self, String> {
+        let mut rows = parse_delimited(text);
+        let header = rows.first().and_then(|first| {
+            first
+                .iter()
+                .map(|name| schema.iter().find(|c| &c.name == name).cloned())
+                .collect::<Option<Vec<_>>>()
+        });
+        let columns = match header {
+            Some(columns) => {
+                rows.remove(0);
+                columns
+            }
+            None => schema,
+        };
+        if rows.is_empty() {
+            return Err("nothing to insert".into());
+        }
+        // 렉서에 따옴표로 감싼 식별자가 없으므로, INSERT에 그대로 쓸 수 없는 이름은 거부
+        if let Some(name) = std::iter::once(&table)
+            .chain(columns.iter().map(|c| &c.name))
+            .find(|name| !is_plain_ident(name))
+        {
+            return Err(format!("'{}' cannot be used as a name in SQL", name));
+        }
+        let rows = rows
+            .into_iter()
+            .map(|row| {
+                if row.len() != columns.len() {
+                    return Err(format!(
+                        "expected {} cells, but found {}",
+                        columns.len(),
+                        row.len()
+                    ));
+                }
+                // 스프레드시트의 빈 칸은 NULL
+                Ok(row
+                    .iter()
+                    .zip(&columns)
+                    .map(|(cell, col)| match cell.as_str() {
+                        "" => Ok("NULL".into()),
+                        cell => sql_literal(cell, col.data_type),
+                    })
+                    .collect())
+            })
+            .collect();
+        Ok(Self {
+            table,
+            columns,
+            rows,
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.rows.iter().all(|row| {
+            row.as_ref()
+                .is_ok_and(|cells| cells.iter().all(Result::is_ok))
+        })
+    }
+
+    fn to_sql(&self) -> String {
+        let names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+        let values: Vec<String> = self
+            .rows
+            .iter()
+            .flatten()
+            .map(|cells| {
+                let cells: Vec<&str> = cells.iter().flatten().map(String::as_str).collect();
+                format!("({})", cells.join(", "))
+            })
+            .collect();
+        format!(
+            "INSERT INTO {} ({}) VALUES {};",
+            self.table,
+            names.join(", "),
+            values.join(", ")
+        )
+    }
+}
+
+/// 키워드가 아니고 렉서가 식별자 하나로 읽는 이름인지
+fn is_plain_ident(name: &str) -> bool {
+    let mut lexer = Lexer::new(name);
+    matches!(lexer.next(), Ok(Token::Ident(ident)) if ident == name)
+        && matches!(lexer.next(), Ok(Token::Eof))
 }
 
 impl App for Application {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_state();
+    }
+
     fn update(&mut self, _ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
+        if !self.editor_focused {
+            let pasted = _ctx.input(|i| {
+                i.events.iter().find_map(|e| match e {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                })
+            });
+            if let Some(text) = pasted {
+                self.start_paste(&text);
+            }
+        }
+        let window = _ctx.input(|i| Some((i.viewport().outer_rect?, i.viewport().inner_rect?)));
+        if let Some((outer, inner)) = window {
+            self.window = Some([outer.min.x, outer.min.y, inner.width(), inner.height()]);
+        }
         eframe::egui::CentralPanel::default().show(_ctx, |ui| {
             let max_rect = ui.max_rect();
             ui.horizontal(|ui| {
                 ui.vertical(|ui| {
                     ui.set_width(max_rect.width() * 0.5);
-                    self.draw_code_editor(max_rect.height() - 20., ui);
-                    ui.button("Query!").clicked().then(|| {
-                        let result = self.exe.run(self.query.clone());
-                        self.result = Some(result);
+                    self.draw_tabs(ui);
+                    self.draw_code_editor(ui.available_height() - 20., ui);
+                    ui.horizontal(|ui| {
+                        ui.button("Query!").clicked().then(|| {
+                            let query = self.tabs[self.tab].clone();
+                            self.result = Some(self.exe.run(query.clone()));
+                            self.notices = self.exe.take_notices();
+                            self.result_query = query;
+                            self.profile = None;
+                        });
+                        ui.button("Profile").clicked().then(|| {
+                            let query = self.tabs[self.tab].clone();
+                            let (result, profile) = self.exe.run_profiled(query.clone());
+                            self.result = Some(result);
+                            self.notices = self.exe.take_notices();
+                            self.result_query = query;
+                            self.profile = Some(profile);
+                        });
+                        let format = ui.button("Format").on_hover_text("Comments are removed");
+                        if format.clicked() {
+                            match format_sql(&self.tabs[self.tab]) {
+                                Ok(sql) => self.tabs[self.tab] = sql,
+                                Err(e) => self.result = Some(QueryResult::Error(e.to_string())),
+                            }
+                        }
+                        let mut autocommit = self.exe.autocommit();
+                        if ui.checkbox(&mut autocommit, "Autocommit").changed() {
+                            let sql = format!("PRAGMA autocommit = {};", autocommit);
+                            self.result = Some(self.exe.run(sql));
+                            self.notices = self.exe.take_notices();
+                        }
+                        let undo = ui.add_enabled(
+                            self.exe.can_undo(),
+                            egui::Button::new("Undo last statement"),
+                        );
+                        undo.clicked().then(|| {
+                            self.exe.undo_last();
+                            self.result = Some(QueryResult::Success);
+                            self.notices.clear();
+                            self.profile = None;
+                        });
                     });
                 });
                 ui.separator();
                 ui.vertical(|ui| {
-                    if let None = &self.result {
-                        ui.label("No results yet.");
-                    } else {
-                        let result = self.result.as_ref().unwrap();
+                    self.draw_paste(ui);
+                    if let Some(profile) = &self.profile {
+                        draw_profile(profile, ui);
+                        ui.separator();
+                    }
+                    let remaining = self.exe.remaining_rows();
+                    if remaining > 0
+                        && let Some(QueryResult::Rows { rows, .. }) = &mut self.result
+                    {
+                        ui.horizontal(|ui| {
+                            ui.label(format!(
+                                "Showing {} rows, {} more not loaded.",
+                                rows.len(),
+                                remaining
+                            ));
+                            if ui.button("Load more").clicked() {
+                                rows.extend(self.exe.fetch_more(self.row_limit));
+                            }
+                        });
+                    }
+                    if !self.notices.is_empty() {
+                        let title = format!("Notices ({})", self.notices.len());
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(title).color(Color32::YELLOW),
+                        )
+                        .id_salt("notices")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for notice in &self.notices {
+                                ui.colored_label(Color32::YELLOW, notice);
+                            }
+                        });
+                    }
+                    if let Some(result) = &self.result {
                         match result {
-                            QueryResult::Rows(_rows) => {
-                                todo!()
+                            QueryResult::Rows { columns, rows }
+                                if self.snapshots.show(&self.result_query, columns, rows, ui) => {}
+                            QueryResult::Rows { columns, rows } => {
+                                draw_rows(columns, rows, &mut self.grid_cursor, ui);
                             }
                             QueryResult::Success => {
                                 ui.colored_label(Color32::GREEN, "Query executed successfully.");
@@ -40,6 +259,8 @@ impl App for Application {
                                 ui.colored_label(Color32::RED, format!("Error: {}", msg));
                             }
                         }
+                    } else {
+                        ui.label("No results yet.");
                     }
                 });
             });
@@ -47,44 +268,474 @@ impl App for Application {
     }
 }
 
+impl Default for Application {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Application {
     pub fn new() -> Self {
+        Self::with_executor(Executor::new())
+    }
+
+    pub fn with_executor(exe: Executor) -> Self {
         Self {
-            exe: Executor::new(),
-            query: String::new(),
+            exe,
+            tabs: vec![String::new()],
+            tab: 0,
+            highlighter: Highlighter::default(),
             result: None,
+            profile: None,
+            paste_target: String::new(),
+            paste: None,
+            editor_focused: false,
+            snapshots: Snapshots::default(),
+            result_query: String::new(),
+            notices: Vec::new(),
+            grid_cursor: (0, 0),
+            row_limit: 0,
+            window: None,
+            database: None,
+            state_path: None,
+        }
+        .with_row_limit(Config::default().gui_row_limit)
+    }
+
+    pub fn with_row_limit(mut self, limit: usize) -> Self {
+        self.row_limit = limit.max(1);
+        self.exe.set_row_limit(Some(self.row_limit));
+        self
+    }
+
+    /// GUI를 염 (지난번에 저장한 창 위치, 탭, 데이터베이스를 되살림)
+    pub fn launch(mut self, database: Option<PathBuf>) {
+        self.state_path = GuiState::default_path();
+        let state = self
+            .state_path
+            .as_deref()
+            .map(GuiState::load)
+            .unwrap_or_default();
+        if !state.tabs.is_empty() {
+            self.tab = state.active_tab.min(state.tabs.len() - 1);
+            self.tabs = state.tabs;
+        }
+        // 지난번 데이터베이스가 그사이 지워졌으면 잊음
+        self.database = database.or(state.database.filter(|db| db.exists()));
+
+        let title = match &self.database {
+            // 쿼리는 메모리의 테이블에서 실행되고 이 데이터베이스에 저장되지 않음
+            Some(db) => format!("SQuirreL GUI - {} (in-memory session)", db.display()),
+            None => "SQuirreL GUI".into(),
+        };
+        let mut viewport = egui::ViewportBuilder::default().with_title(title);
+        if let Some([x, y, width, height]) = state.window {
+            viewport = viewport
+                .with_position([x, y])
+                .with_inner_size([width, height]);
+        }
+        let options = eframe::NativeOptions {
+            viewport,
+            ..Default::default()
+        };
+        if let Err(e) =
+            eframe::run_native("SQuirreL GUI", options, Box::new(|_cc| Ok(Box::new(self))))
+        {
+            eprintln!("Failed to launch GUI: {}", e);
         }
     }
 
-    pub fn launch(self) {
-        let options = eframe::NativeOptions::default();
-        eframe::run_native("SQuirreL GUI", options, Box::new(|_cc| Ok(Box::new(self))));
+    fn save_state(&self) {
+        let Some(path) = &self.state_path else {
+            return;
+        };
+        let state = GuiState {
+            window: self.window,
+            tabs: self.tabs.clone(),
+            active_tab: self.tab,
+            database: self.database.clone(),
+        };
+        if let Err(e) = state.save(path) {
+            eprintln!("Failed to save GUI state to '{}': {}", path.display(), e);
+        }
+    }
+
+    /// 쿼리 탭 목록 (+는 새 탭, ×는 지금 탭을 닫음)
+    fn draw_tabs(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for i in 0..self.tabs.len() {
+                ui.selectable_value(&mut self.tab, i, format!("Query {}", i + 1));
+            }
+            if ui.button("+").on_hover_text("New tab").clicked() {
+                self.tabs.push(String::new());
+                self.tab = self.tabs.len() - 1;
+            }
+            if self.tabs.len() > 1 && ui.button("×").on_hover_text("Close tab").clicked() {
+                self.tabs.remove(self.tab);
+                self.tab = self.tab.min(self.tabs.len() - 1);
+            }
+        });
     }
 
     fn draw_code_editor(&mut self, height: f32, ui: &mut egui::Ui) {
+        let highlighter = &mut self.highlighter;
         let mut layouter = |ui: &egui::Ui, buf: &dyn egui::TextBuffer, wrap_width: f32| {
-            let mut layout_job = egui_extras::syntax_highlighting::highlight(
-                ui.ctx(),
-                ui.style(),
-                &CodeTheme::dark(20.0),
-                buf.as_str(),
-                "SQL",
-            );
-            layout_job.wrap.max_width = wrap_width;
+            let font_id = egui::FontId::monospace(20.0);
+            let layout_job = highlighter.layout(buf.as_str(), &font_id, wrap_width);
             ui.fonts_mut(|f| f.layout_job(layout_job))
         };
         egui::ScrollArea::vertical()
             .min_scrolled_height(height)
             .show(ui, |ui| {
                 ui.take_available_height();
-                let editor = egui::TextEdit::multiline(&mut self.query)
+                let editor = egui::TextEdit::multiline(&mut self.tabs[self.tab])
                     .font(egui::TextStyle::Monospace) // for cursor height
                     .code_editor()
                     .desired_rows(999)
                     .lock_focus(true)
+                    // 화면 낭독기가 읽는 이름 (Esc로 에디터를 빠져나와 Tab으로 이동)
+                    .hint_text("SQL query (press Esc, then Tab to leave the editor)")
                     .desired_width(f32::INFINITY)
                     .layouter(&mut layouter);
-                ui.add(editor);
+                self.editor_focused = ui.add(editor).has_focus();
             });
     }
+
+    fn start_paste(&mut self, text: &str) {
+        let Some(info) = self.exe.table(&self.paste_target) else {
+            self.result = Some(QueryResult::Error(
+                "Select a table to paste rows into.".into(),
+            ));
+            return;
+        };
+        match Paste::new(info.name, info.columns, text) {
+            Ok(paste) => self.paste = Some(paste),
+            Err(msg) => self.result = Some(QueryResult::Error(msg)),
+        }
+    }
+
+    /// 붙여넣을 테이블 선택과, 붙여넣은 행의 미리보기 (잘못된 셀은 빨간색)
+    fn draw_paste(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = ui.label("Paste rows into");
+            egui::ComboBox::from_id_salt("paste_target")
+                .selected_text(&self.paste_target)
+                .show_ui(ui, |ui| {
+                    for table in self.exe.tables() {
+                        ui.selectable_value(&mut self.paste_target, table.name.clone(), table.name);
+                    }
+                })
+                .response
+                .labelled_by(label.id);
+        });
+        let Some(paste) = &self.paste else {
+            ui.separator();
+            return;
+        };
+        egui::Grid::new("paste_grid").striped(true).show(ui, |ui| {
+            for column in &paste.columns {
+                ui.strong(format!("{} ({})", column.name, column.data_type.as_str()));
+            }
+            ui.end_row();
+            for row in &paste.rows {
+                match row {
+                    Ok(cells) => {
+                        for cell in cells {
+                            match cell {
+                                Ok(value) => ui.label(value),
+                                Err(msg) => ui.colored_label(Color32::RED, msg),
+                            };
+                        }
+                    }
+                    Err(msg) => {
+                        ui.colored_label(Color32::RED, msg);
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        let (insert, cancel) = ui
+            .horizontal(|ui| {
+                let insert = ui.add_enabled(
+                    paste.is_valid(),
+                    egui::Button::new(format!("Insert {} rows", paste.rows.len())),
+                );
+                (insert.clicked(), ui.button("Cancel").clicked())
+            })
+            .inner;
+        if insert {
+            let sql = paste.to_sql();
+            self.result = Some(self.exe.run(sql));
+            self.notices = self.exe.take_notices();
+        }
+        if insert || cancel {
+            self.paste = None;
+        }
+        ui.separator();
+    }
+}
+
+/// 스프레드시트에서 복사한 TSV, 또는 CSV를 셀 단위로 나눔
+///
+/// 탭이 있으면 TSV로, 없으면 CSV로 보고, 큰따옴표로 감싼 셀 안의 구분자·줄바꿈·`""`를 처리합니다.
+fn parse_delimited(text: &str) -> Vec<Vec<String>> {
+    let delimiter = if text.contains('\t') { '\t' } else { ',' };
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push(chars.next().unwrap());
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.is_empty() => quoted = true,
+            c if quoted => cell.push(c),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    rows.retain(|row| !(row.len() == 1 && row[0].trim().is_empty()));
+    rows
+}
+
+/// 결과 표를 그림 (행 높이가 모두 같으므로 화면에 보이는 행만 배치)
+///
+/// 표 위의 요약 줄에 Tab으로 포커스를 옮기면 방향키, PageUp/PageDown, Home/End로 셀을 고를 수 있고,
+/// 고른 셀은 "Row 3 of 10, age: 18"처럼 화면 낭독기에 전달됩니다.
+fn draw_rows(
+    columns: &[String],
+    rows: &[Vec<String>],
+    cursor: &mut (usize, usize),
+    ui: &mut egui::Ui,
+) {
+    const PAGE_ROWS: usize = 20;
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace) + 4.;
+    // 결과가 바뀌어 범위를 벗어났으면 처음으로
+    if cursor.0 >= rows.len() || cursor.1 >= columns.len() {
+        *cursor = (0, 0);
+    }
+
+    let summary = format!("{} rows, {} columns", rows.len(), columns.len());
+    let (rect, mut response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), row_height),
+        egui::Sense::focusable_noninteractive(),
+    );
+    let mut scroll_to = None;
+    if response.has_focus() && !rows.is_empty() {
+        ui.memory_mut(|m| {
+            m.set_focus_lock_filter(
+                response.id,
+                egui::EventFilter {
+                    horizontal_arrows: true,
+                    vertical_arrows: true,
+                    ..Default::default()
+                },
+            )
+        });
+        let (row, col) = *cursor;
+        let (last_row, last_col) = (rows.len() - 1, columns.len().saturating_sub(1));
+        let next = ui.input(|i| {
+            use egui::Key;
+            if i.key_pressed(Key::ArrowDown) {
+                ((row + 1).min(last_row), col)
+            } else if i.key_pressed(Key::ArrowUp) {
+                (row.saturating_sub(1), col)
+            } else if i.key_pressed(Key::ArrowRight) {
+                (row, (col + 1).min(last_col))
+            } else if i.key_pressed(Key::ArrowLeft) {
+                (row, col.saturating_sub(1))
+            } else if i.key_pressed(Key::PageDown) {
+                ((row + PAGE_ROWS).min(last_row), col)
+            } else if i.key_pressed(Key::PageUp) {
+                (row.saturating_sub(PAGE_ROWS), col)
+            } else if i.key_pressed(Key::Home) {
+                (0, col)
+            } else if i.key_pressed(Key::End) {
+                (last_row, col)
+            } else {
+                (row, col)
+            }
+        });
+        if next != *cursor {
+            *cursor = next;
+            response.mark_changed();
+            scroll_to = Some(next.0);
+        }
+    }
+    let announcement = match rows.get(cursor.0) {
+        Some(row) if !columns.is_empty() => format!(
+            "Row {} of {}, {}: {}",
+            cursor.0 + 1,
+            rows.len(),
+            columns[cursor.1],
+            row[cursor.1]
+        ),
+        _ => summary.clone(),
+    };
+    response
+        .widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Other, true, &announcement));
+    let visuals = ui.style().interact(&response);
+    ui.painter().text(
+        rect.left_center(),
+        egui::Align2::LEFT_CENTER,
+        if response.has_focus() {
+            &announcement
+        } else {
+            &summary
+        },
+        egui::FontId::proportional(12.),
+        visuals.text_color(),
+    );
+    if response.has_focus() {
+        ui.painter().rect_stroke(
+            rect,
+            2.,
+            ui.visuals().selection.stroke,
+            egui::StrokeKind::Inside,
+        );
+    }
+
+    let mut table = TableBuilder::new(ui)
+        .id_salt("result_table")
+        .striped(true)
+        .resizable(true)
+        .columns(Column::auto().at_least(40.), columns.len());
+    if let Some(row) = scroll_to {
+        table = table.scroll_to_row(row, None);
+    }
+    table
+        .header(row_height, |mut header| {
+            for column in columns {
+                header.col(|ui| {
+                    ui.strong(column);
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(row_height, rows.len(), |mut row| {
+                let idx = row.index();
+                row.set_selected(idx == cursor.0);
+                for (col, cell) in rows[idx].iter().enumerate() {
+                    row.col(|ui| {
+                        let text = egui::RichText::new(cell).monospace();
+                        if (idx, col) == *cursor {
+                            ui.label(text.strong().underline());
+                        } else {
+                            ui.label(text);
+                        }
+                    });
+                }
+            });
+        });
+}
+
+/// 연산자별 실행 시간을 플레임 그래프처럼 그림 (가로: 시간, 세로: 중첩 깊이)
+fn draw_profile(profile: &Profile, ui: &mut egui::Ui) {
+    const ROW_HEIGHT: f32 = 20.;
+    let total = profile.total().as_secs_f64().max(f64::EPSILON);
+    let depth = profile.spans.iter().map(|s| s.depth + 1).max().unwrap_or(0);
+    let size = egui::vec2(ui.available_width(), depth as f32 * ROW_HEIGHT);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    for span in &profile.spans {
+        let x = rect.left() + (span.start.as_secs_f64() / total) as f32 * rect.width();
+        let width = (span.elapsed().as_secs_f64() / total) as f32 * rect.width();
+        let bar = egui::Rect::from_min_size(
+            egui::pos2(x, rect.top() + span.depth as f32 * ROW_HEIGHT),
+            egui::vec2(width.max(1.), ROW_HEIGHT - 2.),
+        );
+        let green = 180u8.saturating_sub(span.depth.min(6) as u8 * 30);
+        painter.rect_filled(bar, 2., Color32::from_rgb(240, green, 60));
+        painter.with_clip_rect(bar.intersect(rect)).text(
+            bar.left_center() + egui::vec2(4., 0.),
+            egui::Align2::LEFT_CENTER,
+            format!(
+                "{} ({:.3} ms)",
+                span.name,
+                span.elapsed().as_secs_f64() * 1000.
+            ),
+            egui::FontId::monospace(12.),
+            Color32::BLACK,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::DataType;
+
+    #[test]
+    fn test_parse_delimited() {
+        assert_eq!(
+            parse_delimited("a\tb\r\n1\t\"x\ty\"\n"),
+            vec![vec!["a", "b"], vec!["1", "x\ty"]]
+        );
+        assert_eq!(
+            parse_delimited("1,\"say \"\"hi\"\"\",\n\n2,\"two\nlines\",z"),
+            vec![vec!["1", "say \"hi\"", ""], vec!["2", "two\nlines", "z"]]
+        );
+    }
+
+    #[test]
+    fn test_paste_preview() {
+        let schema = vec![
+            ColumnInfo {
+                name: "name".into(),
+                data_type: DataType::String,
+            },
+            ColumnInfo {
+                name: "age".into(),
+                data_type: DataType::Int,
+            },
+        ];
+        let paste = Paste::new(
+            "friends".into(),
+            schema.clone(),
+            "age\tname\n18\tAl'pha\n\tBeta\n",
+        )
+        .unwrap();
+        assert!(paste.is_valid());
+        assert_eq!(
+            paste.to_sql(),
+            "INSERT INTO friends (age, name) VALUES (18, 'Al\\'pha'), (NULL, 'Beta');"
+        );
+
+        let paste = Paste::new("friends".into(), schema.clone(), "Alpha\tyoung\nBeta\n").unwrap();
+        assert!(!paste.is_valid());
+        assert!(matches!(&paste.rows[0], Ok(cells) if cells[1].is_err()));
+        assert!(paste.rows[1].is_err());
+
+        // 키워드인 이름은 INSERT에 그대로 쓸 수 없음
+        assert!(Paste::new("select".into(), schema, "Alpha\t1\n").is_err());
+        let keyword_column = vec![ColumnInfo {
+            name: "end".into(),
+            data_type: DataType::Int,
+        }];
+        assert!(Paste::new("friends".into(), keyword_column, "1\n").is_err());
+    }
+
+    #[test]
+    fn test_paste_into_session_tables() {
+        let mut exe = Executor::new();
+        exe.run("CREATE TEMP TABLE scratch (n INT);".into());
+        let mut app = Application::with_executor(exe);
+        assert!(app.exe.tables().iter().any(|t| t.name == "scratch"));
+        app.paste_target = "scratch".into();
+        app.start_paste("n\n1\n2\n");
+        let paste = app.paste.take().unwrap();
+        assert!(matches!(app.exe.run(paste.to_sql()), QueryResult::Success));
+    }
 }
@@ -0,0 +1,204 @@
+use eframe::egui;
+use egui::Color32;
+
+/// 이름을 붙여 고정해 둔 쿼리 결과
+struct Snapshot {
+    name: String,
+    query: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// 고정해 둔 결과들과, 지금 결과와 비교 중인 스냅숏
+#[derive(Default)]
+pub struct Snapshots {
+    list: Vec<Snapshot>,
+    // 새로 고정할 스냅숏의 이름
+    name: String,
+    compare: Option<usize>,
+}
+
+impl Snapshots {
+    /// 결과를 고정하거나 비교할 스냅숏을 고르는 줄을 그리고,
+    /// 비교 중이면 차이를 그린 뒤 true를 돌려줌 (false면 결과를 그대로 그리면 됨)
+    pub fn show(
+        &mut self,
+        query: &str,
+        columns: &[String],
+        rows: &[Vec<String>],
+        ui: &mut egui::Ui,
+    ) -> bool {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.name)
+                    .hint_text("Snapshot name")
+                    .desired_width(120.),
+            );
+            let pin = ui.add_enabled(!self.name.is_empty(), egui::Button::new("Pin"));
+            if pin.clicked() {
+                // 같은 이름이면 새 결과로 바꿈
+                self.list.retain(|s| s.name != self.name);
+                self.list.push(Snapshot {
+                    name: std::mem::take(&mut self.name),
+                    query: query.to_string(),
+                    columns: columns.to_vec(),
+                    rows: rows.to_vec(),
+                });
+                self.compare = None;
+            }
+            if self.list.is_empty() {
+                return;
+            }
+            let selected = self
+                .compare
+                .map_or("(none)", |i| self.list[i].name.as_str());
+            egui::ComboBox::from_label("Compare with")
+                .selected_text(selected.to_string())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.compare, None, "(none)");
+                    for (i, snapshot) in self.list.iter().enumerate() {
+                        ui.selectable_value(&mut self.compare, Some(i), &snapshot.name)
+                            .on_hover_text(&snapshot.query);
+                    }
+                });
+        });
+        let Some(snapshot) = self.compare.map(|i| &self.list[i]) else {
+            return false;
+        };
+        if snapshot.columns != columns {
+            ui.colored_label(
+                Color32::YELLOW,
+                format!("'{}' has different columns.", snapshot.name),
+            );
+            return false;
+        }
+        draw_diff(columns, &diff_rows(&snapshot.rows, rows), ui);
+        true
+    }
+}
+
+/// 스냅숏과 비교한 현재 결과의 행 하나
+#[derive(Debug, PartialEq)]
+enum RowDiff<'a> {
+    Same(&'a [String]),
+    Added(&'a [String]),
+    Removed(&'a [String]),
+    // 첫 번째 컬럼(키)은 같지만 다른 값이 바뀐 행 (before, after)
+    Changed(&'a [String], &'a [String]),
+}
+
+/// 두 결과를 행 단위로 비교 (현재 결과의 순서대로, 없어진 행은 맨 뒤에)
+///
+/// 완전히 같은 행을 먼저 짝짓고, 남은 행 중 첫 번째 컬럼이 같은 행은 바뀐 것으로 봅니다.
+fn diff_rows<'a>(before: &'a [Vec<String>], after: &'a [Vec<String>]) -> Vec<RowDiff<'a>> {
+    let mut matched = vec![false; before.len()];
+    let same: Vec<bool> = after
+        .iter()
+        .map(
+            |row| match (0..before.len()).find(|&i| !matched[i] && before[i] == *row) {
+                Some(idx) => {
+                    matched[idx] = true;
+                    true
+                }
+                None => false,
+            },
+        )
+        .collect();
+    let mut out: Vec<RowDiff> = after
+        .iter()
+        .zip(&same)
+        .map(|(row, same)| {
+            if *same {
+                return RowDiff::Same(row);
+            }
+            let key = row.first();
+            match (0..before.len()).find(|&i| !matched[i] && before[i].first() == key) {
+                Some(idx) => {
+                    matched[idx] = true;
+                    RowDiff::Changed(&before[idx], row)
+                }
+                None => RowDiff::Added(row),
+            }
+        })
+        .collect();
+    out.extend(
+        before
+            .iter()
+            .zip(&matched)
+            .filter(|(_, matched)| !**matched)
+            .map(|(row, _)| RowDiff::Removed(row)),
+    );
+    out
+}
+
+/// 추가된 행은 초록색, 없어진 행은 빨간색, 바뀐 셀은 노란색 (마우스를 올리면 이전 값)
+fn draw_diff(columns: &[String], diff: &[RowDiff], ui: &mut egui::Ui) {
+    egui::Grid::new("diff_grid").striped(true).show(ui, |ui| {
+        ui.strong("");
+        for column in columns {
+            ui.strong(column);
+        }
+        ui.end_row();
+        for row in diff {
+            match row {
+                RowDiff::Same(cells) => {
+                    ui.label("");
+                    for cell in *cells {
+                        ui.label(cell);
+                    }
+                }
+                RowDiff::Added(cells) => {
+                    ui.colored_label(Color32::GREEN, "+");
+                    for cell in *cells {
+                        ui.colored_label(Color32::GREEN, cell);
+                    }
+                }
+                RowDiff::Removed(cells) => {
+                    ui.colored_label(Color32::RED, "-");
+                    for cell in *cells {
+                        ui.colored_label(Color32::RED, cell);
+                    }
+                }
+                RowDiff::Changed(before, after) => {
+                    ui.colored_label(Color32::YELLOW, "~");
+                    for (old, new) in before.iter().zip(*after) {
+                        if old == new {
+                            ui.label(new);
+                        } else {
+                            ui.colored_label(Color32::YELLOW, new)
+                                .on_hover_text(format!("was: {}", old));
+                        }
+                    }
+                }
+            }
+            ui.end_row();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|c| c.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_rows() {
+        let before = rows(&[&["1", "a"], &["2", "b"], &["3", "c"], &["3", "c"]]);
+        let after = rows(&[&["3", "c"], &["2", "B"], &["4", "d"], &["1", "a"]]);
+        assert_eq!(
+            diff_rows(&before, &after),
+            vec![
+                RowDiff::Same(&after[0]),
+                RowDiff::Changed(&before[1], &after[1]),
+                RowDiff::Added(&after[2]),
+                RowDiff::Same(&after[3]),
+                RowDiff::Removed(&before[3]),
+            ]
+        );
+    }
+}
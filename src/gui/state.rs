@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// 다음에 GUI를 열 때 되살릴 상태 (앱 데이터 디렉터리의 gui_state.json)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuiState {
+    // 창의 위치와 크기 [x, y, width, height]
+    pub window: Option<[f32; 4]>,
+    // 열려 있던 쿼리 탭의 내용과, 그중 고른 탭
+    pub tabs: Vec<String>,
+    pub active_tab: usize,
+    pub database: Option<PathBuf>,
+}
+
+impl GuiState {
+    /// $XDG_DATA_HOME/squirrel/gui_state.json (없으면 ~/.local/share 아래)
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = match std::env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => Path::new(&std::env::var_os("HOME")?).join(".local/share"),
+        };
+        Some(dir.join("squirrel").join("gui_state.json"))
+    }
+
+    /// 저장된 상태를 읽음 (파일이 없거나 깨졌으면 기본값)
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("squirrel-gui-{}", std::process::id()))
+            .join("gui_state.json");
+        assert_eq!(GuiState::load(&path), GuiState::default());
+
+        let state = GuiState {
+            window: Some([10., 20., 800., 600.]),
+            tabs: vec!["SELECT 1;".into(), "-- 메모\n".into()],
+            active_tab: 1,
+            database: Some("shop.sq".into()),
+        };
+        state.save(&path).unwrap();
+        assert_eq!(GuiState::load(&path), state);
+
+        // 깨진 파일은 무시
+        fs::write(&path, "{ nope").unwrap();
+        assert_eq!(GuiState::load(&path), GuiState::default());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,7 @@
+pub mod config;
+pub mod executor;
+pub mod gui;
+pub mod query;
+pub mod repl;
+pub mod storage;
+pub mod var_char;
@@ -1,65 +1,222 @@
-pub mod executor;
-pub mod gui;
-pub mod query;
-pub mod storage;
-pub mod var_char;
+use clap::{Parser, Subcommand};
+use squirrel::{config, executor, gui, repl, storage};
+use std::path::{Path, PathBuf};
 
-use clap::Parser;
-use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
-
-/// Simple program to greet a person
+/// A small SQL database with a REPL and a GUI
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
-    #[arg(value_name = "DATABASE NAME")]
-    database: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Config file to use instead of ~/.squirrelrc
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run SQL statements interactively in the terminal
+    ///
+    /// Queries run on in-memory tables: the database is only checked and used
+    /// to name the session, and nothing is saved to it when the REPL exits.
+    Repl {
+        /// Database to check and name the session after (not written to)
+        #[arg(value_name = "DATABASE NAME")]
+        database: PathBuf,
+        /// Create the database first if it does not exist
+        #[arg(long)]
+        create: bool,
+        /// Prompt string (overrides the config file)
+        #[arg(long)]
+        prompt: Option<String>,
+        /// Output mode (overrides the config file)
+        #[arg(long, value_enum)]
+        mode: Option<repl::Mode>,
+    },
+    /// Initialize a new empty database at the given path
+    Create {
+        #[arg(value_name = "DATABASE NAME")]
+        database: PathBuf,
+    },
+    /// Migrate a database written by an older version to the current storage format
+    Upgrade {
+        #[arg(value_name = "DATABASE NAME")]
+        database: PathBuf,
+    },
+    /// Open the graphical editor (default when no command is given)
+    ///
+    /// Like the REPL, queries run on in-memory tables and are not saved to the database.
+    Gui {
+        /// Database to check and show in the title (defaults to the one open when the GUI
+        /// was last closed; not written to)
+        #[arg(value_name = "DATABASE NAME")]
+        database: Option<PathBuf>,
+    },
 }
 
 fn main() {
     let args = Args::parse();
-    // 데이터베이스 유무 체크
-    if let None = args.database {
-        launch_gui();
-        return;
-    } else if let Some(ref path) = args.database
-        && !path.exists()
-    {
-        eprintln!("Database file not found: '{}'", path.display());
-        return;
+    let mut config = match config::Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    match args.command {
+        Some(Command::Repl {
+            database,
+            create,
+            prompt,
+            mode,
+        }) => {
+            if create && !database.exists() && !create_database(&database) {
+                return;
+            }
+            if let Some(prompt) = prompt {
+                config.prompt = prompt;
+            }
+            if let Some(mode) = mode {
+                config.mode = mode;
+            }
+            run_repl(&database, &config)
+        }
+        Some(Command::Create { database }) => {
+            if !create_database(&database) {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Upgrade { database }) => {
+            if !upgrade_database(&database) {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Gui { database }) => launch_gui(database, &config),
+        None => launch_gui(None, &config),
     }
-    // ! 위 조건문에서 확인했으므로 안전
-    let path = args.database.unwrap();
-    let mut exec = executor::Executor::new();
-    println!("SQuirreL REPL (type '.exit' or '.quit' to stop)");
-    let mut buffer = String::new();
-    loop {
-        if buffer.is_empty() {
-            print!("sql> ");
-        } else {
-            print!("...  ");
+}
+
+/// 새 데이터베이스를 만들고 성공 여부를 돌려줌
+fn create_database(path: &Path) -> bool {
+    let result = tokio::runtime::Runtime::new()
+        .and_then(|runtime| runtime.block_on(storage::create_database(path)));
+    match result {
+        Ok(()) => {
+            println!("Created database '{}'", path.display());
+            true
+        }
+        Err(e) => {
+            eprintln!("Failed to create database '{}': {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// 데이터베이스를 현재 저장 형식으로 올리고 성공 여부를 돌려줌
+fn upgrade_database(path: &Path) -> bool {
+    let result = tokio::runtime::Runtime::new()
+        .and_then(|runtime| runtime.block_on(storage::upgrade_database(path)));
+    match result {
+        Ok(Some(from)) => {
+            println!(
+                "Upgraded '{}' from format {} to {}",
+                path.display(),
+                from,
+                storage::FORMAT_VERSION
+            );
+            true
+        }
+        Ok(None) => {
+            println!(
+                "'{}' is already at format {}",
+                path.display(),
+                storage::FORMAT_VERSION
+            );
+            true
         }
-        io::stdout().flush().unwrap();
-        let line = io::stdin().lock().lines().next();
-        if let Some(Ok(input)) = line {
-            // 종료 명령어 처리
-            if input.trim() == ".exit" || input.trim() == ".quit" {
-                break;
-            } else if !input.trim().ends_with(";") {
-                buffer.push_str(&input);
-                buffer.push('\n');
-            } else {
-                buffer.push_str(&input);
-                let src = std::mem::take(&mut buffer);
-                println!("{}", src);
-                exec.run(src);
+        Err(e) => {
+            eprintln!("Failed to upgrade database '{}': {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// 이 버전이 열 수 있는 데이터베이스인지 확인 (예전 형식은 열 수 있으므로 업그레이드를 안내만 함)
+fn check_database(path: &Path) -> bool {
+    let result = tokio::runtime::Runtime::new()
+        .and_then(|runtime| runtime.block_on(storage::open_database(path)));
+    match result {
+        Ok(found) => {
+            if found.format < storage::FORMAT_VERSION {
+                eprintln!(
+                    "'{}' uses storage format {}; run 'SQiurreL upgrade {}' to update it",
+                    path.display(),
+                    found.format,
+                    path.display()
+                );
             }
-        } else {
-            println!("Failed to read line.");
+            true
         }
+        Err(e) => {
+            eprintln!("Failed to open database '{}': {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// 설정 파일의 PRAGMA를 적용한 실행기를 만듦
+fn open_executor(config: &config::Config) -> Option<executor::Executor> {
+    let mut exec = executor::Executor::new();
+    match config.apply(&mut exec) {
+        Ok(()) => Some(exec),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
+fn run_repl(path: &Path, config: &config::Config) {
+    // 데이터베이스 유무 체크
+    if !path.exists() {
+        eprintln!(
+            "Database file not found: '{}' (use --create to make a new one)",
+            path.display()
+        );
+        return;
+    }
+    if !check_database(path) {
+        return;
     }
+    let Some(exec) = open_executor(config) else {
+        return;
+    };
+    // 쿼리는 아직 저장소가 아니라 메모리의 테이블에서 실행됨
+    println!(
+        "Note: tables live in memory for this session; changes are not saved to '{}'",
+        path.display()
+    );
+    repl::Repl::new(exec, config.render_prompt(path), config.mode).run();
 }
 
-fn launch_gui() {
-    gui::Application::new().launch();
+fn launch_gui(database: Option<PathBuf>, config: &config::Config) {
+    if let Some(path) = &database
+        && !path.exists()
+    {
+        eprintln!(
+            "Database file not found: '{}' (use 'create' to make a new one)",
+            path.display()
+        );
+        return;
+    }
+    if let Some(path) = &database
+        && !check_database(path)
+    {
+        return;
+    }
+    if let Some(exec) = open_executor(config) {
+        gui::Application::with_executor(exec)
+            .with_row_limit(config.gui_row_limit)
+            .launch(database);
+    }
 }
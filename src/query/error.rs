@@ -9,8 +9,40 @@ pub enum QueryErr {
     UnterminatedText,
     InvalidIdent(String),
     InvalidToken(char),
-    UnexpectedToken { expected: String, found: String },
+    UnexpectedToken {
+        expected: String,
+        found: String,
+    },
     InvalidExpr(String),
+    InvalidParam(String),
+    // 오류가 난 곳 (1부터 시작하는 줄, 열)
+    At {
+        line: usize,
+        column: usize,
+        err: Box<QueryErr>,
+    },
+}
+
+impl QueryErr {
+    /// 오류에 위치를 붙임 (이미 위치가 있으면 그대로 둠)
+    pub fn at(self, (line, column): (usize, usize)) -> Self {
+        match self {
+            Self::At { .. } => self,
+            err => Self::At {
+                line,
+                column,
+                err: Box::new(err),
+            },
+        }
+    }
+
+    /// 위치가 있으면 (줄, 열)
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::At { line, column, .. } => Some((*line, *column)),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for QueryErr {
@@ -25,6 +57,10 @@ impl fmt::Display for QueryErr {
                 write!(f, "Expected {}, but found {}", expected, found)
             }
             Self::InvalidExpr(e) => write!(f, "Invalid expression: {}", e),
+            Self::InvalidParam(p) => write!(f, "Invalid parameter: '{}'", p),
+            Self::At { line, column, err } => {
+                write!(f, "line {}, column {}: {}", line, column, err)
+            }
         }
     }
 }
@@ -0,0 +1,722 @@
+use super::error::Result;
+use super::lexer::{Lexer, Token};
+use super::parser::{
+    AlterAction, Expr, JoinKind, Order, Parser, SetOp, Source, Stmt, TriggerEvent,
+};
+use std::fmt;
+
+/// 쿼리를 정리된 SQL로 다시 씀 (문장마다 `;`으로 끝나고 빈 줄로 나뉨)
+///
+/// AST에서 다시 만들므로 주석은 남지 않습니다.
+pub fn format_sql(src: &str) -> Result<String> {
+    let stmts = Parser::new(Lexer::new(src))?.parse()?;
+    let stmts: Vec<String> = stmts.iter().map(|stmt| format!("{:#};", stmt)).collect();
+    Ok(stmts.join("\n\n"))
+}
+
+/// 다시 파싱하면 같은 AST가 되는 SQL (`{:#}`면 절마다 줄을 바꿈)
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut w = SqlWriter::new(f.alternate());
+        w.stmt(self);
+        f.write_str(&w.out)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut w = SqlWriter::new(f.alternate());
+        w.expr(self, 0);
+        f.write_str(&w.out)
+    }
+}
+
+// 괄호 없이 쓸 수 있는지 판단할 때 쓰는 식의 우선순위 (파서의 중위 연산자 우선순위와 같음)
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Binary { op, .. } => Parser::precedence(op),
+        Expr::Between { .. } | Expr::IsNull { .. } => Parser::precedence(&Token::Between),
+        Expr::Unary { .. } => 7,
+        _ => 8,
+    }
+}
+
+struct SqlWriter {
+    out: String,
+    // 절마다 줄을 바꾸고 괄호 안의 쿼리를 들여씀
+    pretty: bool,
+    indent: usize,
+}
+
+impl SqlWriter {
+    fn new(pretty: bool) -> Self {
+        Self {
+            out: String::new(),
+            pretty,
+            indent: 0,
+        }
+    }
+
+    fn push(&mut self, s: &str) {
+        self.out.push_str(s);
+    }
+
+    // 한 줄 모드에서는 공백, 아니면 줄 바꿈
+    fn clause(&mut self) {
+        if self.pretty {
+            self.out.push('\n');
+            self.out.push_str(&"  ".repeat(self.indent));
+        } else {
+            self.out.push(' ');
+        }
+    }
+
+    fn list<T>(&mut self, items: &[T], mut item: impl FnMut(&mut Self, &T)) {
+        for (i, x) in items.iter().enumerate() {
+            if i > 0 {
+                self.push(", ");
+            }
+            item(self, x);
+        }
+    }
+
+    fn exprs(&mut self, exprs: &[Expr]) {
+        self.list(exprs, |w, e| w.expr(e, 0));
+    }
+
+    fn names(&mut self, names: &[Box<str>]) {
+        self.list(names, |w, name| w.push(name));
+    }
+
+    // (<items>), 여러 줄 모드에서는 한 줄에 하나씩
+    fn block<T>(&mut self, items: &[T], mut item: impl FnMut(&mut Self, &T)) {
+        if !self.pretty {
+            self.push("(");
+            self.list(items, item);
+            self.push(")");
+            return;
+        }
+        self.push("(");
+        self.indent += 1;
+        for (i, x) in items.iter().enumerate() {
+            if i > 0 {
+                self.push(",");
+            }
+            self.clause();
+            item(self, x);
+        }
+        self.indent -= 1;
+        self.clause();
+        self.push(")");
+    }
+
+    // 괄호 안의 쿼리 (서브쿼리, CTE)
+    fn subquery(&mut self, stmt: &Stmt) {
+        self.push("(");
+        if self.pretty {
+            self.indent += 1;
+            self.clause();
+            self.stmt(stmt);
+            self.indent -= 1;
+            self.clause();
+        } else {
+            self.stmt(stmt);
+        }
+        self.push(")");
+    }
+
+    fn source(&mut self, source: &Source, alias: &Option<Box<str>>) {
+        match source {
+            Source::Table(name) => self.push(name),
+            Source::Subquery(stmt) => self.subquery(stmt),
+        }
+        if let Some(alias) = alias {
+            self.push(" AS ");
+            self.push(alias);
+        }
+    }
+
+    fn order_by(&mut self, order_by: &[(Expr, Order)]) {
+        self.list(order_by, |w, (expr, order)| {
+            w.expr(expr, 0);
+            if !order.asc {
+                w.push(" DESC");
+            }
+            // 기본값(ASC면 NULLS LAST, DESC면 NULLS FIRST)과 다를 때만
+            if order.nulls_first == order.asc {
+                w.push(if order.nulls_first {
+                    " NULLS FIRST"
+                } else {
+                    " NULLS LAST"
+                });
+            }
+        });
+    }
+
+    fn tail(
+        &mut self,
+        order_by: &Option<Vec<(Expr, Order)>>,
+        limit: Option<u64>,
+        offset: Option<u64>,
+    ) {
+        if let Some(order_by) = order_by {
+            self.clause();
+            self.push("ORDER BY ");
+            self.order_by(order_by);
+        }
+        if let Some(limit) = limit {
+            self.clause();
+            self.push(&format!("LIMIT {}", limit));
+            if let Some(offset) = offset {
+                self.push(&format!(" OFFSET {}", offset));
+            }
+        }
+    }
+
+    fn rows(&mut self, rows: &[Vec<Expr>]) {
+        self.push("VALUES ");
+        self.list(rows, |w, row| {
+            w.push("(");
+            w.exprs(row);
+            w.push(")");
+        });
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Create {
+                table,
+                columns,
+                foreign_keys,
+                if_not_exists,
+                temporary,
+            } => {
+                self.push("CREATE ");
+                if *temporary {
+                    self.push("TEMP ");
+                }
+                self.push("TABLE ");
+                if *if_not_exists {
+                    self.push("IF NOT EXISTS ");
+                }
+                self.push(table);
+                self.push(" ");
+                // 컬럼에 붙인 REFERENCES도 테이블 제약으로 씀
+                let defs: Vec<String> = columns
+                    .iter()
+                    .map(|(name, ty)| format!("{} {}", name, ty))
+                    .chain(foreign_keys.iter().map(|fk| {
+                        format!(
+                            "FOREIGN KEY ({}) REFERENCES {} ({})",
+                            fk.columns.join(", "),
+                            fk.table,
+                            fk.ref_columns.join(", ")
+                        )
+                    }))
+                    .collect();
+                self.block(&defs, |w, def| w.push(def));
+            }
+            Stmt::CreateTrigger {
+                name,
+                event,
+                table,
+                body,
+                if_not_exists,
+            } => {
+                self.push("CREATE TRIGGER ");
+                if *if_not_exists {
+                    self.push("IF NOT EXISTS ");
+                }
+                let event = match event {
+                    TriggerEvent::Insert => "INSERT",
+                    TriggerEvent::Update => "UPDATE",
+                };
+                self.push(&format!("{} AFTER {} ON {}", name, event, table));
+                self.clause();
+                self.push("BEGIN");
+                self.indent += 1;
+                for stmt in body {
+                    self.clause();
+                    self.stmt(stmt);
+                    self.push(";");
+                }
+                self.indent -= 1;
+                self.clause();
+                self.push("END");
+            }
+            Stmt::InsertValues {
+                table,
+                columns,
+                values,
+            } => {
+                self.insert_into(table, columns);
+                self.clause();
+                self.rows(values);
+            }
+            Stmt::InsertQuery {
+                table,
+                columns,
+                query,
+            } => {
+                self.insert_into(table, columns);
+                self.clause();
+                self.stmt(query);
+            }
+            Stmt::Values(rows) => self.rows(rows),
+            Stmt::Select {
+                table,
+                alias,
+                sample,
+                joins,
+                columns,
+                distinct,
+                where_clause,
+                group_by,
+                having,
+                order_by,
+                limit,
+                offset,
+            } => {
+                self.push("SELECT ");
+                match distinct.as_deref() {
+                    None => {}
+                    Some([]) => self.push("DISTINCT "),
+                    Some(on) => {
+                        self.push("DISTINCT ON (");
+                        self.exprs(on);
+                        self.push(") ");
+                    }
+                }
+                if columns.is_empty() {
+                    self.push("*");
+                } else {
+                    self.exprs(columns);
+                }
+                self.clause();
+                self.push("FROM ");
+                self.source(table, alias);
+                if let Some((method, percent)) = sample {
+                    self.push(&format!(" TABLESAMPLE {} (", method));
+                    self.expr(percent, 0);
+                    self.push(")");
+                }
+                for join in joins {
+                    self.clause();
+                    self.push(match join.kind {
+                        JoinKind::Inner => "JOIN ",
+                        JoinKind::Left => "LEFT JOIN ",
+                        JoinKind::Right => "RIGHT JOIN ",
+                        JoinKind::Full => "FULL JOIN ",
+                        JoinKind::Cross => "CROSS JOIN ",
+                    });
+                    self.source(&join.table, &join.alias);
+                    if join.kind != JoinKind::Cross {
+                        self.push(" ON ");
+                        self.expr(&join.on, 0);
+                    }
+                }
+                if let Some(cond) = where_clause {
+                    self.clause();
+                    self.push("WHERE ");
+                    self.expr(cond, 0);
+                }
+                if let Some(sets) = group_by {
+                    self.clause();
+                    self.push("GROUP BY ");
+                    match &sets[..] {
+                        [set] => self.exprs(set),
+                        // ROLLUP, CUBE도 풀어 둔 GROUPING SETS로 씀
+                        sets => {
+                            self.push("GROUPING SETS (");
+                            self.list(sets, |w, set| {
+                                w.push("(");
+                                w.exprs(set);
+                                w.push(")");
+                            });
+                            self.push(")");
+                        }
+                    }
+                }
+                if let Some(cond) = having {
+                    self.clause();
+                    self.push("HAVING ");
+                    self.expr(cond, 0);
+                }
+                self.tail(order_by, *limit, *offset);
+            }
+            Stmt::Compound {
+                op,
+                all,
+                left,
+                right,
+                order_by,
+                limit,
+                offset,
+            } => {
+                self.stmt(left);
+                self.clause();
+                self.push(match op {
+                    SetOp::Union => "UNION",
+                    SetOp::Intersect => "INTERSECT",
+                    SetOp::Except => "EXCEPT",
+                });
+                if *all {
+                    self.push(" ALL");
+                }
+                self.clause();
+                self.stmt(right);
+                self.tail(order_by, *limit, *offset);
+            }
+            Stmt::With { ctes, body } => {
+                self.push("WITH ");
+                self.list(ctes, |w, (name, query)| {
+                    w.push(name);
+                    w.push(" AS ");
+                    w.subquery(query);
+                });
+                self.clause();
+                self.stmt(body);
+            }
+            Stmt::Update {
+                table,
+                assigns,
+                from,
+                where_clause,
+            } => {
+                self.push(&format!("UPDATE {} SET ", table));
+                self.list(assigns, |w, (column, value)| {
+                    w.push(column);
+                    w.push(" = ");
+                    w.expr(value, 0);
+                });
+                if let Some((source, alias)) = from {
+                    self.clause();
+                    self.push("FROM ");
+                    self.source(source, alias);
+                }
+                if let Some(cond) = where_clause {
+                    self.clause();
+                    self.push("WHERE ");
+                    self.expr(cond, 0);
+                }
+            }
+            Stmt::Alter { table, actions } => {
+                self.push(&format!("ALTER TABLE {} ", table));
+                self.list(actions, |w, action| match action {
+                    AlterAction::AddColumn(name, ty) => {
+                        w.push(&format!("ADD COLUMN {} {}", name, ty))
+                    }
+                    AlterAction::DropColumn(name) => w.push(&format!("DROP COLUMN {}", name)),
+                    AlterAction::RenameTo(name) => w.push(&format!("RENAME TO {}", name)),
+                });
+            }
+            Stmt::Delete {
+                table,
+                where_clause,
+            } => {
+                self.push(&format!("DELETE FROM {}", table));
+                if let Some(cond) = where_clause {
+                    self.clause();
+                    self.push("WHERE ");
+                    self.expr(cond, 0);
+                }
+            }
+            Stmt::Truncate { table } => self.push(&format!("TRUNCATE TABLE {}", table)),
+            Stmt::Drop {
+                table,
+                if_exists,
+                cascade,
+            } => {
+                self.push("DROP TABLE ");
+                if *if_exists {
+                    self.push("IF EXISTS ");
+                }
+                self.push(table);
+                if *cascade {
+                    self.push(" CASCADE");
+                }
+            }
+            Stmt::Pragma { name, value } => {
+                self.push(&format!("PRAGMA {}", name));
+                if let Some(value) = value {
+                    self.push(" = ");
+                    self.expr(value, 0);
+                }
+            }
+            Stmt::Listen { channel } => self.push(&format!("LISTEN {}", channel)),
+            Stmt::Notify { channel, payload } => {
+                self.push(&format!("NOTIFY {}", channel));
+                if !payload.is_empty() {
+                    self.push(", ");
+                    self.push(&quote(payload));
+                }
+            }
+            Stmt::Explain { stmt, json } => {
+                self.push(if *json {
+                    "EXPLAIN (PROFILE, FORMAT JSON)"
+                } else {
+                    "EXPLAIN (PROFILE)"
+                });
+                self.clause();
+                self.stmt(stmt);
+            }
+            Stmt::Copy {
+                table,
+                path,
+                export,
+                format,
+            } => self.push(&format!(
+                "COPY {} {} {} (FORMAT {})",
+                table,
+                if *export { "TO" } else { "FROM" },
+                quote(path),
+                format
+            )),
+            Stmt::Begin => self.push("BEGIN"),
+            Stmt::Commit => self.push("COMMIT"),
+            Stmt::Rollback => self.push("ROLLBACK"),
+        }
+    }
+
+    fn insert_into(&mut self, table: &str, columns: &[Box<str>]) {
+        self.push(&format!("INSERT INTO {}", table));
+        if !columns.is_empty() {
+            self.push(" (");
+            self.names(columns);
+            self.push(")");
+        }
+    }
+
+    /// `min_prec`보다 우선순위가 낮은 식은 괄호로 감쌈
+    fn expr(&mut self, expr: &Expr, min_prec: u8) {
+        if precedence(expr) < min_prec {
+            self.push("(");
+            self.expr(expr, 0);
+            self.push(")");
+            return;
+        }
+        match expr {
+            Expr::Null => self.push("NULL"),
+            Expr::Bool(b) => self.push(if *b { "TRUE" } else { "FALSE" }),
+            Expr::Int(n) => self.push(&n.to_string()),
+            Expr::Float(f) if f.is_finite() => {
+                // 소수점이 없으면 INTEGER로 읽히므로 붙임
+                let mut s = f.to_string();
+                if !s.contains('.') {
+                    s.push_str(".0");
+                }
+                self.push(&s);
+            }
+            // NaN과 무한대는 리터럴이 없음
+            Expr::Float(f) => self.push(&format!("CAST('{}' AS FLOAT)", f)),
+            Expr::Text(s) => self.push(&quote(s)),
+            Expr::Param(n) => self.push(&format!("${}", n)),
+            Expr::Ident(name) => self.push(name),
+            Expr::FunctionCall {
+                name,
+                args,
+                order_by,
+            } => {
+                self.push(name);
+                self.push("(");
+                if args.is_empty() && name.eq_ignore_ascii_case("COUNT") {
+                    self.push("*");
+                } else {
+                    self.exprs(args);
+                }
+                if let Some(order_by) = order_by {
+                    self.push(" ORDER BY ");
+                    self.order_by(order_by);
+                }
+                self.push(")");
+            }
+            Expr::Unary { op, right } => {
+                if *op == Token::Not {
+                    self.push("NOT ");
+                } else {
+                    self.push(&op.to_string());
+                }
+                // `--`는 주석이 되므로 음수 앞의 `-`는 괄호로 나눔
+                let negative = match right.as_ref() {
+                    Expr::Unary { op, .. } => *op == Token::OpSub,
+                    Expr::Int(n) => *n < 0,
+                    Expr::Float(f) => f.is_sign_negative(),
+                    _ => false,
+                };
+                self.expr(right, if negative { 9 } else { 7 });
+            }
+            Expr::Binary { op, left, right } => {
+                // 같은 우선순위는 왼쪽부터 묶이므로 오른쪽은 괄호가 필요함
+                let prec = Parser::precedence(op);
+                self.expr(left, prec);
+                self.push(&format!(" {} ", op));
+                self.expr(right, prec + 1);
+            }
+            Expr::Subquery(stmt) => self.subquery(stmt),
+            Expr::Exists(stmt) => {
+                self.push("EXISTS ");
+                self.subquery(stmt);
+            }
+            Expr::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => {
+                self.expr(expr, 4);
+                self.push(if *negated {
+                    " NOT BETWEEN "
+                } else {
+                    " BETWEEN "
+                });
+                self.expr(low, 5);
+                self.push(" AND ");
+                self.expr(high, 5);
+            }
+            Expr::IsNull { expr, negated } => {
+                self.expr(expr, 4);
+                self.push(if *negated { " IS NOT NULL" } else { " IS NULL" });
+            }
+            Expr::Cast { expr, ty } => {
+                self.push("CAST(");
+                self.expr(expr, 0);
+                self.push(&format!(" AS {})", ty));
+            }
+            Expr::Window {
+                func,
+                partition_by,
+                order_by,
+            } => {
+                self.expr(func, 0);
+                self.push(" OVER (");
+                if !partition_by.is_empty() {
+                    self.push("PARTITION BY ");
+                    self.exprs(partition_by);
+                    if !order_by.is_empty() {
+                        self.push(" ");
+                    }
+                }
+                if !order_by.is_empty() {
+                    self.push("ORDER BY ");
+                    self.order_by(order_by);
+                }
+                self.push(")");
+            }
+        }
+    }
+}
+
+// 렉서가 읽는 이스케이프를 써서 작은따옴표로 감쌈
+fn quote(s: &str) -> String {
+    let mut out = String::from("'");
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(src: &str) -> Vec<Stmt> {
+        Parser::new(Lexer::new(src))
+            .and_then(|mut p| p.parse())
+            .unwrap_or_else(|e| panic!("{}: {}", src, e))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let queries = [
+            "CREATE TEMP TABLE IF NOT EXISTS main.pets (id INTEGER, owner INTEGER REFERENCES users (id), name TEXT, FOREIGN KEY (id, name) REFERENCES tags (a, b))",
+            "CREATE TRIGGER IF NOT EXISTS audit AFTER UPDATE ON t BEGIN INSERT INTO log SELECT OLD.a, NEW.a FROM u WHERE u.id = NEW.id; NOTIFY ch; END",
+            "INSERT INTO t (a, b) VALUES (1, 'it\\'s'), (-2, NULL)",
+            "INSERT INTO t WITH x AS (SELECT a FROM u) SELECT * FROM x",
+            "VALUES (1.5, TRUE), (2.0, FALSE) UNION ALL SELECT a, b FROM t ORDER BY 1 DESC LIMIT 3",
+            "SELECT DISTINCT ON (a, b) a, COUNT(*), SUM(x ORDER BY y DESC NULLS LAST) FROM t AS s TABLESAMPLE BERNOULLI (10) \
+             LEFT OUTER JOIN (SELECT * FROM u) v ON s.id = v.id CROSS JOIN w, z \
+             WHERE NOT a = 1 AND (b < 2 OR c IS NOT NULL) AND d NOT BETWEEN 1 + 1 AND 3 \
+             GROUP BY ROLLUP (a, b) ORDER BY a NULLS FIRST, b DESC LIMIT 10 OFFSET 5",
+            "SELECT DISTINCT a - (b - c), (a - b) - c, a * (b + c), -(-a), - -1, -(a + b), $2, ? FROM t",
+            "SELECT RANK() OVER (PARTITION BY a ORDER BY b DESC), SUM(a) OVER () FROM t WHERE EXISTS (SELECT 1 FROM u) AND a = (SELECT MAX(b) FROM u)",
+            "SELECT CAST(a AS FLOAT), 'line\\nbreak \\\\ \"q\"' FROM t WHERE (a BETWEEN 1 AND 2) = TRUE",
+            "SELECT a FROM t INTERSECT SELECT a FROM u UNION SELECT a FROM v EXCEPT ALL SELECT a FROM w",
+            "SELECT a FROM t UNION SELECT a FROM u INTERSECT SELECT a FROM v",
+            "SELECT * FROM t GROUP BY GROUPING SETS ((a, b), (a), ())",
+            "UPDATE t SET a = a + 1, b = 'x' FROM u AS v WHERE t.id = v.id",
+            "ALTER TABLE t ADD COLUMN c BOOLEAN, DROP COLUMN d",
+            "ALTER TABLE t RENAME TO u",
+            "DELETE FROM t",
+            "TRUNCATE TABLE t",
+            "DROP TABLE IF EXISTS t CASCADE",
+            "PRAGMA autocommit = off",
+            "PRAGMA strict",
+            "LISTEN ch",
+            "NOTIFY ch, 'hello'",
+            "EXPLAIN (PROFILE, FORMAT JSON) SELECT a FROM t",
+            "COPY t TO 'out.ndjson' (FORMAT ndjson)",
+            "BEGIN",
+            "COMMIT TRANSACTION",
+            "ROLLBACK",
+        ];
+        for src in queries {
+            let stmts = parse(src);
+            for stmt in &stmts {
+                for sql in [stmt.to_string(), format!("{:#}", stmt)] {
+                    assert_eq!(
+                        &parse(&sql),
+                        std::slice::from_ref(stmt),
+                        "{}\n=> {}",
+                        src,
+                        sql
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical() {
+        let one_line = |src: &str| parse(src)[0].to_string();
+        assert_eq!(
+            one_line("select a,b from t f where a=1 and not b order by a asc"),
+            "SELECT a, b FROM t AS f WHERE a = 1 AND NOT b ORDER BY a"
+        );
+        assert_eq!(
+            one_line("SELECT (a + b) * c, a + (b * c), (a = b) = c FROM t"),
+            "SELECT (a + b) * c, a + b * c, a = b = c FROM t"
+        );
+        assert_eq!(
+            one_line("SELECT 1.0, 0.000001, 100. FROM t"),
+            "SELECT 1.0, 0.000001, 100.0 FROM t"
+        );
+        assert_eq!(
+            Expr::Float(f64::NEG_INFINITY).to_string(),
+            "CAST('-inf' AS FLOAT)"
+        );
+        assert_eq!(
+            format_sql("select a from (select a from t) s where a > 1; commit").unwrap(),
+            "SELECT a\nFROM (\n  SELECT a\n  FROM t\n) AS s\nWHERE a > 1;\n\nCOMMIT;"
+        );
+        assert_eq!(
+            format_sql("create table t (a int, b text)").unwrap(),
+            "CREATE TABLE t (\n  a INTEGER,\n  b TEXT\n);"
+        );
+        assert_eq!(
+            format_sql(
+                "create trigger log after insert on t begin insert into log values (new.a); end"
+            )
+            .unwrap(),
+            "CREATE TRIGGER log AFTER INSERT ON t\nBEGIN\n  INSERT INTO log\n  VALUES (new.a);\nEND;"
+        );
+        assert!(format_sql("SELECT FROM").is_err());
+    }
+}
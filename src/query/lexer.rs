@@ -1,5 +1,6 @@
 use super::error::{QueryErr, Result};
 use std::collections::VecDeque;
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
@@ -11,6 +12,7 @@ pub enum Token {
     Int(i64),
     Float(f64),
     Text(String),
+    Param(u32), // ?, $1 (1부터 시작하는 파라미터 번호)
     // 타입
     BoolType,  // BOOL, BOOLEAN
     IntType,   // INT, INTEGER
@@ -19,37 +21,71 @@ pub enum Token {
     // 식별자
     Ident(String),
     // 키워드
-    Create,   // CREATE
-    Table,    // TABLE
-    If,       // IF
-    Exists,   // EXISTS
-    Insert,   // INSERT
-    Into,     // INTO
-    Values,   // VALUES
-    Select,   // SELECT
-    Distinct, // DISTINCT
-    From,     // FROM
-    Where,    // WHERE
-    Group,    // GROUP
-    By,       // BY
-    Having,   // HAVING
-    Order,    // ORDER
-    Asc,      // ASC
-    Desc,     // DESC
-    Limit,    // LIMIT
-    Update,   // UPDATE
-    Set,      // SET
-    Alter,    // ALTER
-    Add,      // ADD
-    Column,   // COLUMN
-    Rename,   // RENAME
-    To,       // TO
-    Delete,   // DELETE
-    Truncate, // TRUNCATE
-    Drop,     // DROP
-    Restrict, // RESTRICT
-    Cascade,  // CASCADE
-    Union,    // UNION
+    Create,      // CREATE
+    Table,       // TABLE
+    If,          // IF
+    Exists,      // EXISTS
+    Insert,      // INSERT
+    Into,        // INTO
+    Values,      // VALUES
+    Select,      // SELECT
+    Distinct,    // DISTINCT
+    From,        // FROM
+    Where,       // WHERE
+    Group,       // GROUP
+    By,          // BY
+    Having,      // HAVING
+    Order,       // ORDER
+    Asc,         // ASC
+    Desc,        // DESC
+    Limit,       // LIMIT
+    Offset,      // OFFSET
+    Update,      // UPDATE
+    Set,         // SET
+    Alter,       // ALTER
+    Add,         // ADD
+    Column,      // COLUMN
+    Rename,      // RENAME
+    To,          // TO
+    Delete,      // DELETE
+    Truncate,    // TRUNCATE
+    Drop,        // DROP
+    Restrict,    // RESTRICT
+    Cascade,     // CASCADE
+    Union,       // UNION
+    Intersect,   // INTERSECT
+    Except,      // EXCEPT
+    Pragma,      // PRAGMA
+    Listen,      // LISTEN
+    Notify,      // NOTIFY
+    Explain,     // EXPLAIN
+    Copy,        // COPY
+    Begin,       // BEGIN
+    Transaction, // TRANSACTION
+    Commit,      // COMMIT
+    Rollback,    // ROLLBACK
+    Foreign,     // FOREIGN
+    Key,         // KEY
+    References,  // REFERENCES
+    With,        // WITH
+    Over,        // OVER
+    Partition,   // PARTITION
+    TableSample, // TABLESAMPLE
+    Join,        // JOIN
+    Inner,       // INNER
+    On,          // ON
+    As,          // AS
+    Left,        // LEFT
+    Right,       // RIGHT
+    Full,        // FULL
+    Outer,       // OUTER
+    Cross,       // CROSS
+    Temp,        // TEMP, TEMPORARY
+    Cast,        // CAST
+    All,         // ALL
+    Trigger,     // TRIGGER
+    After,       // AFTER
+    End,         // END
     // 구분자
     Dot,       // .
     Comma,     // ,
@@ -73,19 +109,82 @@ pub enum Token {
     OpSub,   // -
     OpMul,   // *
     OpDiv,   // /
+    OpMod,   // %
+}
+
+/// 오류 메시지에 쓰는 토큰 모양 (키워드는 대문자로)
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Eof => write!(f, "end of input"),
+            Token::Null => write!(f, "NULL"),
+            Token::Bool(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+            Token::Int(n) => write!(f, "{}", n),
+            Token::Float(n) => write!(f, "{:?}", n),
+            Token::Text(s) => write!(f, "{:?}", s),
+            Token::Param(n) => write!(f, "${}", n),
+            Token::BoolType => write!(f, "BOOLEAN"),
+            Token::IntType => write!(f, "INTEGER"),
+            Token::FloatType => write!(f, "FLOAT"),
+            Token::TextType => write!(f, "TEXT"),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Dot => write!(f, "."),
+            Token::Comma => write!(f, ","),
+            Token::Semicolon => write!(f, ";"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::OpEq => write!(f, "="),
+            Token::OpGt => write!(f, ">"),
+            Token::OpLt => write!(f, "<"),
+            Token::OpGe => write!(f, ">="),
+            Token::OpLe => write!(f, "<="),
+            Token::OpAdd => write!(f, "+"),
+            Token::OpSub => write!(f, "-"),
+            Token::OpMul => write!(f, "*"),
+            Token::OpDiv => write!(f, "/"),
+            Token::OpMod => write!(f, "%"),
+            // 나머지는 키워드 이름 그대로
+            keyword => write!(f, "{}", format!("{:?}", keyword).to_uppercase()),
+        }
+    }
 }
 
 pub struct Lexer {
     src: VecDeque<char>,
+    // 지금까지 나온 가장 큰 파라미터 번호 (`?`는 이 다음 번호를 받음)
+    max_param: u32,
+    // 다음 문자의 위치와 마지막 토큰이 시작한 위치 (1부터 시작하는 줄, 열)
+    line: usize,
+    column: usize,
+    start: (usize, usize),
 }
 
 impl Lexer {
     pub fn new(src: &str) -> Self {
         Self {
             src: src.chars().collect(),
+            max_param: 0,
+            line: 1,
+            column: 1,
+            start: (1, 1),
         }
     }
 
+    /// 마지막으로 읽은 토큰이 시작한 위치 (줄, 열)
+    pub fn token_start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    /// 아직 읽지 않은 문자 수 (토큰이 끝난 위치를 알 때 사용)
+    pub fn remaining(&self) -> usize {
+        self.src.len()
+    }
+
+    /// 지금까지 읽은 파라미터 중 가장 큰 번호 (바인딩해야 하는 값의 수)
+    pub fn max_param(&self) -> u32 {
+        self.max_param
+    }
+
     fn is_letter(ch: char) -> bool {
         ch.is_alphabetic() || ch == '_'
     }
@@ -107,7 +206,14 @@ impl Lexer {
     }
 
     fn walk(&mut self) -> Option<char> {
-        self.src.pop_front()
+        let ch = self.src.pop_front()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
     }
 
     fn skip_ws(&mut self) {
@@ -118,8 +224,10 @@ impl Lexer {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Token> {
         self.skip_ws();
+        self.start = (self.line, self.column);
         // 렉싱이 성공적으로 끝난 경우
         if self.finished() {
             return Ok(Token::Eof);
@@ -135,6 +243,10 @@ impl Lexer {
             return self.next();
         }
         let ch = self.walk().ok_or(QueryErr::UnexpectedEof)?;
+        self.lex_token(ch).map_err(|e| e.at(self.start))
+    }
+
+    fn lex_token(&mut self, ch: char) -> Result<Token> {
         Ok(match ch {
             '.' => Token::Dot,
             ',' => Token::Comma,
@@ -162,6 +274,12 @@ impl Lexer {
             '-' => Token::OpSub,
             '*' => Token::OpMul,
             '/' => Token::OpDiv,
+            '%' => Token::OpMod,
+            '?' => {
+                self.max_param += 1;
+                Token::Param(self.max_param)
+            }
+            '$' => self.lex_param()?,
             '\'' | '"' => self.lex_text(ch)?,
             _ if Self::is_digit(ch) => self.lex_num(ch)?,
             _ if Self::is_letter(ch) => self.lex_keyword(ch)?,
@@ -195,6 +313,23 @@ impl Lexer {
         Err(QueryErr::UnterminatedText)
     }
 
+    fn lex_param(&mut self) -> Result<Token> {
+        let mut out = String::new();
+        while let Some(ch) = self.curr()
+            && Self::is_digit(ch)
+        {
+            // ! `curr()`의 반환값이 `Some`이므로 안전함
+            out.push(self.walk().unwrap());
+        }
+        match out.parse::<u32>() {
+            Ok(n) if n > 0 => {
+                self.max_param = self.max_param.max(n);
+                Ok(Token::Param(n))
+            }
+            _ => Err(QueryErr::InvalidParam(format!("${}", out))),
+        }
+    }
+
     fn lex_num(&mut self, start: char) -> Result<Token> {
         let mut float = false;
         let mut out = String::from(start);
@@ -263,6 +398,7 @@ impl Lexer {
             "ASC" => Token::Asc,
             "DESC" => Token::Desc,
             "LIMIT" => Token::Limit,
+            "OFFSET" => Token::Offset,
             "UPDATE" => Token::Update,
             "SET" => Token::Set,
             "ALTER" => Token::Alter,
@@ -276,6 +412,39 @@ impl Lexer {
             "RESTRICT" => Token::Restrict,
             "CASCADE" => Token::Cascade,
             "UNION" => Token::Union,
+            "INTERSECT" => Token::Intersect,
+            "EXCEPT" => Token::Except,
+            "PRAGMA" => Token::Pragma,
+            "LISTEN" => Token::Listen,
+            "NOTIFY" => Token::Notify,
+            "EXPLAIN" => Token::Explain,
+            "COPY" => Token::Copy,
+            "BEGIN" => Token::Begin,
+            "TRANSACTION" => Token::Transaction,
+            "COMMIT" => Token::Commit,
+            "ROLLBACK" => Token::Rollback,
+            "FOREIGN" => Token::Foreign,
+            "KEY" => Token::Key,
+            "REFERENCES" => Token::References,
+            "WITH" => Token::With,
+            "OVER" => Token::Over,
+            "PARTITION" => Token::Partition,
+            "TABLESAMPLE" => Token::TableSample,
+            "JOIN" => Token::Join,
+            "INNER" => Token::Inner,
+            "ON" => Token::On,
+            "AS" => Token::As,
+            "LEFT" => Token::Left,
+            "RIGHT" => Token::Right,
+            "FULL" => Token::Full,
+            "OUTER" => Token::Outer,
+            "CROSS" => Token::Cross,
+            "TEMP" | "TEMPORARY" => Token::Temp,
+            "CAST" => Token::Cast,
+            "ALL" => Token::All,
+            "TRIGGER" => Token::Trigger,
+            "AFTER" => Token::After,
+            "END" => Token::End,
             // 연산자
             "NOT" => Token::Not,
             "AND" => Token::And,
@@ -330,7 +499,7 @@ mod test {
 
     #[test]
     fn test_operators() {
-        let mut lexer = Lexer::new("= > < >= <= + - * /");
+        let mut lexer = Lexer::new("= > < >= <= + - * / %");
         assert_eq!(lexer.next().unwrap(), Token::OpEq);
         assert_eq!(lexer.next().unwrap(), Token::OpGt);
         assert_eq!(lexer.next().unwrap(), Token::OpLt);
@@ -340,6 +509,7 @@ mod test {
         assert_eq!(lexer.next().unwrap(), Token::OpSub);
         assert_eq!(lexer.next().unwrap(), Token::OpMul);
         assert_eq!(lexer.next().unwrap(), Token::OpDiv);
+        assert_eq!(lexer.next().unwrap(), Token::OpMod);
     }
 
     #[test]
@@ -378,11 +548,31 @@ mod test {
     fn test_unterminated_string() {
         let mut lexer = Lexer::new("'unfinished");
         match lexer.next() {
-            Err(QueryErr::UnterminatedText) => (),
+            Err(QueryErr::At { err, .. }) if *err == QueryErr::UnterminatedText => (),
             _ => panic!("Expected UnterminatedText error"),
         }
     }
 
+    #[test]
+    fn test_positions() {
+        let mut lexer = Lexer::new(
+            "SELECT a,
+  b
+'oops",
+        );
+        lexer.next().unwrap();
+        assert_eq!(lexer.token_start(), (1, 1));
+        lexer.next().unwrap();
+        assert_eq!(lexer.token_start(), (1, 8));
+        lexer.next().unwrap();
+        lexer.next().unwrap();
+        assert_eq!(lexer.token_start(), (2, 3));
+        assert_eq!(lexer.next().unwrap_err().position(), Some((3, 1)));
+        assert_eq!(Token::Select.to_string(), "SELECT");
+        assert_eq!(Token::OpGe.to_string(), ">=");
+        assert_eq!(Token::Text("x".into()).to_string(), "\"x\"");
+    }
+
     #[test]
     fn test_boolean_and_null() {
         let mut lexer = Lexer::new("TRUE FALSE NULL");
@@ -415,6 +605,18 @@ mod test {
         assert_eq!(lexer.next().unwrap(), Token::Ident("users".to_string()));
     }
 
+    #[test]
+    fn test_params() {
+        let mut lexer = Lexer::new("? $3 ? $1");
+        assert_eq!(lexer.next().unwrap(), Token::Param(1));
+        assert_eq!(lexer.next().unwrap(), Token::Param(3));
+        assert_eq!(lexer.next().unwrap(), Token::Param(4));
+        assert_eq!(lexer.next().unwrap(), Token::Param(1));
+        assert_eq!(lexer.max_param(), 4);
+        assert!(Lexer::new("$0").next().is_err());
+        assert!(Lexer::new("$x").next().is_err());
+    }
+
     #[test]
     fn test_hex_not_supported() {
         let mut lexer = Lexer::new("0x123");
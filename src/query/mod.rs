@@ -1,6 +1,10 @@
 pub mod error;
+pub mod format;
 pub mod lexer;
 pub mod parser;
 
+pub use format::format_sql;
 pub use lexer::Lexer;
-pub use parser::{Expr, Parser, Stmt};
+pub use parser::{
+    AlterAction, Expr, ForeignKey, Join, JoinKind, Order, Parser, SetOp, Source, Stmt, TriggerEvent,
+};
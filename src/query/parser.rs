@@ -4,12 +4,25 @@ use std::mem::{discriminant, replace};
 
 #[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
+#[allow(clippy::large_enum_variant)] // SELECT는 절이 많아 다른 문보다 클 수밖에 없음
 pub enum Stmt {
-    // CREATE TABLE [IF NOT EXISTS] <table> (<col1> <type>, <col2> <type>, ...)
+    // CREATE [TEMP] TABLE [IF NOT EXISTS] <table> (<col1> <type> [REFERENCES ...], ...
+    //     [, FOREIGN KEY (<col>, ...) REFERENCES <table> (<col>, ...)])
     Create {
         table: Box<str>,                    // table name
         columns: Vec<(Box<str>, Box<str>)>, // col name, col type
+        foreign_keys: Vec<ForeignKey>,      // referential constraints
         if_not_exists: bool,                // run if not exists
+        temporary: bool,                    // visible only to the current session
+    },
+    // CREATE TRIGGER [IF NOT EXISTS] <name> AFTER {INSERT | UPDATE} ON <table>
+    //     BEGIN <stmt>; ... END
+    CreateTrigger {
+        name: Box<str>,      // trigger name
+        event: TriggerEvent, // statement that fires the trigger
+        table: Box<str>,     // table name
+        body: Vec<Stmt>,     // run once per inserted or updated row
+        if_not_exists: bool, // run if not exists
     },
     // INSERT INTO <table> [(<col1>, <col2>, ...)] VALUES (<val1>, <val2>, ...)
     InsertValues {
@@ -17,35 +30,58 @@ pub enum Stmt {
         columns: Vec<Box<str>>, // col name
         values: Vec<Vec<Expr>>, // row [val expr]
     },
-    // SELECT [DISTINCT] <col1>, <col2>, ... FROM <table>
-    //     [WHERE] [GROUP BY] [HAVING] [ORDER BY] [LIMIT]
+    // INSERT INTO <table> [(<col1>, <col2>, ...)] <query>
+    InsertQuery {
+        table: Box<str>,        // table name
+        columns: Vec<Box<str>>, // col name
+        query: Box<Stmt>,       // SELECT, WITH or VALUES whose rows are inserted
+    },
+    // VALUES (<val1>, <val2>, ...), ... as a query
+    Values(Vec<Vec<Expr>>), // row [val expr], columns are named column1, column2, ...
+    // SELECT [DISTINCT [ON (<expr>, ...)]] <col1>, <col2>, ... FROM <source> [[AS] <alias>]
+    //     [TABLESAMPLE] [[INNER | LEFT | RIGHT | FULL [OUTER]] JOIN <source> [[AS] <alias>] ON <cond> ...]
+    //     [{CROSS JOIN | ,} <source> [[AS] <alias>] ...]
+    //     [WHERE] [GROUP BY] [HAVING] [ORDER BY] [LIMIT [OFFSET]]
     Select {
-        table: Box<str>,                     // table name
-        columns: Vec<Expr>,                  // col name (or expr)
-        distinct: bool,                      // distinct flag
-        where_clause: Option<Expr>,          // condition expr
-        group_by: Option<Vec<Expr>>,         // col name (or expr)
-        having: Option<Expr>,                // condition expr
-        order_by: Option<Vec<(Expr, bool)>>, // col name, ASC/DESC
-        limit: Option<u64>,                  // limit count
+        table: Source,                        // table name or subquery
+        alias: Option<Box<str>>,              // table alias
+        sample: Option<(Box<str>, Expr)>,     // sample method, percent expr
+        joins: Vec<Join>,                     // joined tables
+        columns: Vec<Expr>,                   // col name (or expr)
+        distinct: Option<Vec<Expr>>,          // DISTINCT (empty) or DISTINCT ON key exprs
+        where_clause: Option<Expr>,           // condition expr
+        group_by: Option<Vec<Vec<Expr>>>,     // grouping sets [col name (or expr)]
+        having: Option<Expr>,                 // condition expr
+        order_by: Option<Vec<(Expr, Order)>>, // col name, ASC/DESC, NULLS FIRST/LAST
+        limit: Option<u64>,                   // limit count
+        offset: Option<u64>,                  // skip count
     },
-    // UPDATE <table> SET <col1> = <val1>, <col2> = <val2>, ... [WHERE]
-    Update {
-        table: Box<str>,                // table name
-        assigns: Vec<(Box<str>, Expr)>, // col name, val expr
-        where_clause: Option<Expr>,     // condition expr
+    // <select> {UNION | INTERSECT | EXCEPT} [ALL] <select> ... [ORDER BY] [LIMIT [OFFSET]]
+    Compound {
+        op: SetOp,                            // set operation
+        all: bool,                            // keep duplicates
+        left: Box<Stmt>,                      // left query
+        right: Box<Stmt>,                     // right query
+        order_by: Option<Vec<(Expr, Order)>>, // ordering of the combined result
+        limit: Option<u64>,                   // limit of the combined result
+        offset: Option<u64>,                  // offset of the combined result
     },
-    AlterAdd {
-        table: Box<str>,              // table name
-        column: (Box<str>, Box<str>), // col name, col type
+    // WITH <name> AS (<query>), ... <query>
+    With {
+        ctes: Vec<(Box<str>, Stmt)>, // name, query (run once before the body)
+        body: Box<Stmt>,             // main query
     },
-    AlterDrop {
-        table: Box<str>,  // table name
-        column: Box<str>, // col name
+    // UPDATE <table> SET <col1> = <val1>, <col2> = <val2>, ... [WHERE]
+    Update {
+        table: Box<str>,                          // table name
+        assigns: Vec<(Box<str>, Expr)>,           // col name, val expr
+        from: Option<(Source, Option<Box<str>>)>, // FROM <source> [alias], joined by WHERE
+        where_clause: Option<Expr>,               // condition expr
     },
-    AlterRename {
-        table: Box<str>,    // table name
-        new_name: Box<str>, // new table name
+    // ALTER TABLE <table> <action>, <action>, ...
+    Alter {
+        table: Box<str>,           // table name
+        actions: Vec<AlterAction>, // applied in order
     },
     // DELETE FROM <table> [WHERE]
     Delete {
@@ -62,6 +98,38 @@ pub enum Stmt {
         if_exists: bool, // run if exists
         cascade: bool,   // run despite dependent
     },
+    // PRAGMA <name> [= <value>]
+    Pragma {
+        name: Box<str>,      // pragma name
+        value: Option<Expr>, // new value (None: query current value)
+    },
+    // LISTEN <channel>
+    Listen {
+        channel: Box<str>, // channel name
+    },
+    // NOTIFY <channel> [, '<payload>']
+    Notify {
+        channel: Box<str>, // channel name
+        payload: Box<str>, // message (empty if omitted)
+    },
+    // EXPLAIN (PROFILE [, FORMAT {TEXT | JSON}]) <stmt>
+    Explain {
+        stmt: Box<Stmt>, // profiled statement
+        json: bool,      // speedscope JSON instead of a text tree
+    },
+    // COPY <table> {FROM | TO} '<path>' (FORMAT <format>)
+    Copy {
+        table: Box<str>,  // table name
+        path: Box<str>,   // file path
+        export: bool,     // TO (write the table to the file)
+        format: Box<str>, // file format name
+    },
+    // BEGIN [TRANSACTION]
+    Begin,
+    // COMMIT [TRANSACTION]
+    Commit,
+    // ROLLBACK [TRANSACTION]
+    Rollback,
 }
 
 impl Stmt {
@@ -70,6 +138,88 @@ impl Stmt {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct Join {
+    pub kind: JoinKind,          // join type
+    pub table: Source,           // table name or subquery
+    pub alias: Option<Box<str>>, // table alias
+    pub on: Expr,                // join condition
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterAction {
+    AddColumn(Box<str>, Box<str>), // ADD COLUMN <col name> <col type>
+    DropColumn(Box<str>),          // DROP COLUMN <col name>
+    RenameTo(Box<str>),            // RENAME TO <new table name> (only on its own)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriggerEvent {
+    Insert, // AFTER INSERT (NEW is the inserted row)
+    Update, // AFTER UPDATE (OLD and NEW are the row before and after)
+}
+
+// FOREIGN KEY (<columns>) REFERENCES <table> (<ref_columns>)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignKey {
+    pub columns: Vec<Box<str>>,     // referencing col names
+    pub table: Box<str>,            // referenced table name
+    pub ref_columns: Vec<Box<str>>, // referenced col names
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetOp {
+    Union,
+    Intersect,
+    Except,
+}
+
+impl SetOp {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Union => "Union",
+            Self::Intersect => "Intersect",
+            Self::Except => "Except",
+        }
+    }
+}
+
+/// ORDER BY 키 하나의 방향과 NULL의 위치
+///
+/// NULLS FIRST/LAST를 쓰지 않으면 NULL을 가장 큰 값처럼 둠 (ASC면 맨 뒤, DESC면 맨 앞)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Order {
+    pub asc: bool,
+    pub nulls_first: bool,
+}
+
+impl Order {
+    pub const ASC: Order = Order {
+        asc: true,
+        nulls_first: false,
+    };
+    pub const DESC: Order = Order {
+        asc: false,
+        nulls_first: true,
+    };
+}
+
+// FROM 뒤에 올 수 있는 것: <table> | (<select>)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Table(Box<str>),
+    Subquery(Box<Stmt>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JoinKind {
+    Inner,
+    Left,  // 오른쪽에 짝이 없는 왼쪽 행도 남김
+    Right, // 왼쪽에 짝이 없는 오른쪽 행도 남김
+    Full,  // 양쪽 모두
+    Cross, // 조건 없는 카티전 곱
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Clause {
     Values(Vec<Expr>),               // expr
@@ -113,7 +263,13 @@ pub enum Expr {
     Int(i64),
     Float(f64),
     Text(Box<str>),
+    Param(u32), // ? or $n, bound when a prepared statement is executed
     Ident(Box<str>),
+    FunctionCall {
+        name: Box<str>,
+        args: Vec<Expr>,
+        order_by: Option<Vec<(Expr, Order)>>, // aggregate input order
+    },
     Unary {
         op: Token,
         right: Box<Expr>,
@@ -123,6 +279,27 @@ pub enum Expr {
         left: Box<Expr>,
         right: Box<Expr>,
     },
+    Subquery(Box<Stmt>), // (SELECT ...) evaluated to a single value
+    Exists(Box<Stmt>),   // EXISTS (SELECT ...)
+    Between {
+        expr: Box<Expr>,
+        low: Box<Expr>,
+        high: Box<Expr>,
+        negated: bool, // NOT BETWEEN
+    },
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool, // IS NOT NULL
+    },
+    Cast {
+        expr: Box<Expr>,
+        ty: Box<str>, // target type name
+    },
+    Window {
+        func: Box<Expr>,              // function call before OVER
+        partition_by: Vec<Expr>,      // PARTITION BY
+        order_by: Vec<(Expr, Order)>, // ORDER BY inside OVER
+    },
 }
 
 impl Expr {
@@ -131,37 +308,73 @@ impl Expr {
     }
 }
 
+// ORDER BY, LIMIT, OFFSET
+type Tail = (Option<Vec<(Expr, Order)>>, Option<u64>, Option<u64>);
+
+// CUBE는 식 n개를 2^n개의 GROUPING SETS로 풀기 때문에 식 개수를 제한
+const MAX_CUBE_EXPRS: usize = 12;
+
 pub struct Parser {
     lexer: Lexer,
     curr: Token,
     peek: Token,
+    // curr, peek, 마지막으로 넘긴 토큰이 시작한 위치 (오류 메시지용)
+    pos: (usize, usize),
+    peek_pos: (usize, usize),
+    prev_pos: (usize, usize),
+    // `parse_all`에서는 렉서 오류가 나도 멈추지 않고 모아 둠
+    recover: bool,
+    lex_errors: Vec<QueryErr>,
 }
 
 impl Parser {
-    pub fn new(mut lexer: Lexer) -> Result<Self> {
-        let curr = lexer.next()?;
-        let peek = lexer.next()?;
-        Ok(Self { lexer, curr, peek })
+    pub fn new(lexer: Lexer) -> Result<Self> {
+        Self::start(lexer, false)
+    }
+
+    // 처음 두 토큰을 읽음 (`recover`면 렉서 오류를 모아 두고 다음 토큰을 읽음)
+    fn start(lexer: Lexer, recover: bool) -> Result<Self> {
+        let mut parser = Self {
+            lexer,
+            curr: Token::Eof,
+            peek: Token::Eof,
+            pos: (1, 1),
+            peek_pos: (1, 1),
+            prev_pos: (1, 1),
+            recover,
+            lex_errors: Vec::new(),
+        };
+        parser.next()?;
+        parser.next()?;
+        parser.prev_pos = parser.pos;
+        Ok(parser)
     }
 
-    fn precedence(token: &Token) -> u8 {
+    pub(super) fn precedence(token: &Token) -> u8 {
         match token {
             Token::Or => 1,
             Token::And => 2,
             Token::OpEq => 3,
-            Token::OpGt | Token::OpLt | Token::OpGe | Token::OpLe => 4,
+            Token::OpGt | Token::OpLt | Token::OpGe | Token::OpLe | Token::Between | Token::Is => 4,
             Token::OpAdd | Token::OpSub => 5,
-            Token::OpMul | Token::OpDiv => 6,
-            Token::LParen => 7,
+            Token::OpMul | Token::OpDiv | Token::OpMod => 6,
             _ => 0,
         }
     }
 
     fn next(&mut self) -> Result<Token> {
-        Ok(replace(
-            &mut self.curr,
-            replace(&mut self.peek, self.lexer.next()?),
-        ))
+        let peek = loop {
+            match self.lexer.next() {
+                Ok(token) => break token,
+                Err(e) if self.recover => self.lex_errors.push(e),
+                Err(e) => return Err(e),
+            }
+        };
+        self.prev_pos = replace(
+            &mut self.pos,
+            replace(&mut self.peek_pos, self.lexer.token_start()),
+        );
+        Ok(replace(&mut self.curr, replace(&mut self.peek, peek)))
     }
 
     fn expect(&mut self, tokens: &[Token]) -> Result<()> {
@@ -170,9 +383,10 @@ impl Parser {
                 self.next()?;
             } else {
                 return Err(QueryErr::UnexpectedToken {
-                    expected: format!("{:?}", token),
-                    found: format!("{:?}", self.curr),
-                });
+                    expected: format!("'{}'", token),
+                    found: format!("'{}'", self.curr),
+                }
+                .at(self.pos));
             }
         }
         Ok(())
@@ -184,12 +398,66 @@ impl Parser {
         } else if discriminant(&self.curr) != discriminant(&tokens[0]) {
             Ok(false)
         } else {
-            self.expect(&tokens).map(|_| true)
+            self.expect(tokens).map(|_| true)
         }
     }
 
+    /// 오류에는 위치가 붙음 (토큰을 가리키지 않는 오류는 마지막으로 읽은 토큰의 위치)
     pub fn parse(&mut self) -> Result<Vec<Stmt>> {
         self.parse_block(&[Token::Eof])
+            .map_err(|e| e.at(self.prev_pos))
+    }
+
+    /// 오류가 나도 멈추지 않고 다음 `;`부터 다시 파싱해서, 파싱한 문장과 모든 오류를 돌려줌
+    ///
+    /// 오류가 난 문장은 결과에서 빠집니다. 문장 안에 렉서 오류가 있으면 그 문장의 파싱 오류는
+    /// 렉서 오류 때문에 생긴 것이므로 렉서 오류만 알립니다.
+    pub fn parse_all(lexer: Lexer) -> (Vec<Stmt>, Vec<QueryErr>) {
+        let mut parser = match Self::start(lexer, true) {
+            Ok(parser) => parser,
+            Err(e) => return (Vec::new(), vec![e]),
+        };
+        let mut stmts = Vec::new();
+        let mut errors = Vec::new();
+        while parser.curr != Token::Eof {
+            if parser.curr == Token::Semicolon {
+                if parser.next().is_err() {
+                    break;
+                }
+                continue;
+            }
+            let stmt = parser.parse_stmt().map_err(|e| e.at(parser.prev_pos));
+            if stmt.is_err() {
+                // 문장의 나머지를 버림
+                while !matches!(parser.curr, Token::Semicolon | Token::Eof) {
+                    if parser.next().is_err() {
+                        break;
+                    }
+                }
+            }
+            // 문장이 끝난 곳보다 앞에서 난 렉서 오류는 이 문장의 오류
+            let end = parser.pos;
+            let split = parser
+                .lex_errors
+                .iter()
+                .take_while(|e| e.position().is_none_or(|pos| pos < end))
+                .count();
+            if split > 0 {
+                errors.extend(parser.lex_errors.drain(..split));
+            } else {
+                match stmt {
+                    Ok(stmt) => stmts.push(stmt),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+        errors.append(&mut parser.lex_errors);
+        (stmts, errors)
+    }
+
+    /// 파싱한 문장들에 바인딩해야 하는 파라미터 값의 수
+    pub fn param_count(&self) -> u32 {
+        self.lexer.max_param()
     }
 
     fn parse_block(&mut self, terms: &[Token]) -> Result<Vec<Stmt>> {
@@ -212,54 +480,155 @@ impl Parser {
         match &self.curr {
             Token::Create => self.parse_create(),
             Token::Insert => self.parse_insert(),
-            Token::Select => self.parse_select(),
+            Token::Select | Token::With | Token::Values => self.parse_query(),
             Token::Update => self.parse_update(),
             Token::Alter => self.parse_alter(),
             Token::Delete => self.parse_delete(),
             Token::Truncate => self.parse_truncate(),
             Token::Drop => self.parse_drop(),
+            Token::Pragma => self.parse_pragma(),
+            Token::Listen => self.parse_listen(),
+            Token::Notify => self.parse_notify(),
+            Token::Explain => self.parse_explain(),
+            Token::Copy => self.parse_copy(),
+            Token::Begin | Token::Commit | Token::Rollback => self.parse_transaction(),
             tok => Err(QueryErr::UnexpectedToken {
-                expected: "SELECT, INSERT, UPDATE, DELETE, CREATE, DROP".into(),
-                found: format!("{:?}", tok),
-            }),
+                expected: "SELECT, INSERT, UPDATE, DELETE, CREATE, DROP, PRAGMA, LISTEN, NOTIFY, \
+                     EXPLAIN, COPY, BEGIN, COMMIT, ROLLBACK"
+                    .into(),
+                found: format!("'{}'", tok),
+            }
+            .at(self.pos)),
         }
     }
 
     fn parse_create(&mut self) -> Result<Stmt> {
-        // CREATE TABLE [IF NOT EXISTS] <table> (<col1> <type>, <col2> <type>, ...)
-        self.expect(&[Token::Create, Token::Table])?;
+        // CREATE [TEMP] TABLE [IF NOT EXISTS] <table> (<col1> <type>, <col2> <type>, ...)
+        self.expect(&[Token::Create])?;
+        if self.curr == Token::Trigger {
+            return self.parse_create_trigger();
+        }
+        let temporary = self.maybe(&[Token::Temp])?;
+        self.expect(&[Token::Table])?;
         let if_not_exists = self.maybe(&[Token::If, Token::Not, Token::Exists])?;
-        let table = self.consume_ident()?;
+        let table = self.consume_name()?;
+        let mut foreign_keys = Vec::new();
         let columns = self.parse_list_clause(true, |p| {
+            // 테이블 제약: FOREIGN KEY (<col>, ...) REFERENCES <table> (<col>, ...)
+            if p.maybe(&[Token::Foreign, Token::Key])? {
+                let columns = p.parse_list_clause(true, |p| p.consume_ident())?;
+                let (table, ref_columns) = p.parse_references()?;
+                foreign_keys.push(ForeignKey {
+                    columns,
+                    table,
+                    ref_columns,
+                });
+                return Ok(None);
+            }
             let col_name = p.consume_ident()?;
             let col_type = p.consume_type()?;
-            Ok((col_name, col_type))
+            // 컬럼 제약: <col> <type> REFERENCES <table> (<col>)
+            if p.curr == Token::References {
+                let (table, ref_columns) = p.parse_references()?;
+                foreign_keys.push(ForeignKey {
+                    columns: vec![col_name.clone()],
+                    table,
+                    ref_columns,
+                });
+            }
+            Ok(Some((col_name, col_type)))
         })?;
         Ok(Stmt::Create {
             table,
-            columns,
+            columns: columns.into_iter().flatten().collect(),
+            foreign_keys,
+            if_not_exists,
+            temporary,
+        })
+    }
+
+    fn parse_create_trigger(&mut self) -> Result<Stmt> {
+        // CREATE TRIGGER [IF NOT EXISTS] <name> AFTER {INSERT | UPDATE} ON <table> BEGIN <stmt>; ... END
+        self.expect(&[Token::Trigger])?;
+        let if_not_exists = self.maybe(&[Token::If, Token::Not, Token::Exists])?;
+        let name = self.consume_name()?;
+        self.expect(&[Token::After])?;
+        let event = match self.curr {
+            Token::Insert => TriggerEvent::Insert,
+            Token::Update => TriggerEvent::Update,
+            _ => {
+                return Err(QueryErr::UnexpectedToken {
+                    expected: "INSERT or UPDATE".into(),
+                    found: format!("'{}'", self.curr),
+                }
+                .at(self.pos));
+            }
+        };
+        self.next()?;
+        self.expect(&[Token::On])?;
+        let table = self.consume_name()?;
+        self.expect(&[Token::Begin])?;
+        let body = self.parse_block(&[Token::End])?;
+        self.expect(&[Token::End])?;
+        // 트리거 안에서는 행을 읽고 쓰는 문장만 실행 (DELETE는 아직 실행할 수 없음)
+        let dml = |stmt: &Stmt| {
+            matches!(
+                stmt,
+                Stmt::InsertValues { .. }
+                    | Stmt::InsertQuery { .. }
+                    | Stmt::Update { .. }
+                    | Stmt::Select { .. }
+                    | Stmt::Compound { .. }
+                    | Stmt::With { .. }
+                    | Stmt::Values(_)
+                    | Stmt::Notify { .. }
+            )
+        };
+        if !body.iter().all(dml) {
+            return Err(QueryErr::InvalidExpr(
+                "a trigger may only contain INSERT, UPDATE, SELECT and NOTIFY".into(),
+            ));
+        }
+        Ok(Stmt::CreateTrigger {
+            name,
+            event,
+            table,
+            body,
             if_not_exists,
         })
     }
 
+    fn parse_references(&mut self) -> Result<(Box<str>, Vec<Box<str>>)> {
+        // REFERENCES <table> (<col>, ...)
+        self.expect(&[Token::References])?;
+        let table = self.consume_name()?;
+        let columns = self.parse_list_clause(true, |p| p.consume_ident())?;
+        Ok((table, columns))
+    }
+
     fn parse_insert(&mut self) -> Result<Stmt> {
         // INSERT INTO <table> [(<col1>, <col2>, ...)] ...
         self.expect(&[Token::Insert, Token::Into])?;
-        let table = self.consume_ident()?;
-        let columns = if &self.curr == &Token::LParen {
+        let table = self.consume_name()?;
+        let columns = if self.curr == Token::LParen {
             self.parse_list_clause(true, |p| p.consume_ident())?
         } else {
             vec![]
         };
         if self.maybe(&[Token::Values])? {
             self.parse_insert_values(table, columns)
-        } else if self.maybe(&[Token::Select])? {
-            unimplemented!("최소 구현 우선 (INSERT ... SELECT 지원 보류)")
+        } else if matches!(self.curr, Token::Select | Token::With) {
+            Ok(Stmt::InsertQuery {
+                table,
+                columns,
+                query: self.parse_query()?.boxed(),
+            })
         } else {
             Err(QueryErr::UnexpectedToken {
                 expected: "VALUES or SELECT".into(),
-                found: format!("{:?}", self.curr),
-            })
+                found: format!("'{}'", self.curr),
+            }
+            .at(self.pos))
         }
     }
 
@@ -274,11 +643,133 @@ impl Parser {
         })
     }
 
+    fn parse_query(&mut self) -> Result<Stmt> {
+        // WITH <name> AS (<query>), ... <query>
+        if self.maybe(&[Token::With])? {
+            let ctes = self.parse_list_clause(false, |p| {
+                let name = p.consume_ident()?;
+                p.expect(&[Token::As, Token::LParen])?;
+                let query = p.parse_query()?;
+                p.expect(&[Token::RParen])?;
+                Ok((name, query))
+            })?;
+            let body = self.parse_query()?.boxed();
+            return Ok(Stmt::With { ctes, body });
+        }
+        // <select> {(UNION | INTERSECT | EXCEPT) [ALL] <select>} ... [ORDER BY] [LIMIT [OFFSET]]
+        let first = self.parse_query_term()?;
+        let mut ops = Vec::new();
+        let mut selects = Vec::new();
+        while let Some(op) = self.parse_set_op()? {
+            let all = self.maybe(&[Token::All])?;
+            ops.push((op, all));
+            selects.push(self.parse_query_term()?);
+        }
+        let Some(last) = selects.last_mut() else {
+            return Ok(first);
+        };
+        // 마지막 SELECT 뒤의 ORDER BY, LIMIT은 합친 결과 전체에 적용됨
+        let (order_by, limit, offset) = match last {
+            Stmt::Select {
+                order_by,
+                limit,
+                offset,
+                ..
+            } => (order_by.take(), limit.take(), offset.take()),
+            _ => self.parse_tail()?,
+        };
+        if has_tail(&first) || selects.iter().any(has_tail) {
+            return Err(QueryErr::InvalidExpr(
+                "ORDER BY and LIMIT must come after the last SELECT of a compound query".into(),
+            ));
+        }
+
+        // INTERSECT가 UNION, EXCEPT보다 먼저 묶이고, 같은 우선순위끼리는 왼쪽부터 묶임
+        let mut terms = Vec::new();
+        let mut outer_ops = Vec::new();
+        let mut term = first;
+        for ((op, all), right) in ops.into_iter().zip(selects) {
+            if op == SetOp::Intersect {
+                term = compound(op, all, term, right);
+            } else {
+                terms.push(std::mem::replace(&mut term, right));
+                outer_ops.push((op, all));
+            }
+        }
+        terms.push(term);
+        let mut terms = terms.into_iter();
+        let mut stmt = terms.next().unwrap();
+        for ((op, all), right) in outer_ops.into_iter().zip(terms) {
+            stmt = compound(op, all, stmt, right);
+        }
+        if let Stmt::Compound {
+            order_by: o,
+            limit: l,
+            offset: f,
+            ..
+        } = &mut stmt
+        {
+            (*o, *l, *f) = (order_by, limit, offset);
+        }
+        Ok(stmt)
+    }
+
+    // [ORDER BY <expr> [ASC | DESC], ...] [LIMIT <n> [OFFSET <n>]]
+    fn parse_tail(&mut self) -> Result<Tail> {
+        let order_by = if self.maybe(&[Token::Order, Token::By])? {
+            Some(self.parse_order_by()?)
+        } else {
+            None
+        };
+        let (limit, offset) = if self.maybe(&[Token::Limit])? {
+            let limit = self.consume_count()?;
+            let offset = if self.maybe(&[Token::Offset])? {
+                Some(self.consume_count()?)
+            } else {
+                None
+            };
+            (Some(limit), offset)
+        } else {
+            (None, None)
+        };
+        Ok((order_by, limit, offset))
+    }
+
+    fn parse_set_op(&mut self) -> Result<Option<SetOp>> {
+        let op = match self.curr {
+            Token::Union => SetOp::Union,
+            Token::Intersect => SetOp::Intersect,
+            Token::Except => SetOp::Except,
+            _ => return Ok(None),
+        };
+        self.next()?;
+        Ok(Some(op))
+    }
+
+    fn parse_query_term(&mut self) -> Result<Stmt> {
+        // <select> | VALUES (<val1>, <val2>, ...), ...
+        if self.maybe(&[Token::Values])? {
+            let rows =
+                self.parse_list_clause(false, |p| p.parse_list_clause(true, |p| p.parse_expr(0)))?;
+            Ok(Stmt::Values(rows))
+        } else {
+            self.parse_select()
+        }
+    }
+
     fn parse_select(&mut self) -> Result<Stmt> {
-        // SELECT [DISTINCT] <col1>, <col2>, ... FROM <table>
-        //     [WHERE] [GROUP BY] [HAVING] [ORDER BY] [LIMIT]
+        // SELECT [DISTINCT [ON (<expr>, ...)]] <col1>, <col2>, ... FROM <source> [[AS] <alias>]
+        //     [TABLESAMPLE] [[INNER | LEFT | RIGHT | FULL [OUTER]] JOIN <source> [[AS] <alias>] ON <cond> ...]
+        //     [{CROSS JOIN | ,} <source> [[AS] <alias>] ...]
+        //     [WHERE] [GROUP BY] [HAVING] [ORDER BY] [LIMIT [OFFSET]]
         self.expect(&[Token::Select])?;
-        let distinct = self.maybe(&[Token::Distinct])?;
+        let distinct = if !self.maybe(&[Token::Distinct])? {
+            None
+        } else if self.maybe(&[Token::On])? {
+            Some(self.parse_list_clause(true, |p| p.parse_expr(0))?)
+        } else {
+            Some(vec![])
+        };
         // 전체 컬럼 선택 '*' 처리
         let columns = if !self.maybe(&[Token::OpMul])? {
             self.parse_list_clause(false, |p| p.parse_expr(0))?
@@ -286,15 +777,45 @@ impl Parser {
             vec![]
         };
         self.expect(&[Token::From])?;
-        let table = self.consume_ident()?;
+        let table = self.parse_source()?;
+        let alias = self.parse_alias()?;
+        let sample = if self.maybe(&[Token::TableSample])? {
+            Some(self.parse_table_sample()?)
+        } else {
+            None
+        };
+        let mut joins = Vec::new();
+        while let Some(kind) = self.parse_join_kind()? {
+            let table = self.parse_source()?;
+            let alias = self.parse_alias()?;
+            // CROSS JOIN은 ON 없이 모든 행 쌍을 만듦
+            let on = if kind == JoinKind::Cross {
+                Expr::Bool(true)
+            } else {
+                self.expect(&[Token::On])?;
+                self.parse_expr(0)?
+            };
+            joins.push(Join {
+                kind,
+                table,
+                alias,
+                on,
+            });
+        }
+        let where_clause = self.parse_where()?;
+        let group_by = if self.maybe(&[Token::Group, Token::By])? {
+            Some(self.parse_group_by()?)
+        } else {
+            None
+        };
         // TODO: 최소 구현 우선
-        let where_clause = None;
-        let group_by = None;
         let having = None;
-        let order_by = None;
-        let limit = None;
+        let (order_by, limit, offset) = self.parse_tail()?;
         Ok(Stmt::Select {
             table,
+            alias,
+            sample,
+            joins,
             distinct,
             columns,
             where_clause,
@@ -302,13 +823,146 @@ impl Parser {
             having,
             order_by,
             limit,
+            offset,
         })
     }
 
+    fn parse_source(&mut self) -> Result<Source> {
+        // ... <table> | (<select>)
+        if self.maybe(&[Token::LParen])? {
+            let stmt = self.parse_query()?;
+            self.expect(&[Token::RParen])?;
+            Ok(Source::Subquery(stmt.boxed()))
+        } else {
+            Ok(Source::Table(self.consume_name()?))
+        }
+    }
+
+    fn parse_join_kind(&mut self) -> Result<Option<JoinKind>> {
+        // ... {[INNER | LEFT | RIGHT | FULL [OUTER] | CROSS] JOIN | ,}
+        let kind = match self.curr {
+            Token::Join => return self.expect(&[Token::Join]).map(|_| Some(JoinKind::Inner)),
+            Token::Comma => return self.expect(&[Token::Comma]).map(|_| Some(JoinKind::Cross)),
+            Token::Cross => JoinKind::Cross,
+            Token::Inner => JoinKind::Inner,
+            Token::Left => JoinKind::Left,
+            Token::Right => JoinKind::Right,
+            Token::Full => JoinKind::Full,
+            _ => return Ok(None),
+        };
+        self.next()?;
+        if matches!(kind, JoinKind::Left | JoinKind::Right | JoinKind::Full) {
+            self.maybe(&[Token::Outer])?;
+        }
+        self.expect(&[Token::Join])?;
+        Ok(Some(kind))
+    }
+
+    fn parse_alias(&mut self) -> Result<Option<Box<str>>> {
+        // ... [[AS] <alias>]
+        if self.maybe(&[Token::As])? || matches!(self.curr, Token::Ident(_)) {
+            Ok(Some(self.consume_ident()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_table_sample(&mut self) -> Result<(Box<str>, Expr)> {
+        // ... TABLESAMPLE BERNOULLI|SYSTEM (<percent>)
+        let method = match self.next()? {
+            Token::Ident(name)
+                if matches!(name.to_uppercase().as_str(), "BERNOULLI" | "SYSTEM") =>
+            {
+                name.to_uppercase().into_boxed_str()
+            }
+            tok => {
+                return Err(QueryErr::UnexpectedToken {
+                    expected: "BERNOULLI or SYSTEM".into(),
+                    found: format!("'{}'", tok),
+                }
+                .at(self.prev_pos));
+            }
+        };
+        self.expect(&[Token::LParen])?;
+        let percent = self.parse_expr(0)?;
+        self.expect(&[Token::RParen])?;
+        Ok((method, percent))
+    }
+
+    fn parse_where(&mut self) -> Result<Option<Expr>> {
+        // ... [WHERE <cond>]
+        if self.maybe(&[Token::Where])? {
+            Ok(Some(self.parse_expr(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_group_by(&mut self) -> Result<Vec<Vec<Expr>>> {
+        // ... GROUP BY <expr1>, <expr2>, ...
+        //   | ROLLUP (<expr1>, ...) | CUBE (<expr1>, ...) | GROUPING SETS ((<expr1>, ...), ...)
+        // ROLLUP과 CUBE는 동등한 GROUPING SETS로 풀어서 저장
+        let keyword = match (&self.curr, &self.peek) {
+            (Token::Ident(name), Token::LParen) => name.to_uppercase(),
+            (Token::Ident(name), Token::Ident(next)) if next.eq_ignore_ascii_case("SETS") => {
+                name.to_uppercase()
+            }
+            _ => String::new(),
+        };
+        match keyword.as_str() {
+            "ROLLUP" => {
+                self.next()?;
+                let exprs = self.parse_list_clause(true, |p| p.parse_expr(0))?;
+                Ok((0..=exprs.len())
+                    .rev()
+                    .map(|n| exprs[..n].to_vec())
+                    .collect())
+            }
+            "CUBE" => {
+                self.next()?;
+                let exprs = self.parse_list_clause(true, |p| p.parse_expr(0))?;
+                let n = exprs.len();
+                if n > MAX_CUBE_EXPRS {
+                    return Err(QueryErr::InvalidExpr(format!(
+                        "CUBE takes at most {} expressions, but got {}",
+                        MAX_CUBE_EXPRS, n
+                    )));
+                }
+                Ok((0..1u32 << n)
+                    .rev()
+                    .map(|mask| {
+                        exprs
+                            .iter()
+                            .enumerate()
+                            .filter(|(i, _)| mask & (1 << (n - 1 - i)) != 0)
+                            .map(|(_, expr)| expr.clone())
+                            .collect()
+                    })
+                    .collect())
+            }
+            "GROUPING" => {
+                self.next()?;
+                self.next()?;
+                self.parse_list_clause(true, |p| {
+                    if p.curr == Token::LParen && p.peek == Token::RParen {
+                        p.expect(&[Token::LParen, Token::RParen])?;
+                        Ok(vec![])
+                    } else if p.curr == Token::LParen {
+                        p.parse_list_clause(true, |p| p.parse_expr(0))
+                    } else {
+                        Ok(vec![p.parse_expr(0)?])
+                    }
+                })
+            }
+            _ => Ok(vec![self.parse_list_clause(false, |p| p.parse_expr(0))?]),
+        }
+    }
+
     fn parse_update(&mut self) -> Result<Stmt> {
-        // UPDATE <table> SET <col1> = <val1>, <col2> = <val2>, ... [WHERE]
+        // UPDATE <table> SET <col1> = <val1>, <col2> = <val2>, ...
+        //   [FROM <source> [[AS] <alias>]] [WHERE <cond>]
         self.expect(&[Token::Update])?;
-        let table = self.consume_ident()?;
+        let table = self.consume_name()?;
         self.expect(&[Token::Set])?;
         let assigns = self.parse_list_clause(false, |p| {
             let col_name = p.consume_ident()?;
@@ -316,56 +970,63 @@ impl Parser {
             let val_expr = p.parse_expr(0)?;
             Ok((col_name, val_expr))
         })?;
-        // TODO: 최소 구현 우선
-        let where_clause = None;
+        let from = if self.maybe(&[Token::From])? {
+            Some((self.parse_source()?, self.parse_alias()?))
+        } else {
+            None
+        };
+        let where_clause = self.parse_where()?;
         Ok(Stmt::Update {
             table,
             assigns,
+            from,
             where_clause,
         })
     }
 
     fn parse_alter(&mut self) -> Result<Stmt> {
-        // ALTER TABLE <table> ...
+        // ALTER TABLE <table> <action>, <action>, ...
         self.expect(&[Token::Alter, Token::Table])?;
-        let table = self.consume_ident()?;
+        let table = self.consume_name()?;
+        let actions = self.parse_list_clause(false, |p| p.parse_alter_action())?;
+        // 이름을 바꾸는 동작은 다른 동작과 함께 쓸 수 없음
+        if actions.len() > 1
+            && actions
+                .iter()
+                .any(|a| matches!(a, AlterAction::RenameTo(_)))
+        {
+            return Err(QueryErr::InvalidExpr(
+                "RENAME TO cannot be combined with other ALTER TABLE actions".into(),
+            ));
+        }
+        Ok(Stmt::Alter { table, actions })
+    }
+
+    fn parse_alter_action(&mut self) -> Result<AlterAction> {
         if self.maybe(&[Token::Add, Token::Column])? {
-            self.parse_alter_add(table)
+            // ... ADD COLUMN <col_name> <col_type>
+            let col_name = self.consume_ident()?;
+            let col_type = self.consume_type()?;
+            Ok(AlterAction::AddColumn(col_name, col_type))
         } else if self.maybe(&[Token::Drop, Token::Column])? {
-            self.parse_alter_drop(table)
+            // ... DROP COLUMN <col_name>
+            Ok(AlterAction::DropColumn(self.consume_ident()?))
         } else if self.maybe(&[Token::Rename, Token::To])? {
-            self.parse_alter_rename(table)
+            // ... RENAME TO <new_table_name>
+            Ok(AlterAction::RenameTo(self.consume_ident()?))
         } else {
             Err(QueryErr::UnexpectedToken {
                 expected: "ADD, DROP, or RENAME".into(),
-                found: format!("{:?}", self.curr),
-            })
+                found: format!("'{}'", self.curr),
+            }
+            .at(self.pos))
         }
     }
-    fn parse_alter_add(&mut self, table: Box<str>) -> Result<Stmt> {
-        // ... ADD COLUMN <col_name> <col_type>
-        let col_name = self.consume_ident()?;
-        let col_type = self.consume_type()?;
-        let column = (col_name, col_type);
-        Ok(Stmt::AlterAdd { table, column })
-    }
-
-    fn parse_alter_drop(&mut self, table: Box<str>) -> Result<Stmt> {
-        // ... DROP COLUMN <col_name>
-        let column = self.consume_ident()?;
-        Ok(Stmt::AlterDrop { table, column })
-    }
-
-    fn parse_alter_rename(&mut self, table: Box<str>) -> Result<Stmt> {
-        // ... RENAME TO <new_table_name>
-        let new_name = self.consume_ident()?;
-        Ok(Stmt::AlterRename { table, new_name })
-    }
 
     fn parse_delete(&mut self) -> Result<Stmt> {
         // DELETE FROM <table> [WHERE]
         self.expect(&[Token::Delete, Token::From])?;
-        let table = self.consume_ident()?;
+        let table = self.consume_name()?;
         // TODO: 최소 구현 우선
         let where_clause = None;
         Ok(Stmt::Delete {
@@ -376,7 +1037,7 @@ impl Parser {
 
     fn parse_truncate(&mut self) -> Result<Stmt> {
         self.expect(&[Token::Truncate, Token::Table])?;
-        let table = self.consume_ident()?;
+        let table = self.consume_name()?;
         Ok(Stmt::Truncate { table })
     }
 
@@ -384,7 +1045,7 @@ impl Parser {
         // DROP TABLE [IF EXISTS] <table> [RESTRICT|CASCADE]
         self.expect(&[Token::Drop, Token::Table])?;
         let if_exists = self.maybe(&[Token::If, Token::Exists])?;
-        let table = self.consume_ident()?;
+        let table = self.consume_name()?;
         let cascade = !self.maybe(&[Token::Restrict])? && self.maybe(&[Token::Cascade])?;
         Ok(Stmt::Drop {
             table,
@@ -393,6 +1054,147 @@ impl Parser {
         })
     }
 
+    fn parse_pragma(&mut self) -> Result<Stmt> {
+        // PRAGMA <name> [= <value>]
+        self.expect(&[Token::Pragma])?;
+        let name = self.consume_ident()?;
+        let value = if !self.maybe(&[Token::OpEq])? {
+            None
+        } else if self.maybe(&[Token::On])? {
+            // ON, OFF는 TRUE, FALSE와 같음
+            Some(Expr::Bool(true))
+        } else if matches!(&self.curr, Token::Ident(s) if s.eq_ignore_ascii_case("off")) {
+            self.next()?;
+            Some(Expr::Bool(false))
+        } else {
+            Some(self.parse_expr(0)?)
+        };
+        Ok(Stmt::Pragma { name, value })
+    }
+
+    fn parse_listen(&mut self) -> Result<Stmt> {
+        // LISTEN <channel>
+        self.expect(&[Token::Listen])?;
+        let channel = self.consume_ident()?;
+        Ok(Stmt::Listen { channel })
+    }
+
+    fn parse_notify(&mut self) -> Result<Stmt> {
+        // NOTIFY <channel> [, '<payload>']
+        self.expect(&[Token::Notify])?;
+        let channel = self.consume_ident()?;
+        let payload = if self.maybe(&[Token::Comma])? {
+            match self.next()? {
+                Token::Text(text) => text.into_boxed_str(),
+                tok => {
+                    return Err(QueryErr::UnexpectedToken {
+                        expected: "payload string".into(),
+                        found: format!("'{}'", tok),
+                    }
+                    .at(self.prev_pos));
+                }
+            }
+        } else {
+            "".into()
+        };
+        Ok(Stmt::Notify { channel, payload })
+    }
+
+    fn parse_copy(&mut self) -> Result<Stmt> {
+        // COPY <table> {FROM | TO} '<path>' (FORMAT <format>)
+        self.expect(&[Token::Copy])?;
+        let table = self.consume_name()?;
+        let export = match self.next()? {
+            Token::From => false,
+            Token::To => true,
+            tok => {
+                return Err(QueryErr::UnexpectedToken {
+                    expected: "FROM or TO".into(),
+                    found: format!("'{}'", tok),
+                }
+                .at(self.prev_pos));
+            }
+        };
+        let path = match self.next()? {
+            Token::Text(path) => path.into_boxed_str(),
+            tok => {
+                return Err(QueryErr::UnexpectedToken {
+                    expected: "file path string".into(),
+                    found: format!("'{}'", tok),
+                }
+                .at(self.prev_pos));
+            }
+        };
+        let mut format = None;
+        for (name, value) in self.parse_list_clause(true, |p| {
+            Ok((p.consume_ident()?.to_uppercase(), p.consume_ident()?))
+        })? {
+            match name.as_str() {
+                "FORMAT" => format = Some(value.to_lowercase().into_boxed_str()),
+                _ => {
+                    return Err(QueryErr::InvalidExpr(format!(
+                        "unknown COPY option '{}'",
+                        name
+                    )));
+                }
+            }
+        }
+        let format = format
+            .ok_or_else(|| QueryErr::InvalidExpr("COPY requires the FORMAT option".into()))?;
+        Ok(Stmt::Copy {
+            table,
+            path,
+            export,
+            format,
+        })
+    }
+
+    fn parse_transaction(&mut self) -> Result<Stmt> {
+        // {BEGIN | COMMIT | ROLLBACK} [TRANSACTION]
+        let stmt = match self.next()? {
+            Token::Begin => Stmt::Begin,
+            Token::Commit => Stmt::Commit,
+            _ => Stmt::Rollback,
+        };
+        self.maybe(&[Token::Transaction])?;
+        Ok(stmt)
+    }
+
+    fn parse_explain(&mut self) -> Result<Stmt> {
+        // EXPLAIN (PROFILE [, FORMAT {TEXT | JSON}]) <stmt>
+        self.expect(&[Token::Explain])?;
+        let options = self.parse_list_clause(true, |p| {
+            let name = p.consume_ident()?.to_uppercase();
+            match name.as_str() {
+                "FORMAT" => Ok((name, Some(p.consume_ident()?.to_uppercase()))),
+                _ => Ok((name, None)),
+            }
+        })?;
+        let mut profile = false;
+        let mut json = false;
+        for (name, value) in options {
+            match (name.as_str(), value.as_deref()) {
+                ("PROFILE", _) => profile = true,
+                ("FORMAT", Some("TEXT")) => json = false,
+                ("FORMAT", Some("JSON")) => json = true,
+                (name, value) => {
+                    return Err(QueryErr::InvalidExpr(format!(
+                        "unknown EXPLAIN option '{}'",
+                        value.unwrap_or(name)
+                    )));
+                }
+            }
+        }
+        // 실행 계획만 보여주는 EXPLAIN은 아직 없으므로 PROFILE이 필요함
+        if !profile {
+            return Err(QueryErr::InvalidExpr(
+                "EXPLAIN requires the PROFILE option".into(),
+            ));
+        }
+        let stmt = self.parse_stmt()?.boxed();
+        Ok(Stmt::Explain { stmt, json })
+    }
+
     fn parse_list_clause<T, F>(&mut self, with_parens: bool, mut parse_fn: F) -> Result<Vec<T>>
     where
         F: FnMut(&mut Self) -> Result<T>,
@@ -418,8 +1220,30 @@ impl Parser {
             Token::Ident(name) => Ok(name.into_boxed_str()),
             tok => Err(QueryErr::UnexpectedToken {
                 expected: "identifier".into(),
-                found: format!("{:?}", tok),
-            }),
+                found: format!("'{}'", tok),
+            }
+            .at(self.prev_pos)),
+        }
+    }
+
+    /// 테이블 이름: <name> 또는 <schema>.<name> (점으로 이은 이름 그대로 사용)
+    fn consume_name(&mut self) -> Result<Box<str>> {
+        let mut name = self.consume_ident()?.into_string();
+        while self.maybe(&[Token::Dot])? {
+            name.push('.');
+            name.push_str(&self.consume_ident()?);
+        }
+        Ok(name.into_boxed_str())
+    }
+
+    fn consume_count(&mut self) -> Result<u64> {
+        match self.next()? {
+            Token::Int(n) if n >= 0 => Ok(n as u64),
+            tok => Err(QueryErr::UnexpectedToken {
+                expected: "non-negative integer".into(),
+                found: format!("'{}'", tok),
+            }
+            .at(self.prev_pos)),
         }
     }
 
@@ -431,14 +1255,15 @@ impl Parser {
             Token::TextType => Ok("TEXT".into()),
             tok => Err(QueryErr::UnexpectedToken {
                 expected: "type".into(),
-                found: format!("{:?}", tok),
-            }),
+                found: format!("'{}'", tok),
+            }
+            .at(self.prev_pos)),
         }
     }
 
     fn parse_expr(&mut self, prec: u8) -> Result<Expr> {
         let mut left = self.parse_unary()?;
-        while prec < Self::precedence(&self.curr) {
+        while prec < self.infix_precedence() {
             left = self.parse_binary(left)?;
         }
         Ok(left)
@@ -451,29 +1276,171 @@ impl Parser {
             Token::Int(n) => Ok(Expr::Int(n)),
             Token::Float(f) => Ok(Expr::Float(f)),
             Token::Text(t) => Ok(Expr::Text(t.into_boxed_str())),
+            Token::Param(n) => Ok(Expr::Param(n)),
+            Token::Ident(i) if self.curr == Token::LParen => self.parse_call(i.into_boxed_str()),
+            // [<schema>.]<table>.<column>
+            Token::Ident(mut name) if self.curr == Token::Dot => {
+                while self.maybe(&[Token::Dot])? {
+                    name.push('.');
+                    name.push_str(&self.consume_ident()?);
+                }
+                Ok(Expr::Ident(name.into_boxed_str()))
+            }
             Token::Ident(i) => Ok(Expr::Ident(i.into_boxed_str())),
             op @ (Token::Not | Token::OpSub) => {
                 let right = self.parse_expr(7)?.boxed();
                 Ok(Expr::Unary { op, right })
             }
             Token::LParen => self.parse_group(),
+            // CAST(<expr> AS <type>)
+            Token::Cast => {
+                self.expect(&[Token::LParen])?;
+                let expr = self.parse_expr(0)?.boxed();
+                self.expect(&[Token::As])?;
+                let ty = self.consume_type()?;
+                self.expect(&[Token::RParen])?;
+                Ok(Expr::Cast { expr, ty })
+            }
+            Token::Exists => {
+                self.expect(&[Token::LParen])?;
+                let stmt = self.parse_query()?;
+                self.expect(&[Token::RParen])?;
+                Ok(Expr::Exists(stmt.boxed()))
+            }
             tok => Err(QueryErr::UnexpectedToken {
                 expected: "expression (literal, identifier, or '(')".into(),
-                found: format!("{:?}", tok),
-            }),
+                found: format!("'{}'", tok),
+            }
+            .at(self.prev_pos)),
         }
     }
 
-    fn parse_group(&mut self) -> Result<Expr> {
-        let expr = self.parse_expr(0)?;
+    fn parse_call(&mut self, name: Box<str>) -> Result<Expr> {
+        // <name>([<arg1>, <arg2>, ... [ORDER BY <expr> [ASC|DESC], ...]]), COUNT(*)
+        // 뒤에 OVER ([PARTITION BY <expr>, ...] [ORDER BY <expr> [ASC|DESC], ...])가 오면 윈도우 함수
+        self.expect(&[Token::LParen])?;
+        let args = if self.curr == Token::RParen || self.maybe(&[Token::OpMul])? {
+            vec![]
+        } else {
+            self.parse_list_clause(false, |p| p.parse_expr(0))?
+        };
+        let order_by = if self.maybe(&[Token::Order, Token::By])? {
+            Some(self.parse_order_by()?)
+        } else {
+            None
+        };
         self.expect(&[Token::RParen])?;
-        Ok(expr)
+        let func = Expr::FunctionCall {
+            name,
+            args,
+            order_by,
+        };
+        if !self.maybe(&[Token::Over])? {
+            return Ok(func);
+        }
+        self.expect(&[Token::LParen])?;
+        let partition_by = if self.maybe(&[Token::Partition, Token::By])? {
+            self.parse_list_clause(false, |p| p.parse_expr(0))?
+        } else {
+            vec![]
+        };
+        let order_by = if self.maybe(&[Token::Order, Token::By])? {
+            self.parse_order_by()?
+        } else {
+            vec![]
+        };
+        self.expect(&[Token::RParen])?;
+        Ok(Expr::Window {
+            func: func.boxed(),
+            partition_by,
+            order_by,
+        })
     }
 
-    fn parse_binary(&mut self, left: Expr) -> Result<Expr> {
+    fn parse_order_by(&mut self) -> Result<Vec<(Expr, Order)>> {
+        // ... ORDER BY <expr1> [ASC|DESC] [NULLS {FIRST|LAST}], ...
+        self.parse_list_clause(false, |p| {
+            let expr = p.parse_expr(0)?;
+            let asc = p.maybe(&[Token::Asc])? || !p.maybe(&[Token::Desc])?;
+            let mut order = if asc { Order::ASC } else { Order::DESC };
+            // NULLS, FIRST, LAST는 컬럼 이름으로도 쓸 수 있도록 키워드로 만들지 않음
+            if p.maybe_word("NULLS")? {
+                order.nulls_first = if p.maybe_word("FIRST")? {
+                    true
+                } else if p.maybe_word("LAST")? {
+                    false
+                } else {
+                    return Err(QueryErr::UnexpectedToken {
+                        expected: "FIRST or LAST".into(),
+                        found: format!("'{}'", p.curr),
+                    }
+                    .at(p.pos));
+                };
+            }
+            Ok((expr, order))
+        })
+    }
+
+    // 키워드가 아닌 단어를 대소문자 구분 없이 확인하고 넘김
+    fn maybe_word(&mut self, word: &str) -> Result<bool> {
+        if matches!(&self.curr, Token::Ident(s) if s.eq_ignore_ascii_case(word)) {
+            self.next()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn parse_group(&mut self) -> Result<Expr> {
+        // 괄호 안이 SELECT면 스칼라 서브쿼리
+        let expr = if self.curr == Token::Select {
+            Expr::Subquery(self.parse_query()?.boxed())
+        } else {
+            self.parse_expr(0)?
+        };
+        self.expect(&[Token::RParen])?;
+        Ok(expr)
+    }
+
+    /// 현재 토큰을 중위 연산자로 볼 때의 우선순위 (`NOT BETWEEN`은 두 토큰을 함께 봄)
+    fn infix_precedence(&self) -> u8 {
+        if self.curr == Token::Not && self.peek == Token::Between {
+            Self::precedence(&Token::Between)
+        } else {
+            Self::precedence(&self.curr)
+        }
+    }
+
+    fn parse_binary(&mut self, left: Expr) -> Result<Expr> {
         let token = self.next()?;
         let prec = Self::precedence(&token);
         match token {
+            // <expr> IS [NOT] NULL
+            Token::Is => {
+                let negated = self.maybe(&[Token::Not])?;
+                self.expect(&[Token::Null])?;
+                Ok(Expr::IsNull {
+                    expr: left.boxed(),
+                    negated,
+                })
+            }
+            // <expr> [NOT] BETWEEN <low> AND <high>
+            Token::Between | Token::Not => {
+                let negated = token == Token::Not;
+                if negated {
+                    self.expect(&[Token::Between])?;
+                }
+                let prec = Self::precedence(&Token::Between);
+                let low = self.parse_expr(prec)?.boxed();
+                self.expect(&[Token::And])?;
+                let high = self.parse_expr(prec)?.boxed();
+                Ok(Expr::Between {
+                    expr: left.boxed(),
+                    low,
+                    high,
+                    negated,
+                })
+            }
             op if prec > 0 => {
                 let left = left.boxed();
                 let right = self.parse_expr(prec)?.boxed();
@@ -481,12 +1448,44 @@ impl Parser {
             }
             _ => Err(QueryErr::UnexpectedToken {
                 expected: "binary operator".to_string(),
-                found: format!("{:?}", token),
-            }),
+                found: format!("'{}'", token),
+            }
+            .at(self.prev_pos)),
         }
     }
 }
 
+/// 복합 쿼리의 마지막이 아닌 곳에 ORDER BY나 LIMIT이 붙었는지 확인
+fn compound(op: SetOp, all: bool, left: Stmt, right: Stmt) -> Stmt {
+    Stmt::Compound {
+        op,
+        all,
+        left: left.boxed(),
+        right: right.boxed(),
+        order_by: None,
+        limit: None,
+        offset: None,
+    }
+}
+
+fn has_tail(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Select {
+            order_by,
+            limit,
+            offset,
+            ..
+        }
+        | Stmt::Compound {
+            order_by,
+            limit,
+            offset,
+            ..
+        } => order_by.is_some() || limit.is_some() || offset.is_some(),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -508,6 +1507,7 @@ mod tests {
                 table,
                 columns,
                 if_not_exists,
+                ..
             } => {
                 assert_eq!(table.as_ref(), "users");
                 assert_eq!(columns.len(), 2);
@@ -525,6 +1525,7 @@ mod tests {
                 table,
                 columns,
                 if_not_exists,
+                ..
             } => {
                 assert_eq!(table.as_ref(), "items");
                 assert_eq!(columns.len(), 1);
@@ -532,6 +1533,89 @@ mod tests {
             }
             _ => panic!("Expected Create stmt"),
         }
+
+        let input_temp = "CREATE TEMPORARY TABLE scratch (n INTEGER);";
+        let stmt = parse(input_temp);
+        match stmt {
+            Stmt::Create {
+                table, temporary, ..
+            } => {
+                assert_eq!(table.as_ref(), "scratch");
+                assert!(temporary);
+            }
+            _ => panic!("Expected Create stmt"),
+        }
+    }
+
+    #[test]
+    fn test_create_foreign_key() {
+        let input = "CREATE TABLE pets (owner TEXT REFERENCES friends (name), kind TEXT, age INT,
+             FOREIGN KEY (kind, age) REFERENCES kinds (name, max_age));";
+        match parse(input) {
+            Stmt::Create {
+                columns,
+                foreign_keys,
+                ..
+            } => {
+                assert_eq!(columns.len(), 3);
+                assert_eq!(
+                    foreign_keys,
+                    vec![
+                        ForeignKey {
+                            columns: vec!["owner".into()],
+                            table: "friends".into(),
+                            ref_columns: vec!["name".into()],
+                        },
+                        ForeignKey {
+                            columns: vec!["kind".into(), "age".into()],
+                            table: "kinds".into(),
+                            ref_columns: vec!["name".into(), "max_age".into()],
+                        },
+                    ]
+                );
+            }
+            _ => panic!("Expected Create stmt"),
+        }
+    }
+
+    #[test]
+    fn test_create_trigger() {
+        let input = "CREATE TRIGGER audit AFTER UPDATE ON users BEGIN
+                 INSERT INTO log VALUES (OLD.name, NEW.name);
+                 NOTIFY users;
+             END;";
+        match parse(input) {
+            Stmt::CreateTrigger {
+                name,
+                event,
+                table,
+                body,
+                if_not_exists,
+            } => {
+                assert_eq!(name.as_ref(), "audit");
+                assert_eq!(event, TriggerEvent::Update);
+                assert_eq!(table.as_ref(), "users");
+                assert_eq!(body.len(), 2);
+                assert!(matches!(body[0], Stmt::InsertValues { .. }));
+                assert!(!if_not_exists);
+            }
+            _ => panic!("Expected CreateTrigger stmt"),
+        }
+
+        for input in [
+            "CREATE TRIGGER t AFTER DELETE ON users BEGIN NOTIFY ch; END;",
+            "CREATE TRIGGER t AFTER INSERT ON users BEGIN NOTIFY ch;",
+            "CREATE TRIGGER t AFTER INSERT ON users BEGIN DROP TABLE users; END;",
+            "CREATE TRIGGER t AFTER INSERT ON users BEGIN BEGIN; END;",
+            "CREATE TRIGGER t AFTER INSERT ON users BEGIN DELETE FROM users; END;",
+        ] {
+            let lexer = Lexer::new(input);
+            assert!(
+                Parser::new(lexer).and_then(|mut p| p.parse()).is_err(),
+                "{}",
+                input
+            );
+        }
     }
 
     #[test]
@@ -573,6 +1657,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_values_query() {
+        match parse("VALUES (1, 'a'), (2, 'b');") {
+            Stmt::Values(rows) => {
+                assert_eq!(rows.len(), 2);
+                assert_eq!(rows[1][1], Expr::Text("b".into()));
+            }
+            _ => panic!("Expected Values stmt"),
+        }
+        // 합친 쿼리의 마지막이 VALUES여도 ORDER BY는 결과 전체에 적용됨
+        match parse("INSERT INTO users (id) SELECT id FROM old UNION VALUES (3) ORDER BY id;") {
+            Stmt::InsertQuery {
+                table,
+                columns,
+                query,
+            } => {
+                assert_eq!(table.as_ref(), "users");
+                assert_eq!(columns.len(), 1);
+                assert!(matches!(
+                    *query,
+                    Stmt::Compound {
+                        order_by: Some(_),
+                        ..
+                    }
+                ));
+            }
+            _ => panic!("Expected InsertQuery stmt"),
+        }
+    }
+
     #[test]
     fn test_select() {
         let input = "SELECT id, name FROM users;";
@@ -584,9 +1698,9 @@ mod tests {
                 distinct,
                 ..
             } => {
-                assert_eq!(table.as_ref(), "users");
+                assert_eq!(table, Source::Table("users".into()));
                 assert_eq!(columns.len(), 2);
-                assert!(!distinct);
+                assert!(distinct.is_none());
             }
             _ => panic!("Expected Select stmt"),
         }
@@ -604,10 +1718,98 @@ mod tests {
         let stmt = parse(input_distinct);
         match stmt {
             Stmt::Select { distinct, .. } => {
-                assert!(distinct);
+                assert_eq!(distinct, Some(vec![]));
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+
+        let input_distinct_on = "SELECT DISTINCT ON (dept, age + 1) name FROM users;";
+        match parse(input_distinct_on) {
+            Stmt::Select { distinct, .. } => {
+                let keys = distinct.expect("DISTINCT ON keys");
+                assert_eq!(keys.len(), 2);
+                assert_eq!(keys[0], Expr::Ident("dept".into()));
             }
             _ => panic!("Expected Select stmt"),
         }
+
+        let input_order = "SELECT name FROM users ORDER BY age DESC, name ASC, id;";
+        let stmt = parse(input_order);
+        match stmt {
+            Stmt::Select { order_by, .. } => {
+                assert_eq!(
+                    order_by,
+                    Some(vec![
+                        (Expr::Ident("age".into()), Order::DESC),
+                        (Expr::Ident("name".into()), Order::ASC),
+                        (Expr::Ident("id".into()), Order::ASC),
+                    ])
+                );
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+
+        let input_nulls = "SELECT name FROM users ORDER BY age DESC NULLS LAST, name nulls first;";
+        match parse(input_nulls) {
+            Stmt::Select { order_by, .. } => {
+                let orders: Vec<Order> = order_by.unwrap().into_iter().map(|(_, o)| o).collect();
+                assert_eq!(
+                    orders,
+                    vec![
+                        Order {
+                            asc: false,
+                            nulls_first: false
+                        },
+                        Order {
+                            asc: true,
+                            nulls_first: true
+                        },
+                    ]
+                );
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+        assert!(
+            Parser::new(Lexer::new("SELECT * FROM users ORDER BY age NULLS;"))
+                .and_then(|mut p| p.parse())
+                .is_err()
+        );
+
+        let input_limit = "SELECT * FROM users LIMIT 10 OFFSET 20;";
+        let stmt = parse(input_limit);
+        match stmt {
+            Stmt::Select { limit, offset, .. } => {
+                assert_eq!(limit, Some(10));
+                assert_eq!(offset, Some(20));
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+
+        let input_where = "SELECT name FROM users WHERE age < 30 AND male = FALSE;";
+        let stmt = parse(input_where);
+        match stmt {
+            Stmt::Select { where_clause, .. } => match where_clause {
+                Some(Expr::Binary { op, left, right }) => {
+                    assert_eq!(op, Token::And);
+                    assert!(matches!(
+                        *left,
+                        Expr::Binary {
+                            op: Token::OpLt,
+                            ..
+                        }
+                    ));
+                    assert!(matches!(
+                        *right,
+                        Expr::Binary {
+                            op: Token::OpEq,
+                            ..
+                        }
+                    ));
+                }
+                _ => panic!("Expected WHERE condition"),
+            },
+            _ => panic!("Expected Select stmt"),
+        }
     }
 
     #[test]
@@ -623,6 +1825,41 @@ mod tests {
             }
             _ => panic!("Expected Update stmt"),
         }
+
+        match parse("UPDATE a SET x = b.x FROM bees AS b WHERE a.id = b.id;") {
+            Stmt::Update {
+                from, where_clause, ..
+            } => {
+                assert_eq!(from, Some((Source::Table("bees".into()), Some("b".into()))));
+                assert!(where_clause.is_some());
+            }
+            _ => panic!("Expected Update stmt"),
+        }
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        match parse("SELECT main.users.id, u.name FROM main.users u;") {
+            Stmt::Select { table, columns, .. } => {
+                assert_eq!(table, Source::Table("main.users".into()));
+                assert_eq!(
+                    columns,
+                    vec![
+                        Expr::Ident("main.users.id".into()),
+                        Expr::Ident("u.name".into())
+                    ]
+                );
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+        assert!(matches!(
+            parse("INSERT INTO main.users VALUES (1);"),
+            Stmt::InsertValues { table, .. } if table.as_ref() == "main.users"
+        ));
+        assert!(matches!(
+            parse("CREATE TABLE main.users (id INT);"),
+            Stmt::Create { table, .. } if table.as_ref() == "main.users"
+        ));
     }
 
     #[test]
@@ -630,32 +1867,44 @@ mod tests {
         let input_add = "ALTER TABLE users ADD COLUMN age INT;";
         let stmt = parse(input_add);
         match stmt {
-            Stmt::AlterAdd { table, column } => {
+            Stmt::Alter { table, actions } => {
                 assert_eq!(table.as_ref(), "users");
-                assert_eq!(column, ("age".into(), "INTEGER".into()));
+                assert_eq!(
+                    actions,
+                    vec![AlterAction::AddColumn("age".into(), "INTEGER".into())]
+                );
             }
-            _ => panic!("Expected AlterAdd stmt"),
+            _ => panic!("Expected Alter stmt"),
         }
 
-        let input_drop = "ALTER TABLE users DROP COLUMN age;";
-        let stmt = parse(input_drop);
+        let input_rename = "ALTER TABLE users RENAME TO super_users;";
+        let stmt = parse(input_rename);
         match stmt {
-            Stmt::AlterDrop { table, column } => {
+            Stmt::Alter { table, actions } => {
                 assert_eq!(table.as_ref(), "users");
-                assert_eq!(column.as_ref(), "age");
+                assert_eq!(actions, vec![AlterAction::RenameTo("super_users".into())]);
             }
-            _ => panic!("Expected AlterDrop stmt"),
+            _ => panic!("Expected Alter stmt"),
         }
 
-        let input_rename = "ALTER TABLE users RENAME TO super_users;";
-        let stmt = parse(input_rename);
+        let input_many = "ALTER TABLE users ADD COLUMN a INT, ADD COLUMN b TEXT, DROP COLUMN c;";
+        let stmt = parse(input_many);
         match stmt {
-            Stmt::AlterRename { table, new_name } => {
-                assert_eq!(table.as_ref(), "users");
-                assert_eq!(new_name.as_ref(), "super_users");
+            Stmt::Alter { actions, .. } => {
+                assert_eq!(
+                    actions,
+                    vec![
+                        AlterAction::AddColumn("a".into(), "INTEGER".into()),
+                        AlterAction::AddColumn("b".into(), "TEXT".into()),
+                        AlterAction::DropColumn("c".into()),
+                    ]
+                );
             }
-            _ => panic!("Expected AlterRename stmt"),
+            _ => panic!("Expected Alter stmt"),
         }
+
+        let lexer = Lexer::new("ALTER TABLE users DROP COLUMN c, RENAME TO people;");
+        assert!(Parser::new(lexer).and_then(|mut p| p.parse()).is_err());
     }
 
     #[test]
@@ -711,4 +1960,658 @@ mod tests {
             _ => panic!("Expected Drop stmt"),
         }
     }
+
+    #[test]
+    fn test_pragma() {
+        let input = "PRAGMA seed = 42;";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Pragma { name, value } => {
+                assert_eq!(name.as_ref(), "seed");
+                assert_eq!(value, Some(Expr::Int(42)));
+            }
+            _ => panic!("Expected Pragma stmt"),
+        }
+
+        // 값 없이 현재 값 조회
+        let input_query = "PRAGMA seed;";
+        let stmt = parse(input_query);
+        match stmt {
+            Stmt::Pragma { name, value } => {
+                assert_eq!(name.as_ref(), "seed");
+                assert!(value.is_none());
+            }
+            _ => panic!("Expected Pragma stmt"),
+        }
+
+        for (input, on) in [
+            ("PRAGMA strict = on;", true),
+            ("PRAGMA strict = OFF;", false),
+        ] {
+            assert_eq!(
+                parse(input),
+                Stmt::Pragma {
+                    name: "strict".into(),
+                    value: Some(Expr::Bool(on)),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_listen_notify() {
+        assert_eq!(
+            parse("LISTEN orders;"),
+            Stmt::Listen {
+                channel: "orders".into()
+            }
+        );
+        assert_eq!(
+            parse("NOTIFY orders, 'new';"),
+            Stmt::Notify {
+                channel: "orders".into(),
+                payload: "new".into(),
+            }
+        );
+        assert_eq!(
+            parse("NOTIFY orders;"),
+            Stmt::Notify {
+                channel: "orders".into(),
+                payload: "".into(),
+            }
+        );
+
+        let lexer = Lexer::new("NOTIFY orders, 42;");
+        assert!(Parser::new(lexer).and_then(|mut p| p.parse()).is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        match parse("EXPLAIN (PROFILE, FORMAT json) SELECT a FROM t;") {
+            Stmt::Explain { stmt, json } => {
+                assert!(json);
+                assert!(matches!(*stmt, Stmt::Select { .. }));
+            }
+            _ => panic!("Expected Explain stmt"),
+        }
+        assert!(matches!(
+            parse("EXPLAIN (PROFILE) SELECT a FROM t;"),
+            Stmt::Explain { json: false, .. }
+        ));
+
+        for input in [
+            "EXPLAIN SELECT a FROM t;",
+            "EXPLAIN (FORMAT JSON) SELECT a FROM t;",
+            "EXPLAIN (PROFILE, FORMAT xml) SELECT a FROM t;",
+        ] {
+            let lexer = Lexer::new(input);
+            assert!(Parser::new(lexer).and_then(|mut p| p.parse()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_function_call() {
+        let input = "SELECT RANDOM(), RANDOM() % 10, RANDOM_TEXT(8), COUNT(*) FROM users;";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select { columns, .. } => {
+                assert_eq!(columns.len(), 4);
+                assert_eq!(
+                    columns[0],
+                    Expr::FunctionCall {
+                        name: "RANDOM".into(),
+                        args: vec![],
+                        order_by: None,
+                    }
+                );
+                match &columns[1] {
+                    Expr::Binary { op, left, .. } => {
+                        assert_eq!(op, &Token::OpMod);
+                        assert!(matches!(left.as_ref(), Expr::FunctionCall { .. }));
+                    }
+                    _ => panic!("Expected Binary expr"),
+                }
+                assert_eq!(
+                    columns[2],
+                    Expr::FunctionCall {
+                        name: "RANDOM_TEXT".into(),
+                        args: vec![Expr::Int(8)],
+                        order_by: None,
+                    }
+                );
+                assert_eq!(
+                    columns[3],
+                    Expr::FunctionCall {
+                        name: "COUNT".into(),
+                        args: vec![],
+                        order_by: None,
+                    }
+                );
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+    }
+
+    #[test]
+    fn test_table_sample() {
+        let input = "SELECT * FROM big TABLESAMPLE BERNOULLI (1);";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select { table, sample, .. } => {
+                assert_eq!(table, Source::Table("big".into()));
+                assert_eq!(sample, Some(("BERNOULLI".into(), Expr::Int(1))));
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+
+        let input_system = "SELECT * FROM big TABLESAMPLE system (2.5);";
+        let stmt = parse(input_system);
+        match stmt {
+            Stmt::Select { sample, .. } => {
+                assert_eq!(sample, Some(("SYSTEM".into(), Expr::Float(2.5))));
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+
+        let input_invalid = "SELECT * FROM big TABLESAMPLE RANDOM (1);";
+        let mut parser = Parser::new(Lexer::new(input_invalid)).unwrap();
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn test_group_by() {
+        let ident = |name: &str| Expr::Ident(name.into());
+        let group_by = |input: &str| match parse(input) {
+            Stmt::Select { group_by, .. } => group_by.expect("Expected GROUP BY"),
+            _ => panic!("Expected Select stmt"),
+        };
+
+        let sets = group_by("SELECT a, COUNT(*) FROM t GROUP BY a, b;");
+        assert_eq!(sets, vec![vec![ident("a"), ident("b")]]);
+
+        let sets = group_by("SELECT a, b FROM t GROUP BY ROLLUP (a, b);");
+        assert_eq!(
+            sets,
+            vec![vec![ident("a"), ident("b")], vec![ident("a")], vec![]]
+        );
+
+        let sets = group_by("SELECT a, b FROM t GROUP BY CUBE (a, b);");
+        assert_eq!(
+            sets,
+            vec![
+                vec![ident("a"), ident("b")],
+                vec![ident("a")],
+                vec![ident("b")],
+                vec![],
+            ]
+        );
+
+        let sets = group_by("SELECT a, b FROM t GROUP BY GROUPING SETS ((a, b), b, ());");
+        assert_eq!(
+            sets,
+            vec![vec![ident("a"), ident("b")], vec![ident("b")], vec![]]
+        );
+
+        // 일반 컬럼 이름으로도 사용할 수 있음
+        let sets = group_by("SELECT rollup FROM t GROUP BY rollup;");
+        assert_eq!(sets, vec![vec![ident("rollup")]]);
+
+        // CUBE는 풀기 전에 식 개수를 확인
+        let sets = group_by(&format!(
+            "SELECT * FROM t GROUP BY CUBE ({});",
+            ["a"; MAX_CUBE_EXPRS].join(", ")
+        ));
+        assert_eq!(sets.len(), 1 << MAX_CUBE_EXPRS);
+        for n in [MAX_CUBE_EXPRS + 1, 40] {
+            let input = format!(
+                "SELECT * FROM t GROUP BY CUBE ({});",
+                vec!["a"; n].join(", ")
+            );
+            let mut parser = Parser::new(Lexer::new(&input)).unwrap();
+            assert!(parser.parse().is_err());
+        }
+    }
+
+    #[test]
+    fn test_aggregate_order_by() {
+        let input = "SELECT STRING_AGG(name, ', ' ORDER BY age DESC, name) FROM users;";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select { columns, .. } => {
+                assert_eq!(
+                    columns[0],
+                    Expr::FunctionCall {
+                        name: "STRING_AGG".into(),
+                        args: vec![Expr::Ident("name".into()), Expr::Text(", ".into())],
+                        order_by: Some(vec![
+                            (Expr::Ident("age".into()), Order::DESC),
+                            (Expr::Ident("name".into()), Order::ASC),
+                        ]),
+                    }
+                );
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+    }
+
+    #[test]
+    fn test_join() {
+        let input =
+            "SELECT a.x, b.y FROM a JOIN b ON a.id = b.id INNER JOIN c AS z ON z.id = a.id;";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select {
+                table,
+                alias,
+                columns,
+                joins,
+                ..
+            } => {
+                assert_eq!(table, Source::Table("a".into()));
+                assert!(alias.is_none());
+                assert_eq!(columns[0], Expr::Ident("a.x".into()));
+                assert_eq!(joins.len(), 2);
+                assert_eq!(joins[0].kind, JoinKind::Inner);
+                assert_eq!(joins[0].table, Source::Table("b".into()));
+                assert_eq!(
+                    joins[0].on,
+                    Expr::Binary {
+                        op: Token::OpEq,
+                        left: Expr::Ident("a.id".into()).boxed(),
+                        right: Expr::Ident("b.id".into()).boxed(),
+                    }
+                );
+                assert_eq!(joins[1].table, Source::Table("c".into()));
+                assert_eq!(joins[1].alias.as_deref(), Some("z"));
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+
+        let input_outer = "SELECT * FROM a LEFT JOIN b ON a.id = b.id RIGHT OUTER JOIN c ON TRUE FULL JOIN d ON TRUE;";
+        let stmt = parse(input_outer);
+        match stmt {
+            Stmt::Select { joins, .. } => {
+                let kinds: Vec<_> = joins.iter().map(|j| j.kind).collect();
+                assert_eq!(kinds, vec![JoinKind::Left, JoinKind::Right, JoinKind::Full]);
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+
+        let input_cross = "SELECT * FROM a, b AS x CROSS JOIN c WHERE a.id = x.id;";
+        let stmt = parse(input_cross);
+        match stmt {
+            Stmt::Select {
+                joins,
+                where_clause,
+                ..
+            } => {
+                assert_eq!(joins.len(), 2);
+                assert!(joins.iter().all(|j| j.kind == JoinKind::Cross));
+                assert_eq!(joins[0].alias.as_deref(), Some("x"));
+                assert_eq!(joins[1].on, Expr::Bool(true));
+                assert!(where_clause.is_some());
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+
+        let input_alias = "SELECT f.name FROM friends f WHERE f.age > 1;";
+        let stmt = parse(input_alias);
+        match stmt {
+            Stmt::Select { alias, joins, .. } => {
+                assert_eq!(alias.as_deref(), Some("f"));
+                assert!(joins.is_empty());
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+    }
+
+    #[test]
+    fn test_derived_table() {
+        let input = "SELECT sub.x FROM (SELECT x FROM t WHERE x > 1) AS sub JOIN (SELECT y FROM u) v ON sub.x = v.y;";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select {
+                table,
+                alias,
+                joins,
+                ..
+            } => {
+                let Source::Subquery(inner) = table else {
+                    panic!("Expected subquery source");
+                };
+                assert!(matches!(
+                    *inner,
+                    Stmt::Select {
+                        where_clause: Some(_),
+                        ..
+                    }
+                ));
+                assert_eq!(alias.as_deref(), Some("sub"));
+                assert!(matches!(joins[0].table, Source::Subquery(_)));
+                assert_eq!(joins[0].alias.as_deref(), Some("v"));
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+    }
+
+    #[test]
+    fn test_scalar_subquery() {
+        let input = "SELECT name FROM t WHERE age > (SELECT AVG(age) FROM t);";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select {
+                where_clause: Some(Expr::Binary { op, right, .. }),
+                ..
+            } => {
+                assert_eq!(op, Token::OpGt);
+                assert!(matches!(*right, Expr::Subquery(_)));
+            }
+            _ => panic!("Expected Select stmt with WHERE"),
+        }
+    }
+
+    #[test]
+    fn test_exists() {
+        let input = "SELECT name FROM t WHERE NOT EXISTS (SELECT * FROM u WHERE u.id = t.id);";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select {
+                where_clause: Some(Expr::Unary { op, right }),
+                ..
+            } => {
+                assert_eq!(op, Token::Not);
+                assert!(matches!(*right, Expr::Exists(_)));
+            }
+            _ => panic!("Expected Select stmt with WHERE"),
+        }
+    }
+
+    #[test]
+    fn test_between() {
+        let input = "SELECT * FROM t WHERE x BETWEEN 1 + 1 AND 20 AND y NOT BETWEEN 'a' AND 'c';";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select {
+                where_clause: Some(Expr::Binary { op, left, right }),
+                ..
+            } => {
+                assert_eq!(op, Token::And);
+                assert_eq!(
+                    *left,
+                    Expr::Between {
+                        expr: Expr::Ident("x".into()).boxed(),
+                        low: Expr::Binary {
+                            op: Token::OpAdd,
+                            left: Expr::Int(1).boxed(),
+                            right: Expr::Int(1).boxed(),
+                        }
+                        .boxed(),
+                        high: Expr::Int(20).boxed(),
+                        negated: false,
+                    }
+                );
+                assert!(matches!(*right, Expr::Between { negated: true, .. }));
+            }
+            _ => panic!("Expected Select stmt with WHERE"),
+        }
+    }
+
+    #[test]
+    fn test_is_null() {
+        let input = "SELECT * FROM t WHERE a IS NULL OR b + 1 IS NOT NULL;";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select {
+                where_clause: Some(Expr::Binary { op, left, right }),
+                ..
+            } => {
+                assert_eq!(op, Token::Or);
+                assert_eq!(
+                    *left,
+                    Expr::IsNull {
+                        expr: Expr::Ident("a".into()).boxed(),
+                        negated: false,
+                    }
+                );
+                assert!(matches!(
+                    *right,
+                    Expr::IsNull { expr, negated: true } if matches!(*expr, Expr::Binary { .. })
+                ));
+            }
+            _ => panic!("Expected Select stmt with WHERE"),
+        }
+    }
+
+    #[test]
+    fn test_cast() {
+        let input = "SELECT CAST(age AS TEXT), CAST('1.5' AS FLOAT) + 1 FROM t;";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Select { columns, .. } => {
+                assert_eq!(
+                    columns[0],
+                    Expr::Cast {
+                        expr: Expr::Ident("age".into()).boxed(),
+                        ty: "TEXT".into(),
+                    }
+                );
+                assert!(
+                    matches!(&columns[1], Expr::Binary { left, .. } if matches!(**left, Expr::Cast { .. }))
+                );
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+    }
+
+    #[test]
+    fn test_union() {
+        let input =
+            "SELECT a FROM t UNION ALL SELECT b FROM u UNION SELECT c FROM v ORDER BY 1 LIMIT 2;";
+        let stmt = parse(input);
+        match stmt {
+            Stmt::Compound {
+                op,
+                all,
+                left,
+                right,
+                order_by,
+                limit,
+                ..
+            } => {
+                assert_eq!(op, SetOp::Union);
+                assert!(!all);
+                assert!(matches!(*left, Stmt::Compound { all: true, .. }));
+                assert!(matches!(
+                    *right,
+                    Stmt::Select {
+                        order_by: None,
+                        limit: None,
+                        ..
+                    }
+                ));
+                assert_eq!(order_by, Some(vec![(Expr::Int(1), Order::ASC)]));
+                assert_eq!(limit, Some(2));
+            }
+            _ => panic!("Expected Compound stmt"),
+        }
+
+        let lexer = Lexer::new("SELECT a FROM t LIMIT 1 UNION SELECT b FROM u;");
+        assert!(Parser::new(lexer).and_then(|mut p| p.parse()).is_err());
+    }
+    #[test]
+    fn test_with() {
+        let input = "WITH young AS (SELECT name FROM users WHERE age < 20), few AS (SELECT * FROM young LIMIT 2)
+             SELECT * FROM few UNION SELECT name FROM admins;";
+        match parse(input) {
+            Stmt::With { ctes, body } => {
+                let names: Vec<_> = ctes.iter().map(|(name, _)| name.as_ref()).collect();
+                assert_eq!(names, vec!["young", "few"]);
+                assert!(matches!(ctes[1].1, Stmt::Select { limit: Some(2), .. }));
+                assert!(matches!(*body, Stmt::Compound { .. }));
+            }
+            _ => panic!("Expected With stmt"),
+        }
+
+        let lexer = Lexer::new("WITH young SELECT * FROM young;");
+        assert!(Parser::new(lexer).and_then(|mut p| p.parse()).is_err());
+    }
+
+    #[test]
+    fn test_intersect_except() {
+        // a EXCEPT b INTERSECT c EXCEPT ALL d => (a EXCEPT (b INTERSECT c)) EXCEPT ALL d
+        let stmt = parse(
+            "SELECT a FROM t EXCEPT SELECT b FROM u INTERSECT SELECT c FROM v EXCEPT ALL SELECT d FROM w;",
+        );
+        match stmt {
+            Stmt::Compound {
+                op: SetOp::Except,
+                all: true,
+                left,
+                ..
+            } => match *left {
+                Stmt::Compound {
+                    op: SetOp::Except,
+                    all: false,
+                    left,
+                    right,
+                    ..
+                } => {
+                    assert!(matches!(*left, Stmt::Select { .. }));
+                    assert!(matches!(
+                        *right,
+                        Stmt::Compound {
+                            op: SetOp::Intersect,
+                            ..
+                        }
+                    ));
+                }
+                _ => panic!("Expected Compound stmt"),
+            },
+            _ => panic!("Expected Compound stmt"),
+        }
+    }
+
+    #[test]
+    fn test_window() {
+        match parse(
+            "SELECT RANK() OVER (PARTITION BY team ORDER BY score DESC), SUM(x) OVER () FROM t;",
+        ) {
+            Stmt::Select { columns, .. } => {
+                assert_eq!(
+                    columns[0],
+                    Expr::Window {
+                        func: Expr::FunctionCall {
+                            name: "RANK".into(),
+                            args: vec![],
+                            order_by: None,
+                        }
+                        .boxed(),
+                        partition_by: vec![Expr::Ident("team".into())],
+                        order_by: vec![(Expr::Ident("score".into()), Order::DESC)],
+                    }
+                );
+                assert!(matches!(
+                    &columns[1],
+                    Expr::Window { partition_by, order_by, .. } if partition_by.is_empty() && order_by.is_empty()
+                ));
+            }
+            _ => panic!("Expected Select stmt"),
+        }
+    }
+
+    #[test]
+    fn test_transaction() {
+        let lexer = Lexer::new("BEGIN; INSERT INTO t VALUES (1); COMMIT TRANSACTION; ROLLBACK;");
+        let stmts = Parser::new(lexer).and_then(|mut p| p.parse()).unwrap();
+        assert_eq!(stmts.len(), 4);
+        assert_eq!(stmts[0], Stmt::Begin);
+        assert_eq!(stmts[2], Stmt::Commit);
+        assert_eq!(stmts[3], Stmt::Rollback);
+    }
+
+    #[test]
+    fn test_copy() {
+        match parse("COPY logs FROM 'logs.ndjson' (FORMAT NDJSON);") {
+            Stmt::Copy {
+                table,
+                path,
+                export,
+                format,
+            } => {
+                assert_eq!(table.as_ref(), "logs");
+                assert_eq!(path.as_ref(), "logs.ndjson");
+                assert!(!export);
+                assert_eq!(format.as_ref(), "ndjson");
+            }
+            _ => panic!("Expected Copy stmt"),
+        }
+        assert!(matches!(
+            parse("COPY logs TO 'out.ndjson' (FORMAT ndjson);"),
+            Stmt::Copy { export: true, .. }
+        ));
+
+        for input in [
+            "COPY logs FROM 'logs.ndjson';",
+            "COPY logs INTO 'x' (FORMAT ndjson);",
+        ] {
+            let lexer = Lexer::new(input);
+            assert!(Parser::new(lexer).and_then(|mut p| p.parse()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let lexer =
+            Lexer::new("INSERT INTO users VALUES (?, $3); SELECT * FROM users WHERE id = ?;");
+        let mut parser = Parser::new(lexer).unwrap();
+        let stmts = parser.parse().unwrap();
+        assert_eq!(parser.param_count(), 4);
+        match &stmts[0] {
+            Stmt::InsertValues { values, .. } => {
+                assert_eq!(values[0], vec![Expr::Param(1), Expr::Param(3)]);
+            }
+            _ => panic!("Expected InsertValues stmt"),
+        }
+    }
+
+    #[test]
+    fn test_error_position() {
+        let lexer = Lexer::new("SELECT name\nFROM users\nWHERE (age > 3 FROM x;");
+        let err = Parser::new(lexer).and_then(|mut p| p.parse()).unwrap_err();
+        assert_eq!(err.position(), Some((3, 16)));
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 16: Expected ')', but found 'FROM'"
+        );
+
+        let lexer = Lexer::new("SELECT * FROM users WHERE");
+        let err = Parser::new(lexer).and_then(|mut p| p.parse()).unwrap_err();
+        assert_eq!(err.position(), Some((1, 26)));
+        assert!(err.to_string().ends_with("'end of input'"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let src = "SELECT a FROM t;\nSELECT (a FROM t;\nSELECT $x, a FROM t; SELECT b FROM t;\nSELECT FROM";
+        let (stmts, errors) = Parser::parse_all(Lexer::new(src));
+        assert_eq!(stmts.len(), 2);
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "line 2, column 11: Expected ')', but found 'FROM'",
+                "line 3, column 8: Invalid parameter: '$'",
+                "line 4, column 8: Expected expression (literal, identifier, or '('), but found 'FROM'",
+            ]
+        );
+
+        // 렉서 오류가 다음 문장의 첫 토큰에서 나도 앞 문장은 그대로
+        let (stmts, errors) =
+            Parser::parse_all(Lexer::new("SELECT a FROM t; $x a; SELECT b FROM t"));
+        assert_eq!(stmts.len(), 2);
+        assert_eq!(errors.len(), 1);
+        let (stmts, errors) = Parser::parse_all(Lexer::new("$x; 'open"));
+        assert!(stmts.is_empty());
+        assert_eq!(errors.len(), 2);
+    }
 }
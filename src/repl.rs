@@ -0,0 +1,671 @@
+use crate::executor::{Executor, Prepared, QueryResult};
+use crate::query::Lexer;
+use crate::query::error::QueryErr;
+use crate::query::lexer::Token;
+use crate::storage::{DataType, DataValue};
+use serde::Deserialize;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// 터미널에서 SQL을 한 줄씩 입력받아 실행하는 REPL
+pub struct Repl {
+    exec: Executor,
+    // `{tx}`는 입력을 받을 때마다 트랜잭션 상태로 바뀜
+    prompt: String,
+    // 결과가 화면보다 길면 $PAGER로 보여줌 (.pager on|off)
+    pager: bool,
+    // 결과 출력 형식 (.mode table|markdown)
+    mode: Mode,
+    // `run`에서 한 번만 설치한 Ctrl-C 핸들러 (`.watch` 중에 누르면 여기로 들어옴)
+    ctrl_c: Option<CtrlC>,
+}
+
+struct CtrlC {
+    watching: Arc<AtomicBool>,
+    pressed: Receiver<()>,
+}
+
+/// 결과 출력 형식 (설정 파일의 `mode`, `--mode`, `.mode`로 정함)
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    #[default]
+    Table,
+    Markdown,
+}
+
+impl Repl {
+    pub fn new(exec: Executor, prompt: String, mode: Mode) -> Self {
+        Self {
+            exec,
+            prompt,
+            pager: io::stdout().is_terminal(),
+            mode,
+            ctrl_c: None,
+        }
+    }
+
+    /// 프롬프트 템플릿의 `{tx}`를 트랜잭션이 열려 있으면 `*`, 아니면 빈 문자열로 바꿈
+    fn prompt(&self) -> String {
+        let tx = if self.exec.in_transaction() { "*" } else { "" };
+        self.prompt.replace("{tx}", tx)
+    }
+
+    pub fn run(&mut self) {
+        println!("SQuirreL REPL (type '.exit' or '.quit' to stop)");
+        match install_ctrl_c() {
+            Ok(ctrl_c) => self.ctrl_c = Some(ctrl_c),
+            Err(e) => println!("Failed to handle Ctrl-C: {}", e),
+        }
+        let mut buffer = String::new();
+        loop {
+            if buffer.is_empty() {
+                print!("{}", self.prompt());
+            } else {
+                print!("...  ");
+            }
+            io::stdout().flush().unwrap();
+            let line = io::stdin().lock().lines().next();
+            if let Some(Ok(input)) = line {
+                let trimmed = input.trim();
+                // '.'으로 시작하는 명령어는 SQL이 아니라 REPL 자체를 조작함
+                if trimmed.starts_with('.') {
+                    if !self.meta(trimmed) {
+                        break;
+                    }
+                } else if let Some((sql, pipe)) = split_pipe(&buffer, &input) {
+                    // SELECT ... \g | <명령어> 는 결과를 셸 명령어의 입력으로 넘김
+                    buffer.push_str(sql);
+                    let src = std::mem::take(&mut buffer);
+                    println!("{}", src);
+                    let result = self.exec.run(src);
+                    let text = self.format(result);
+                    match pipe {
+                        Some(cmd) => {
+                            if let Err(e) = run_with_input(shell(cmd), &text) {
+                                println!("Failed to run '{}': {}", cmd, e);
+                            }
+                        }
+                        None => self.output(&text),
+                    }
+                    self.print_notices();
+                    self.print_notifications();
+                } else if !trimmed.ends_with(";") || inside_trigger(&(buffer.clone() + &input)) {
+                    buffer.push_str(&input);
+                    buffer.push('\n');
+                } else {
+                    buffer.push_str(&input);
+                    let src = std::mem::take(&mut buffer);
+                    println!("{}", src);
+                    let result = self.exec.run(src);
+                    self.output(&self.format(result));
+                    self.print_notices();
+                    self.print_notifications();
+                }
+            } else if let Some(Err(e)) = line {
+                println!("Failed to read line: {}", e);
+            } else {
+                // 입력이 끝나면 (Ctrl-D, 파이프 종료) .exit과 같이 처리
+                println!();
+                break;
+            }
+        }
+    }
+
+    /// REPL 명령어를 처리하고, 계속 실행할지 여부를 돌려줌
+    fn meta(&mut self, input: &str) -> bool {
+        let (cmd, arg) = input
+            .split_once(char::is_whitespace)
+            .map_or((input, ""), |(cmd, arg)| (cmd, arg.trim()));
+        match (cmd, arg) {
+            (".exit" | ".quit", _) => return false,
+            (".pager", "on") => self.pager = true,
+            (".pager", "off") => self.pager = false,
+            (".pager", _) => println!("Usage: .pager on|off"),
+            (".shell", "") => println!("Usage: .shell <command>"),
+            (".shell", cmd) => {
+                if let Err(e) = shell(cmd).status() {
+                    println!("Failed to run '{}': {}", cmd, e);
+                }
+            }
+            (".size", _) => {
+                let rows = self
+                    .exec
+                    .database()
+                    .tables()
+                    .into_iter()
+                    .map(|t| vec![t.name, t.rows.to_string(), t.size_bytes.to_string()])
+                    .collect();
+                let result = QueryResult::Rows {
+                    columns: vec!["table".into(), "rows".into(), "bytes".into()],
+                    rows,
+                };
+                self.output(&self.format(result));
+            }
+            (".mode", "table") => self.mode = Mode::Table,
+            (".mode", "markdown") => self.mode = Mode::Markdown,
+            (".mode", _) => println!("Usage: .mode table|markdown"),
+            (".import_md", arg) => match arg.split_whitespace().collect::<Vec<_>>()[..] {
+                [file, table] => match self.import_markdown(file, table) {
+                    Ok(count) => println!("Imported {} rows into '{}'", count, table),
+                    Err(e) => println!("Failed to import '{}': {}", file, e),
+                },
+                _ => println!("Usage: .import_md <file> <table>"),
+            },
+            (".watch", arg) => match parse_watch(arg) {
+                Some((interval, query)) => {
+                    if let Err(e) = self.watch(interval, query) {
+                        println!("Failed to watch: {}", e);
+                    }
+                }
+                None => println!("Usage: .watch <seconds> <query> (Ctrl-C stops watching)"),
+            },
+            (cmd, _) => println!("Unknown command: '{}'", cmd),
+        }
+        true
+    }
+
+    /// Ctrl-C를 누르거나 오류가 날 때까지 `interval`마다 쿼리를 다시 실행해서 보여줌
+    ///
+    /// Ctrl-C는 REPL을 끝내지 않고 watch만 멈추고 프롬프트로 돌아감
+    fn watch(&mut self, interval: Duration, query: &str) -> io::Result<()> {
+        if let Some(ctrl_c) = &self.ctrl_c {
+            // watch 전에 눌린 Ctrl-C는 무시
+            while ctrl_c.pressed.try_recv().is_ok() {}
+            ctrl_c.watching.store(true, Ordering::SeqCst);
+        }
+        let result = self.watch_loop(interval, query);
+        if let Some(ctrl_c) = &self.ctrl_c {
+            ctrl_c.watching.store(false, Ordering::SeqCst);
+        }
+        result
+    }
+
+    fn watch_loop(&mut self, interval: Duration, query: &str) -> io::Result<()> {
+        loop {
+            let result = self.exec.run(query.to_string());
+            let failed = matches!(result, QueryResult::Error(_));
+            if io::stdout().is_terminal() {
+                // 화면을 지우고 커서를 맨 위로
+                print!("\x1B[2J\x1B[H");
+            }
+            println!("Every {}s: {}\n", interval.as_secs_f64(), query);
+            print!("{}", self.format(result));
+            io::stdout().flush()?;
+            if failed {
+                return Ok(());
+            }
+            match &self.ctrl_c {
+                Some(ctrl_c) => match ctrl_c.pressed.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    _ => return Ok(()),
+                },
+                None => std::thread::sleep(interval),
+            }
+        }
+    }
+
+    // 오류와 구분되도록 경고는 노란색 NOTICE로 보여줌 (터미널이 아니면 색 없이)
+    fn print_notices(&mut self) {
+        for notice in self.exec.take_notices() {
+            if io::stdout().is_terminal() {
+                println!("\x1B[33mNOTICE: {}\x1B[0m", notice);
+            } else {
+                println!("NOTICE: {}", notice);
+            }
+        }
+    }
+
+    // psql처럼 쿼리를 실행한 뒤 그동안 들어온 알림을 보여줌
+    fn print_notifications(&self) {
+        for n in self.exec.notifications() {
+            println!(
+                "Asynchronous notification \"{}\" with payload \"{}\" received.",
+                n.channel, n.payload
+            );
+        }
+    }
+
+    fn format(&self, result: QueryResult) -> String {
+        match (self.mode, result) {
+            (Mode::Markdown, QueryResult::Rows { columns, rows }) => {
+                format_markdown(&columns, &rows)
+            }
+            (_, result) => format_result(result),
+        }
+    }
+
+    /// 마크다운 표를 읽어 이미 있는 테이블에 추가하고, 추가한 행 수를 돌려줌
+    fn import_markdown(&mut self, file: &str, table: &str) -> Result<usize, String> {
+        let text = std::fs::read_to_string(file).map_err(|e| e.to_string())?;
+        let (columns, rows) = parse_markdown(&text)?;
+        let info = self
+            .exec
+            .table(table)
+            .ok_or_else(|| format!("Table not found: '{}'", table))?;
+        let types = columns
+            .iter()
+            .map(|col| {
+                info.columns
+                    .iter()
+                    .find(|c| &c.name == col)
+                    .map(|c| c.data_type)
+                    .ok_or_else(|| format!("Column not found: '{}'", col))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        let params = rows
+            .iter()
+            .flat_map(|row| row.iter().zip(&types))
+            .map(|(cell, ty)| cell_value(cell, *ty))
+            .collect::<Result<Vec<_>, _>>()?;
+        // 모든 행을 INSERT 하나로 넣어서, 중간에 실패하면 아무 행도 들어가지 않음
+        let row = format!("({})", vec!["?"; columns.len()].join(", "));
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES {};",
+            table,
+            columns.join(", "),
+            vec![row; rows.len()].join(", ")
+        );
+        let insert = Prepared::new(&sql).map_err(|e| e.to_string())?;
+        match self.exec.run_prepared(&insert, &params) {
+            QueryResult::Error(msg) => Err(msg),
+            _ => Ok(rows.len()),
+        }
+    }
+
+    fn output(&self, text: &str) {
+        if self.pager && text.lines().count() >= terminal_height() && page(text).is_ok() {
+            return;
+        }
+        print!("{}", text);
+    }
+}
+
+pub fn format_result(result: QueryResult) -> String {
+    match result {
+        QueryResult::Rows { columns, rows } => {
+            let mut out = String::from(">>> Rows:\n");
+            out.push_str(&format!("    | {} |\n", columns.join(" | ")));
+            for row in rows {
+                out.push_str(&format!("    | {} |\n", row.join(" | ")));
+            }
+            out
+        }
+        QueryResult::Success => ">>> SUCCESS\n".into(),
+        // 파싱 오류는 여러 개일 수 있음 (한 줄에 하나씩)
+        QueryResult::Error(msg) => msg
+            .lines()
+            .map(|line| format!(">>> Error: {}\n", line))
+            .collect(),
+    }
+}
+
+pub fn format_markdown(columns: &[String], rows: &[Vec<String>]) -> String {
+    let line = |cells: &[String]| {
+        let cells: Vec<_> = cells.iter().map(|c| c.replace('|', "\\|")).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut out = line(columns);
+    out.push_str(&line(&vec!["---".to_string(); columns.len()]));
+    for row in rows {
+        out.push_str(&line(row));
+    }
+    out
+}
+
+/// 텍스트에서 처음 나오는 마크다운 표를 (헤더, 행들)로 읽음
+fn parse_markdown(text: &str) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with('|'));
+    let header = lines.next().ok_or("no markdown table found")?;
+    let columns = split_cells(header);
+    let separator = lines.next().map(split_cells).unwrap_or_default();
+    let is_separator = |cell: &String| {
+        let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+        !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+    };
+    if separator.len() != columns.len() || !separator.iter().all(is_separator) {
+        return Err("expected a '| --- |' line under the header".into());
+    }
+    let mut rows = Vec::new();
+    for line in lines.take_while(|line| line.starts_with('|')) {
+        let row = split_cells(line);
+        if row.len() != columns.len() {
+            return Err(format!(
+                "expected {} cells, but found {} in '{}'",
+                columns.len(),
+                row.len(),
+                line
+            ));
+        }
+        rows.push(row);
+    }
+    Ok((columns, rows))
+}
+
+// `| a | b \| c |` → ["a", "b | c"]
+fn split_cells(line: &str) -> Vec<String> {
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line
+        .strip_suffix('|')
+        .filter(|l| !l.ends_with('\\'))
+        .unwrap_or(line);
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut().unwrap().push(chars.next().unwrap())
+            }
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.into_iter().map(|c| c.trim().to_string()).collect()
+}
+
+/// 내보낸 결과를 다시 읽을 수 있도록 셀을 값으로 바꿈 (null은 NULL, yes/no는 BOOL)
+pub fn cell_value(cell: &str, ty: DataType) -> Result<DataValue, String> {
+    if cell == "null" {
+        return Ok(DataValue::Null);
+    }
+    let invalid = || format!("'{}' is not a valid {}", cell, ty.as_str());
+    match ty {
+        DataType::Int => cell.parse().map(DataValue::Int).map_err(|_| invalid()),
+        DataType::Float => cell.parse().map(DataValue::Float).map_err(|_| invalid()),
+        DataType::Bool => match cell.to_lowercase().as_str() {
+            "yes" | "true" => Ok(DataValue::Bool(true)),
+            "no" | "false" => Ok(DataValue::Bool(false)),
+            _ => Err(invalid()),
+        },
+        DataType::String => Ok(DataValue::String(cell.to_string())),
+    }
+}
+
+/// `cell_value`와 같지만 SQL 리터럴 문자열로 돌려줌
+pub fn sql_literal(cell: &str, ty: DataType) -> Result<String, String> {
+    Ok(match cell_value(cell, ty)? {
+        DataValue::Null => "NULL".into(),
+        DataValue::Bool(b) => b.to_string().to_uppercase(),
+        DataValue::Int(n) => n.to_string(),
+        DataValue::Float(f) => format!("{:?}", f),
+        DataValue::String(s) => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+    })
+}
+
+/// `.watch <초> <쿼리>`의 인자를 해석
+fn parse_watch(arg: &str) -> Option<(Duration, &str)> {
+    let (secs, query) = arg.split_once(char::is_whitespace)?;
+    let secs: f64 = secs.parse().ok()?;
+    let query = query.trim();
+    (secs.is_finite() && secs > 0.0 && !query.is_empty())
+        .then(|| (Duration::from_secs_f64(secs), query))
+}
+
+// 셸이 LINES를 내보내지 않으면 일반적인 터미널 높이로 가정
+fn terminal_height() -> usize {
+    std::env::var("LINES")
+        .ok()
+        .and_then(|lines| lines.parse().ok())
+        .unwrap_or(24)
+}
+
+/// $PAGER (없으면 less)에 결과를 넘겨 보여줌
+fn page(text: &str) -> io::Result<()> {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".into());
+    run_with_input(shell(&pager), text)
+}
+
+/// 줄 끝의 `\g [| <명령어>]`를 찾아 (SQL, 명령어)로 나눔
+///
+/// `\g`는 렉서로 찾으므로 문자열 리터럴이나 주석 안의 `\g`는 무시함 (`buffer`는 앞 줄까지 쌓인 SQL)
+fn split_pipe<'a>(buffer: &str, line: &'a str) -> Option<(&'a str, Option<&'a str>)> {
+    let src = format!("{}{}", buffer, line);
+    let total = src.chars().count();
+    let mut lexer = Lexer::new(&src);
+    let at = loop {
+        match lexer.next() {
+            Ok(Token::Eof) => return None,
+            Ok(_) => {}
+            Err(QueryErr::At { err, .. }) if *err == QueryErr::InvalidToken('\\') => {
+                let at = total - lexer.remaining() - 1;
+                if src[src.char_indices().nth(at)?.0..].starts_with("\\g") {
+                    break at;
+                }
+            }
+            Err(QueryErr::At { err, .. }) if matches!(*err, QueryErr::InvalidToken(_)) => {}
+            // 리터럴이 어디서 끝나는지 알 수 없으므로 `\g`로 보지 않음
+            Err(_) => return None,
+        }
+    };
+    let at = at.checked_sub(buffer.chars().count())?;
+    let (start, _) = line.char_indices().nth(at)?;
+    let sql = &line[..start];
+    let rest = line[start + 2..].trim();
+    if rest.is_empty() {
+        Some((sql, None))
+    } else {
+        let cmd = rest.strip_prefix('|')?.trim();
+        (!cmd.is_empty()).then_some((sql, Some(cmd)))
+    }
+}
+
+/// CREATE TRIGGER의 BEGIN ~ END 사이에서 끝나는지 확인 (트리거 안의 `;`에서 실행하지 않도록)
+fn inside_trigger(src: &str) -> bool {
+    let mut lexer = Lexer::new(src);
+    let mut open = false;
+    loop {
+        match lexer.next() {
+            Ok(Token::Trigger) => open = true,
+            Ok(Token::End) => open = false,
+            Ok(Token::Eof) | Err(_) => return open,
+            Ok(_) => {}
+        }
+    }
+}
+
+/// Ctrl-C 핸들러를 설치함
+///
+/// 핸들러는 프로세스가 끝날 때까지 남으므로, `.watch` 중이 아니면 기본 동작처럼 종료함
+fn install_ctrl_c() -> io::Result<CtrlC> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let watching = Arc::new(AtomicBool::new(false));
+    let (tx, pressed) = mpsc::channel();
+    let flag = watching.clone();
+    std::thread::spawn(move || {
+        runtime.block_on(async {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if !flag.load(Ordering::SeqCst) {
+                    std::process::exit(130);
+                }
+                let _ = tx.send(());
+            }
+        })
+    });
+    Ok(CtrlC { watching, pressed })
+}
+
+fn shell(cmd: &str) -> Command {
+    let mut command;
+    if cfg!(windows) {
+        command = Command::new("cmd");
+        command.arg("/C");
+    } else {
+        command = Command::new("sh");
+        command.arg("-c");
+    }
+    command.arg(cmd);
+    command
+}
+
+/// 명령어를 실행하고 `text`를 표준 입력으로 넘긴 뒤 끝날 때까지 기다림
+fn run_with_input(mut command: Command, text: &str) -> io::Result<()> {
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // 명령어가 입력을 끝까지 읽지 않고 끝나면 (pager를 닫는 등) BrokenPipe가 나는데, 오류가 아님
+        match stdin.write_all(text.as_bytes()) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_result() {
+        let result = QueryResult::Rows {
+            columns: vec!["a".into(), "b".into()],
+            rows: vec![vec!["1".into(), "x".into()]],
+        };
+        assert_eq!(
+            format_result(result),
+            ">>> Rows:\n    | a | b |\n    | 1 | x |\n"
+        );
+        assert_eq!(format_result(QueryResult::Success), ">>> SUCCESS\n");
+    }
+
+    #[test]
+    fn test_meta_commands() {
+        let mut repl = Repl::new(Executor::new(), "sql> ".into(), Mode::Table);
+        assert!(repl.meta(".pager on"));
+        assert!(repl.pager);
+        assert!(repl.meta(".pager off"));
+        assert!(!repl.pager);
+        assert!(repl.meta(".nope"));
+        assert!(repl.meta(".watch"));
+        assert!(repl.meta(".shell"));
+        assert!(repl.meta(".size"));
+        assert!(repl.meta(".mode markdown"));
+        assert_eq!(repl.mode, Mode::Markdown);
+        assert!(repl.meta(".mode table"));
+        assert_eq!(repl.mode, Mode::Table);
+        assert!(!repl.meta(".exit"));
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        assert_eq!(
+            parse_watch("2 SELECT * FROM t;"),
+            Some((Duration::from_secs(2), "SELECT * FROM t;"))
+        );
+        assert_eq!(
+            parse_watch("0.5   SELECT 1;"),
+            Some((Duration::from_millis(500), "SELECT 1;"))
+        );
+        assert_eq!(parse_watch("SELECT 1;"), None);
+        assert_eq!(parse_watch("0 SELECT 1;"), None);
+        assert_eq!(parse_watch("2"), None);
+    }
+
+    #[test]
+    fn test_prompt_tx() {
+        let mut repl = Repl::new(Executor::new(), "shop[{tx}]> ".into(), Mode::Table);
+        assert_eq!(repl.prompt(), "shop[]> ");
+        repl.exec.run("BEGIN;".into());
+        assert_eq!(repl.prompt(), "shop[*]> ");
+        repl.exec.run("ROLLBACK;".into());
+        assert_eq!(repl.prompt(), "shop[]> ");
+    }
+
+    #[test]
+    fn test_split_pipe() {
+        assert_eq!(
+            split_pipe("", "SELECT * FROM t \\g | sort -r"),
+            Some(("SELECT * FROM t ", Some("sort -r")))
+        );
+        assert_eq!(split_pipe("", "SELECT 1 \\g"), Some(("SELECT 1 ", None)));
+        assert_eq!(split_pipe("", "SELECT 1;"), None);
+        assert_eq!(split_pipe("", "SELECT 1 \\g |"), None);
+        // 리터럴이나 주석 안의 \\g는 파이프가 아님
+        assert_eq!(split_pipe("", "SELECT 'x\\g | echo PWNED' FROM a;"), None);
+        assert_eq!(split_pipe("", "SELECT a FROM t -- \\g | echo PWNED"), None);
+        assert_eq!(
+            split_pipe("SELECT 'a\n", "b \\g | echo PWNED' FROM t;"),
+            None
+        );
+        assert_eq!(
+            split_pipe("SELECT a\n", "FROM t WHERE b = 'x\\g|y' \\g | wc -l"),
+            Some(("FROM t WHERE b = 'x\\g|y' ", Some("wc -l")))
+        );
+    }
+
+    #[test]
+    fn test_inside_trigger() {
+        let src = "CREATE TRIGGER t AFTER INSERT ON a BEGIN\n  INSERT INTO b VALUES (NEW.x);";
+        assert!(inside_trigger(src));
+        assert!(!inside_trigger(&format!("{}\nEND;", src)));
+        assert!(!inside_trigger("INSERT INTO b VALUES (1);"));
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let mut repl = Repl::new(Executor::new(), "sql> ".into(), Mode::Table);
+        repl.exec.run(
+            "CREATE TABLE t(name TEXT, ok BOOL, n INT, x FLOAT);
+             INSERT INTO t VALUES ('a|b', TRUE, 1, 0.5), ('it\\'s', NULL, NULL, 2.0);"
+                .into(),
+        );
+        repl.mode = Mode::Markdown;
+        let result = repl.exec.run("SELECT * FROM t;".into());
+        let text = repl.format(result);
+        assert_eq!(
+            text,
+            "| name | ok | n | x |\n| --- | --- | --- | --- |\n| a\\|b | yes | 1 | 0.5 |\n| it's | null | null | 2 |\n"
+        );
+
+        let path = std::env::temp_dir().join(format!("squirrel-md-{}.md", std::process::id()));
+        std::fs::write(&path, format!("Some notes\n\n{}\nmore text\n", text)).unwrap();
+        assert_eq!(repl.import_markdown(path.to_str().unwrap(), "t"), Ok(2));
+        std::fs::remove_file(&path).unwrap();
+        let result = repl
+            .exec
+            .run("SELECT COUNT(*) FROM t WHERE name = 'a|b';".into());
+        let text = repl.format(result);
+        assert!(text.ends_with("| 2 |\n"));
+    }
+
+    #[test]
+    fn test_import_markdown_session_tables() {
+        let mut repl = Repl::new(Executor::new(), "sql> ".into(), Mode::Table);
+        let path = std::env::temp_dir().join(format!("squirrel-md-tmp-{}.md", std::process::id()));
+        std::fs::write(&path, "| n |\n| --- |\n| 1 |\n| 2 |\n").unwrap();
+        let file = path.to_str().unwrap();
+        // 공유 테이블이 아닌 임시 테이블과 트랜잭션 안에서 만든 테이블에도 넣을 수 있음
+        repl.exec.run("CREATE TEMP TABLE scratch (n INT);".into());
+        assert_eq!(repl.import_markdown(file, "scratch"), Ok(2));
+        repl.exec.run("BEGIN; CREATE TABLE pending (n INT);".into());
+        assert_eq!(repl.import_markdown(file, "pending"), Ok(2));
+        std::fs::remove_file(&path).unwrap();
+        for table in ["scratch", "pending"] {
+            let result = repl.exec.run(format!("SELECT SUM(n) FROM {};", table));
+            assert!(repl.format(result).ends_with("| 3 |\n"), "{}", table);
+        }
+    }
+
+    #[test]
+    fn test_parse_markdown_errors() {
+        assert!(parse_markdown("no table here").is_err());
+        assert!(parse_markdown("| a | b |\n| 1 | 2 |").is_err());
+        assert!(parse_markdown("| a | b |\n|:--|--:|\n| 1 |").is_err());
+        assert_eq!(
+            parse_markdown("| a |\n|:-:|\n| 1 |"),
+            Ok((vec!["a".to_string()], vec![vec!["1".to_string()]]))
+        );
+    }
+}
@@ -1,10 +1,16 @@
 use crate::executor::{ColumnId, RowId, TableId};
-use std::cmp::PartialEq;
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::cmp::{Ordering, PartialEq};
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::io::SeekFrom;
-use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use tokio::{fs, io};
 
+mod row;
+
+pub use row::{ROW_FORMAT_VERSION, decode_row, encode_row};
+
 #[repr(u8)]
 #[derive(PartialEq, Eq, Hash, Clone, Debug, Copy)]
 pub enum DataType {
@@ -14,14 +20,68 @@ pub enum DataType {
     String = 14,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub enum DataValue {
+    Null,
     Int(i64),
     Float(f64),
     Bool(bool),
     String(String),
 }
 
+/// 실수의 전순서: NaN은 다른 모든 값보다 크고 NaN끼리는 같으며, -0과 0은 같음
+///
+/// f64의 `partial_cmp`는 NaN이 끼면 순서가 없어서 정렬 결과가 입력 순서에 따라 달라지므로,
+/// 정렬, GROUP BY, DISTINCT는 모두 이 순서를 씁니다.
+pub fn compare_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    }
+}
+
+// 실수는 `compare_floats`로 비교 (NaN == NaN, -0 == 0)
+impl PartialEq for DataValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (DataValue::Null, DataValue::Null) => true,
+            (DataValue::Int(a), DataValue::Int(b)) => a == b,
+            (DataValue::Float(a), DataValue::Float(b)) => compare_floats(*a, *b).is_eq(),
+            (DataValue::Bool(a), DataValue::Bool(b)) => a == b,
+            (DataValue::String(a), DataValue::String(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for DataValue {}
+
+// `PartialEq`와 맞도록 실수는 모든 NaN을 하나의 값으로, -0을 0으로 바꿔서 해시
+// (GROUP BY, DISTINCT, 집합 연산의 해시 테이블이 같은 값을 한 곳에 모으도록)
+impl Hash for DataValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            DataValue::Null => 0u8.hash(state),
+            DataValue::Int(n) => (1u8, n).hash(state),
+            DataValue::Float(f) => (2u8, canonical_bits(*f)).hash(state),
+            DataValue::Bool(b) => (3u8, b).hash(state),
+            DataValue::String(s) => (4u8, s).hash(state),
+        }
+    }
+}
+
+fn canonical_bits(f: f64) -> u64 {
+    if f.is_nan() {
+        f64::NAN.to_bits()
+    } else if f == 0.0 {
+        0
+    } else {
+        f.to_bits()
+    }
+}
+
 impl DataType {
     pub fn as_str(self) -> &'static str {
         match self {
@@ -31,44 +91,289 @@ impl DataType {
             DataType::String => "String",
         }
     }
+
+    /// `as_str`로 쓴 이름을 다시 변환 (schema 파일을 읽을 때)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Int" => Some(DataType::Int),
+            "Float" => Some(DataType::Float),
+            "Bool" => Some(DataType::Bool),
+            "String" => Some(DataType::String),
+            _ => None,
+        }
+    }
+
+    /// 사용자가 SQL에 쓰는 타입 이름 (`from_sql`의 반대)
+    pub fn sql_name(self) -> &'static str {
+        match self {
+            DataType::Int => "INTEGER",
+            DataType::Float => "FLOAT",
+            DataType::Bool => "BOOLEAN",
+            DataType::String => "TEXT",
+        }
+    }
+
+    /// 파서가 돌려주는 SQL 타입 이름을 변환
+    pub fn from_sql(name: &str) -> Option<Self> {
+        match name {
+            "INTEGER" => Some(DataType::Int),
+            "FLOAT" => Some(DataType::Float),
+            "BOOLEAN" => Some(DataType::Bool),
+            "TEXT" => Some(DataType::String),
+            _ => None,
+        }
+    }
 }
 
 impl DataValue {
-    pub fn verify(self, data_type: DataType) -> bool {
+    pub fn verify(&self, data_type: DataType) -> bool {
         match self {
+            DataValue::Null => true,
             DataValue::Int(_) => DataType::Int == data_type,
             DataValue::Float(_) => DataType::Float == data_type,
             DataValue::Bool(_) => DataType::Bool == data_type,
             DataValue::String(_) => DataType::String == data_type,
         }
     }
+
+    /// 실행할 때마다, 플랫폼이 바뀌어도 같은 해시 (타입 태그와 리틀 엔디언 값에 `name_hash`와 같은 FNV-1a)
+    pub fn stable_hash(&self) -> u64 {
+        let tagged = |tag: u8, bytes: &[u8]| fnv1a(fnv1a(FNV_OFFSET, &[tag]), bytes);
+        match self {
+            DataValue::Null => tagged(0, &[]),
+            DataValue::Int(n) => tagged(1, &n.to_le_bytes()),
+            DataValue::Float(f) => tagged(2, &canonical_bits(*f).to_le_bytes()),
+            DataValue::Bool(b) => tagged(3, &[*b as u8]),
+            DataValue::String(s) => tagged(4, s.as_bytes()),
+        }
+    }
+
+    /// 값의 타입 (NULL은 타입이 없음)
+    pub fn data_type(&self) -> Option<DataType> {
+        match self {
+            DataValue::Null => None,
+            DataValue::Int(_) => Some(DataType::Int),
+            DataValue::Float(_) => Some(DataType::Float),
+            DataValue::Bool(_) => Some(DataType::Bool),
+            DataValue::String(_) => Some(DataType::String),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            DataValue::Null => "Null",
+            DataValue::Int(_) => DataType::Int.as_str(),
+            DataValue::Float(_) => DataType::Float.as_str(),
+            DataValue::Bool(_) => DataType::Bool.as_str(),
+            DataValue::String(_) => DataType::String.as_str(),
+        }
+    }
+}
+
+impl Display for DataValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataValue::Null => write!(f, "null"),
+            DataValue::Int(n) => write!(f, "{}", n),
+            DataValue::Float(n) => write!(f, "{}", n),
+            DataValue::Bool(b) => write!(f, "{}", if *b { "yes" } else { "no" }),
+            DataValue::String(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+// 데이터베이스 디렉터리 안의 header 파일에 기록하는 저장 형식 버전
+// (1: 테이블과 컬럼 ID가 `DefaultHasher`의 해시, 2: `name_hash`)
+pub const FORMAT_VERSION: u32 = 2;
+
+// header 파일의 내용 (저장 형식과 행 형식의 버전)
+fn header() -> String {
+    format!("FORMAT {}\nROW {}\n", FORMAT_VERSION, ROW_FORMAT_VERSION)
+}
+
+/// 새 데이터베이스 디렉터리를 만들고 header 파일을 기록
+pub async fn create_database(path: &Path) -> io::Result<()> {
+    // 다른 OS로 옮겨도 열 수 있도록 Windows에서 못 쓰는 이름은 어디서든 막음
+    if let Some(name) = path.file_name().and_then(|name| name.to_str())
+        && is_reserved_name(name)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("'{}' is a reserved file name on Windows", name),
+        ));
+    }
+    if fs::try_exists(path).await? {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("'{}' already exists", path.display()),
+        ));
+    }
+    fs::create_dir_all(path).await?;
+    let mut file = fs::File::create(path.join("header")).await?;
+    file.write_all(header().as_bytes()).await?;
+    file.flush().await?;
+    Ok(())
+}
+
+/// header 파일에 기록된 버전
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DatabaseFormat {
+    pub format: u32,
+    pub row: u32,
+}
+
+// FORMAT <버전>, [ROW <버전>] (ROW 줄이 없던 때는 행을 저장하지 않았으므로 행 형식 1로 봄)
+fn parse_header(text: &str) -> Option<DatabaseFormat> {
+    let mut lines = text.lines();
+    let format = lines.next()?.strip_prefix("FORMAT ")?.parse().ok()?;
+    let row = match lines.next() {
+        Some(line) => line.strip_prefix("ROW ")?.parse().ok()?,
+        None => 1,
+    };
+    let valid = format > 0 && row > 0 && lines.next().is_none();
+    valid.then_some(DatabaseFormat { format, row })
+}
+
+/// header 파일을 읽어 이 빌드가 읽을 수 있는 데이터베이스인지 확인
+///
+/// 더 새 버전이 만든 데이터베이스는 잘못 읽거나 망가뜨리지 않도록 열지 않습니다.
+/// 예전 형식은 읽을 수 있지만, 그 위에 쓰기 전에 `upgrade_database`로 올려야 합니다.
+/// 열 수 있으면 테이블마다 `recover_rows`로 쓰다가 멈춘 행을 정리합니다.
+pub async fn open_database(path: &Path) -> io::Result<DatabaseFormat> {
+    let text = match fs::read_to_string(path.join("header")).await {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "not a database (no header file)",
+            ));
+        }
+        Err(e) => return Err(e),
+    };
+    let found = parse_header(&text)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Header file is corrupted"))?;
+    for (kind, version, supported) in [
+        ("storage", found.format, FORMAT_VERSION),
+        ("row", found.row, ROW_FORMAT_VERSION),
+    ] {
+        if version > supported {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "database uses {} format {}, but this version only reads up to {}; \
+                     open it with a newer SQuirreL",
+                    kind, version, supported
+                ),
+            ));
+        }
+    }
+    for (table_id, _) in list_tables(path).await? {
+        recover_rows(path, &table_id).await?;
+    }
+    Ok(found)
+}
+
+/// 예전 형식의 데이터베이스를 현재 형식으로 올리고, 올리기 전 형식을 돌려줌 (이미 현재 형식이면 None)
+///
+/// header 파일을 마지막에 바꾸므로, 도중에 멈추면 다시 실행해서 마저 올릴 수 있습니다.
+pub async fn upgrade_database(path: &Path) -> io::Result<Option<u32>> {
+    let found = open_database(path).await?;
+    if found.format == FORMAT_VERSION {
+        return Ok(None);
+    }
+    // 1 -> 2: 테이블과 컬럼 ID를 이름의 `name_hash`로 다시 계산
+    if found.format < 2 {
+        for (table_id, mut schema) in list_tables(path).await? {
+            for (id, _, name) in &mut schema.columns {
+                *id = ColumnId(name_hash(name));
+            }
+            let text = schema_text(&schema);
+            replace_file(&schema_path(path, &table_id), text.as_bytes()).await?;
+            let new_id = TableId(name_hash(&schema.name));
+            if new_id != table_id {
+                let target = table_dir(path, &new_id);
+                if fs::try_exists(&target).await? {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!("'{}' already exists", target.display()),
+                    ));
+                }
+                fs::rename(table_dir(path, &table_id), target).await?;
+            }
+        }
+    }
+    replace_file(&path.join("header"), header().as_bytes()).await?;
+    Ok(Some(found.format))
+}
+
+// 임시 파일에 다 쓴 다음 이름을 바꿔서, 도중에 멈춰도 예전 내용과 새 내용 중 하나만 남게 함
+async fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp).await?;
+    file.write_all(contents).await?;
+    file.sync_all().await?;
+    fs::rename(&tmp, path).await
+}
+
+/// CON, NUL, COM1 같은 Windows 장치 이름 (확장자가 붙어도 예약됨)이거나 점, 공백으로 끝나는 이름
+fn is_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name).to_ascii_uppercase();
+    let device = matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ((stem.starts_with("COM") || stem.starts_with("LPT"))
+            && stem.len() == 4
+            && matches!(stem.as_bytes()[3], b'1'..=b'9'));
+    device || name.ends_with(['.', ' '])
+}
+
+// 테이블 디렉터리 이름은 테이블 이름의 해시이므로, 테이블 이름에 구분자나 예약어가 있어도 안전함
+fn table_dir(root: &Path, table_id: &TableId) -> PathBuf {
+    root.join(table_id.0.to_string())
+}
+
+fn schema_path(root: &Path, table_id: &TableId) -> PathBuf {
+    table_dir(root, table_id).join("schema")
+}
+
+/// 테이블과 컬럼 ID로 쓰는 이름의 해시 (FNV-1a 64비트)
+///
+/// 파일에 남는 값이므로, Rust 버전이나 플랫폼마다 달라질 수 있는 `DefaultHasher` 대신
+/// UTF-8 바이트만으로 정해지는 해시를 씁니다.
+pub fn name_hash(name: &str) -> u64 {
+    fnv1a(FNV_OFFSET, name.as_bytes())
 }
 
-pub async fn create_table(name: String) -> io::Result<TableId> {
-    let mut hasher = DefaultHasher::new();
-    name.hash(&mut hasher);
-    let val = hasher.finish();
-    fs::create_dir(val.to_string()).await?;
-    let mut file = fs::File::create(format!("{}/schema", val)).await?;
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+/// `hash`에서 이어서 `bytes`를 FNV-1a로 섞음
+pub fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// `root` 데이터베이스 안에 테이블 디렉터리와 schema 파일을 만듦
+pub async fn create_table(root: &Path, name: String) -> io::Result<TableId> {
+    let table_id = TableId(name_hash(&name));
+    fs::create_dir(table_dir(root, &table_id)).await?;
+    let mut file = fs::File::create(schema_path(root, &table_id)).await?;
     file.write_all(format!("NAME {}\n", name).as_bytes())
         .await?;
     file.write_all("LAST_ID 0000000000000000\n".as_bytes())
         .await?;
     file.flush().await?;
-    Ok(TableId(val))
+    Ok(table_id)
 }
 
 pub async fn create_column(
+    root: &Path,
     table_id: TableId,
     col_name: String,
     col_type: DataType,
 ) -> tokio::io::Result<ColumnId> {
-    let mut hasher = DefaultHasher::new();
-    col_name.hash(&mut hasher);
-    let val = hasher.finish();
+    let val = name_hash(&col_name);
     let mut file = fs::File::options()
         .append(true)
-        .open(format!("{}/schema", table_id.0))
+        .open(schema_path(root, &table_id))
         .await?;
     file.write_all(format!("COLUMN {} {} {col_name}\n", val, col_type.as_str()).as_bytes())
         .await?;
@@ -76,42 +381,792 @@ pub async fn create_column(
     Ok(ColumnId(val))
 }
 
-pub async fn create_row(table_id: TableId, values: Vec<DataValue>) -> io::Result<RowId> {
+/// schema 파일의 내용
+#[derive(Debug, PartialEq)]
+pub struct TableSchema {
+    pub name: String,
+    // 마지막으로 만든 행의 ID
+    pub last_id: u64,
+    pub columns: Vec<(ColumnId, DataType, String)>,
+}
+
+const LAST_ID: &str = "\nLAST_ID ";
+
+fn corrupted_schema() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "Schema file is corrupted")
+}
+
+// NAME <name>, LAST_ID <16자리 16진수>, COLUMN <id> <type> <name>...
+fn parse_schema(text: &str) -> Option<TableSchema> {
+    let mut lines = text.lines();
+    let name = lines.next()?.strip_prefix("NAME ")?.to_string();
+    let last_id = lines.next()?.strip_prefix("LAST_ID ")?;
+    if last_id.len() != 16 || !last_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let last_id = u64::from_str_radix(last_id, 16).ok()?;
+    let columns = lines
+        .map(|line| {
+            let mut parts = line.strip_prefix("COLUMN ")?.splitn(3, ' ');
+            let id = parts.next()?.parse().ok()?;
+            let ty = DataType::from_name(parts.next()?)?;
+            Some((ColumnId(id), ty, parts.next()?.to_string()))
+        })
+        .collect::<Option<_>>()?;
+    Some(TableSchema {
+        name,
+        last_id,
+        columns,
+    })
+}
+
+fn schema_text(schema: &TableSchema) -> String {
+    let mut text = format!("NAME {}\nLAST_ID {:016X}\n", schema.name, schema.last_id);
+    for (id, ty, name) in &schema.columns {
+        text.push_str(&format!("COLUMN {} {} {}\n", id.0, ty.as_str(), name));
+    }
+    text
+}
+
+pub async fn read_schema(root: &Path, table_id: &TableId) -> io::Result<TableSchema> {
+    let text = fs::read_to_string(schema_path(root, table_id)).await?;
+    parse_schema(&text).ok_or_else(corrupted_schema)
+}
+
+/// 데이터베이스의 모든 테이블을 이름 순서로 (ID는 디렉터리 이름이므로 예전 형식의 ID도 그대로 읽음)
+pub async fn list_tables(root: &Path) -> io::Result<Vec<(TableId, TableSchema)>> {
+    let mut tables = Vec::new();
+    let mut entries = fs::read_dir(root).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let id = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok());
+        let Some(id) = id else {
+            continue;
+        };
+        if entry.file_type().await?.is_dir() {
+            let table_id = TableId(id);
+            let schema = read_schema(root, &table_id).await?;
+            tables.push((table_id, schema));
+        }
+    }
+    tables.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+    Ok(tables)
+}
+
+fn rows_path(root: &Path, table_id: &TableId) -> PathBuf {
+    table_dir(root, table_id).join("rows")
+}
+
+/// 행을 rows 파일 끝에 붙이고 새 행 ID를 돌려줌
+///
+/// rows 파일의 레코드는 행 ID(u64), 길이(u32), `encode_row`로 만든 바이트 순서이고
+/// 정수는 리틀 엔디언입니다. schema 파일의 LAST_ID는 자릿수가 고정되어 있어 그 자리만 덮어씁니다.
+pub async fn create_row(
+    root: &Path,
+    table_id: TableId,
+    values: Vec<DataValue>,
+) -> io::Result<RowId> {
+    let path = schema_path(root, &table_id);
+    let text = fs::read_to_string(&path).await?;
+    let schema = parse_schema(&text).ok_or_else(corrupted_schema)?;
+    let types: Vec<DataType> = schema.columns.iter().map(|(_, ty, _)| *ty).collect();
+    let row = encode_row(&types, &values)?;
+    let len = u32::try_from(row.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "row is too large"))?;
+    let row_id = schema
+        .last_id
+        .checked_add(1)
+        .ok_or_else(|| io::Error::other("no row IDs left"))?;
+
+    // 행을 쓰다가 멈춰도 같은 ID를 다시 쓰지 않도록 LAST_ID를 먼저 올림
+    let position = text.find(LAST_ID).ok_or_else(corrupted_schema)? + LAST_ID.len();
+    let mut file = fs::File::options().write(true).open(&path).await?;
+    file.seek(SeekFrom::Start(position as u64)).await?;
+    file.write_all(format!("{:016X}", row_id).as_bytes())
+        .await?;
+    file.flush().await?;
+
+    let mut record = Vec::with_capacity(12 + row.len());
+    record.extend(row_id.to_le_bytes());
+    record.extend(len.to_le_bytes());
+    record.extend(row);
     let mut file = fs::File::options()
-        .write(true)
-        .open(format!("{}/schema", table_id.0))
+        .create(true)
+        .append(true)
+        .open(rows_path(root, &table_id))
         .await?;
-    let mut matching_count = 0;
-    let pattern = "\nLAST_ID ";
-    let mut position = 0;
-    let mut buffered = BufReader::new(file);
-    loop {
-        let value = buffered.read_u8().await?;
-        position += 1;
-        if pattern.as_bytes()[matching_count] != value {
-            matching_count = 0;
-            continue;
+    file.write_all(&record).await?;
+    file.flush().await?;
+    Ok(RowId(row_id))
+}
+
+// rows 파일 맨 앞의 레코드를 (행 ID, 행 바이트, 나머지)로 나눔 (레코드가 끝까지 없으면 None)
+fn split_record(bytes: &[u8]) -> Option<(u64, &[u8], &[u8])> {
+    let (head, body) = bytes.split_at_checked(12)?;
+    let row_id = u64::from_le_bytes(head[..8].try_into().unwrap());
+    let len = u32::from_le_bytes(head[8..].try_into().unwrap()) as usize;
+    let (row, rest) = body.split_at_checked(len)?;
+    Some((row_id, row, rest))
+}
+
+/// 쓰다가 멈춘 마지막 레코드를 rows 파일에서 잘라 내고, 잘라 낸 바이트 수를 돌려줌
+///
+/// 레코드는 파일 끝에 덧붙이기만 하므로 `create_row` 도중 프로세스가 죽으면 마지막 레코드만
+/// 덜 쓰일 수 있습니다. 그 행은 아직 ID를 돌려주기 전이므로 버려도 됩니다.
+pub async fn recover_rows(root: &Path, table_id: &TableId) -> io::Result<u64> {
+    let path = rows_path(root, table_id);
+    let bytes = match fs::read(&path).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut rest = &bytes[..];
+    while let Some((_, _, tail)) = split_record(rest) {
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        let file = fs::File::options().write(true).open(&path).await?;
+        file.set_len((bytes.len() - rest.len()) as u64).await?;
+        file.sync_all().await?;
+    }
+    Ok(rest.len() as u64)
+}
+
+/// rows 파일의 행을 쓴 순서대로 모두 읽음 (행을 만든 적이 없으면 빈 목록)
+pub async fn read_rows(
+    root: &Path,
+    table_id: &TableId,
+) -> io::Result<Vec<(RowId, Vec<DataValue>)>> {
+    let schema = read_schema(root, table_id).await?;
+    let types: Vec<DataType> = schema.columns.iter().map(|(_, ty, _)| *ty).collect();
+    let bytes = match fs::read(rows_path(root, table_id)).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut rows = Vec::new();
+    let mut rest = &bytes[..];
+    while !rest.is_empty() {
+        let (row_id, row, tail) = split_record(rest)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Rows file is truncated"))?;
+        rows.push((RowId(row_id), decode_row(&types, row)?));
+        rest = tail;
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 테스트마다 겹치지 않는 빈 임시 디렉터리 (테스트가 실패해도 지워짐)
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("squirrel-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            Self(path)
         }
-        matching_count += 1;
-        if matching_count == pattern.len() {
-            break;
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
         }
     }
-    if matching_count != pattern.len() {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "Schema file is corrupted",
-        ));
+
+    // 컬럼이 (name TEXT, age INTEGER, score FLOAT, male BOOLEAN)인 users 테이블이 있는 데이터베이스
+    async fn users_db(name: &str) -> (TempDir, TableId) {
+        let dir = TempDir::new(name);
+        create_database(&dir.0).await.unwrap();
+        let table_id = create_table(&dir.0, "users".into()).await.unwrap();
+        for (col, ty) in [
+            ("name", DataType::String),
+            ("age", DataType::Int),
+            ("score", DataType::Float),
+            ("male", DataType::Bool),
+        ] {
+            create_column(&dir.0, table_id, col.into(), ty)
+                .await
+                .unwrap();
+        }
+        (dir, table_id)
     }
 
-    let mut hexadecimal = [0u8; 8];
-    buffered.read_exact(&mut hexadecimal).await?;
-    let hexadecimal = String::from_utf8_lossy(&hexadecimal);
-    let parsed = u32::from_str_radix(&hexadecimal, 16).unwrap();
-    buffered.seek(SeekFrom::Start(position)).await?;
-    buffered
-        .write(format!("{:016X}", parsed + 1).as_bytes())
-        .await?;
+    // tests/fixtures 아래 데이터베이스의 사본 (원본은 바꾸지 않음)
+    fn copy_fixture(name: &str) -> TempDir {
+        fn copy_dir(from: &Path, to: &Path) {
+            std::fs::create_dir_all(to).unwrap();
+            for entry in std::fs::read_dir(from).unwrap() {
+                let entry = entry.unwrap();
+                let target = to.join(entry.file_name());
+                if entry.file_type().unwrap().is_dir() {
+                    copy_dir(&entry.path(), &target);
+                } else {
+                    std::fs::copy(entry.path(), target).unwrap();
+                }
+            }
+        }
+        let dir = TempDir::new(&format!("fixture-{}", name));
+        copy_dir(&fixture_path(name), &dir.0);
+        dir
+    }
+
+    fn fixture_path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    // 디렉터리 안의 모든 파일의 (상대 경로, 내용)
+    fn dir_contents(root: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut files = Vec::new();
+        let mut dirs = vec![root.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else {
+                    let bytes = std::fs::read(&path).unwrap();
+                    files.push((path.strip_prefix(root).unwrap().to_path_buf(), bytes));
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    fn fixture_rows() -> [Vec<DataValue>; 2] {
+        [
+            vec![DataValue::String("Alpha".into()), DataValue::Int(18)],
+            vec![DataValue::String("Beta".into()), DataValue::Null],
+        ]
+    }
+
+    #[test]
+    fn test_compare_floats() {
+        let mut values = [
+            f64::NAN,
+            1.0,
+            f64::INFINITY,
+            -0.0,
+            f64::NEG_INFINITY,
+            0.0,
+            -f64::NAN,
+        ];
+        values.sort_by(|a, b| compare_floats(*a, *b));
+        assert_eq!(&values[..3], [f64::NEG_INFINITY, -0.0, 0.0]);
+        assert_eq!(&values[3..5], [1.0, f64::INFINITY]);
+        assert!(values[5].is_nan() && values[6].is_nan());
+
+        assert_eq!(DataValue::Float(-0.0), DataValue::Float(0.0));
+        assert_eq!(DataValue::Float(f64::NAN), DataValue::Float(-f64::NAN));
+        assert_ne!(DataValue::Float(1.0), DataValue::Int(1));
+    }
+
+    #[test]
+    fn test_hash_data_value() {
+        let same = [
+            (DataValue::Float(0.0), DataValue::Float(-0.0)),
+            (DataValue::Float(f64::NAN), DataValue::Float(-f64::NAN)),
+            (
+                DataValue::Float(f64::NAN),
+                DataValue::Float(f64::from_bits(f64::NAN.to_bits() | 1)),
+            ),
+            (DataValue::Null, DataValue::Null),
+            (DataValue::String("a".into()), DataValue::String("a".into())),
+        ];
+        for (a, b) in same {
+            assert_eq!(a, b);
+            assert_eq!(a.stable_hash(), b.stable_hash(), "{:?} {:?}", a, b);
+        }
+        // 값이 같아 보여도 타입이 다르면 다른 값
+        let distinct = [
+            DataValue::Null,
+            DataValue::Int(0),
+            DataValue::Int(1),
+            DataValue::Int(-1),
+            DataValue::Int(i64::MIN),
+            DataValue::Float(0.0),
+            DataValue::Float(1.0),
+            DataValue::Float(f64::INFINITY),
+            DataValue::Float(f64::NEG_INFINITY),
+            DataValue::Float(f64::NAN),
+            DataValue::Bool(false),
+            DataValue::Bool(true),
+            DataValue::String("".into()),
+            DataValue::String("1".into()),
+        ];
+        let hashes: std::collections::HashSet<u64> =
+            distinct.iter().map(DataValue::stable_hash).collect();
+        assert_eq!(hashes.len(), distinct.len());
+        // 행 단위로 해시해도 경계가 섞이지 않음
+        let row = |values: &[&str]| -> Vec<DataValue> {
+            values
+                .iter()
+                .map(|s| DataValue::String(s.to_string()))
+                .collect()
+        };
+        let hash_row = |row: &Vec<DataValue>| {
+            let mut hasher = std::hash::DefaultHasher::new();
+            row.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_ne!(hash_row(&row(&["ab", "c"])), hash_row(&row(&["a", "bc"])));
+    }
+
+    #[test]
+    fn test_name_hash() {
+        // 파일에 남는 ID이므로 플랫폼이나 Rust 버전이 바뀌어도 값이 같아야 함
+        assert_eq!(name_hash(""), 0xcbf29ce484222325);
+        assert_eq!(name_hash("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(name_hash("foobar"), 0x85944171f73967e8);
+        assert_eq!(
+            DataValue::String("foobar".into()).stable_hash(),
+            fnv1a(fnv1a(FNV_OFFSET, &[4]), b"foobar")
+        );
+        assert_eq!(DataValue::Int(1).stable_hash(), 0x7194f3e59ae47dcd);
+    }
 
-    todo!()
+    #[tokio::test]
+    async fn test_create_database() {
+        let dir = TempDir::new("test");
+        let path = &dir.0;
+        create_database(path).await.unwrap();
+        let header = fs::read_to_string(path.join("header")).await.unwrap();
+        assert_eq!(
+            header,
+            format!("FORMAT {}\nROW {}\n", FORMAT_VERSION, ROW_FORMAT_VERSION)
+        );
+        // 이미 있는 경로는 덮어쓰지 않음
+        let err = create_database(path).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn test_create_table_under_root() {
+        let dir = TempDir::new("tables");
+        let path = &dir.0;
+        create_database(path).await.unwrap();
+        let table_id = create_table(path, "users".into()).await.unwrap();
+        assert_eq!(table_id.0, name_hash("users"));
+        let table_dir = path.join(table_id.0.to_string());
+        create_column(path, table_id, "name".into(), DataType::String)
+            .await
+            .unwrap();
+
+        let schema = fs::read_to_string(table_dir.join("schema")).await.unwrap();
+        assert!(schema.starts_with("NAME users\nLAST_ID "));
+        assert!(schema.trim_end().ends_with(" String name"), "{}", schema);
+        // 같은 이름의 테이블은 다시 만들 수 없음
+        let err = create_table(path, "users".into()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        // 데이터베이스가 없으면 테이블도 만들 수 없음
+        let err = create_table(&path.join("missing"), "users".into())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_create_row_and_reopen() {
+        let (dir, table_id) = users_db("rows").await;
+        assert!(read_rows(&dir.0, &table_id).await.unwrap().is_empty());
+        let rows = [
+            vec![
+                DataValue::String("Alpha".into()),
+                DataValue::Int(18),
+                DataValue::Float(4.5),
+                DataValue::Bool(true),
+            ],
+            vec![
+                DataValue::String("".into()),
+                DataValue::Null,
+                DataValue::Float(f64::NAN),
+                DataValue::Null,
+            ],
+            vec![DataValue::Null; 4],
+        ];
+        for (i, row) in rows.iter().enumerate() {
+            let row_id = create_row(&dir.0, table_id, row.clone()).await.unwrap();
+            assert_eq!(row_id, RowId(i as u64 + 1));
+        }
+
+        // 파일에서 다시 읽어도 같은 스키마와 행
+        let schema = read_schema(&dir.0, &table_id).await.unwrap();
+        assert_eq!(schema.name, "users");
+        assert_eq!(schema.last_id, 3);
+        let columns: Vec<(&str, DataType)> = schema
+            .columns
+            .iter()
+            .map(|(id, ty, name)| {
+                assert_eq!(id.0, name_hash(name));
+                (name.as_str(), *ty)
+            })
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                ("name", DataType::String),
+                ("age", DataType::Int),
+                ("score", DataType::Float),
+                ("male", DataType::Bool),
+            ]
+        );
+        let read: Vec<(u64, Vec<DataValue>)> = read_rows(&dir.0, &table_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, row)| (id.0, row))
+            .collect();
+        assert_eq!(
+            read,
+            [
+                (1, rows[0].clone()),
+                (2, rows[1].clone()),
+                (3, rows[2].clone())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_row_errors() {
+        let (dir, table_id) = users_db("row-errors").await;
+        // 값의 수나 타입이 스키마와 다르면 아무것도 쓰지 않음
+        for row in [
+            vec![DataValue::Null],
+            vec![
+                DataValue::Int(1),
+                DataValue::Int(2),
+                DataValue::Float(3.0),
+                DataValue::Bool(true),
+            ],
+        ] {
+            let err = create_row(&dir.0, table_id, row).await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert_eq!(read_schema(&dir.0, &table_id).await.unwrap().last_id, 0);
+        assert!(read_rows(&dir.0, &table_id).await.unwrap().is_empty());
+
+        let err = create_row(&dir.0, TableId(name_hash("nope")), vec![])
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_files() {
+        let (dir, table_id) = users_db("corrupted").await;
+        create_row(&dir.0, table_id, vec![DataValue::Null; 4])
+            .await
+            .unwrap();
+        let schema_file = schema_path(&dir.0, &table_id);
+        let good = fs::read_to_string(&schema_file).await.unwrap();
+        for bad in [
+            String::new(),
+            good.replace("NAME ", "NAM "),
+            good.replace("LAST_ID 0000000000000001", "LAST_ID 000000000000001G"),
+            good.replace("LAST_ID 0000000000000001", "LAST_ID 1"),
+            good.replace(" Int age", " Integer age"),
+            good.replace("COLUMN ", "COLUMN x"),
+            format!("{}garbage\n", good),
+        ] {
+            fs::write(&schema_file, &bad).await.unwrap();
+            let err = read_schema(&dir.0, &table_id).await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", bad);
+            let err = create_row(&dir.0, table_id, vec![DataValue::Null; 4])
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", bad);
+        }
+        fs::write(&schema_file, &good).await.unwrap();
+
+        // 레코드 중간에서 끝난 rows 파일
+        let rows_file = rows_path(&dir.0, &table_id);
+        let mut bytes = fs::read(&rows_file).await.unwrap();
+        for len in [bytes.len() - 1, 5] {
+            bytes.truncate(len);
+            fs::write(&rows_file, &bytes).await.unwrap();
+            let err = read_rows(&dir.0, &table_id).await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recover_after_crash() {
+        let (dir, table_id) = users_db("crash").await;
+        let schema_file = schema_path(&dir.0, &table_id);
+        let rows_file = rows_path(&dir.0, &table_id);
+        let rows: Vec<Vec<DataValue>> = (0..4)
+            .map(|i| {
+                vec![
+                    DataValue::String("x".repeat(i)),
+                    DataValue::Int(i as i64),
+                    DataValue::Null,
+                    DataValue::Bool(i % 2 == 0),
+                ]
+            })
+            .collect();
+        // 행을 하나씩 만들 때마다 두 파일의 내용
+        let mut states = vec![(fs::read(&schema_file).await.unwrap(), Vec::new())];
+        for row in &rows {
+            create_row(&dir.0, table_id, row.clone()).await.unwrap();
+            let rows = fs::read(&rows_file).await.unwrap();
+            states.push((fs::read(&schema_file).await.unwrap(), rows));
+        }
+
+        // create_row는 LAST_ID를 덮어쓴 다음 레코드를 덧붙이므로, i번째 행을 쓰다가 죽으면
+        // 앞에서부터 일부 바이트만 쓰인 상태가 남음 (모든 바이트 위치에서 죽여 봄)
+        let position = std::str::from_utf8(&states[0].0)
+            .unwrap()
+            .find(LAST_ID)
+            .unwrap()
+            + LAST_ID.len();
+        for i in 0..rows.len() {
+            let (old_schema, old_rows) = &states[i];
+            let (new_schema, new_rows) = &states[i + 1];
+            let mut crashes = Vec::new();
+            for k in 0..=16 {
+                let mut schema = old_schema.clone();
+                schema[position..position + k].copy_from_slice(&new_schema[position..position + k]);
+                crashes.push((schema, old_rows.clone()));
+            }
+            for k in old_rows.len()..=new_rows.len() {
+                crashes.push((new_schema.clone(), new_rows[..k].to_vec()));
+            }
+            for (schema, rows_bytes) in crashes {
+                fs::write(&schema_file, &schema).await.unwrap();
+                fs::write(&rows_file, &rows_bytes).await.unwrap();
+                let dropped = recover_rows(&dir.0, &table_id).await.unwrap();
+                let read = read_rows(&dir.0, &table_id).await.unwrap();
+                // 행은 앞의 i개이거나, 레코드를 끝까지 썼으면 i + 1개
+                let n = read.len();
+                assert!(n == i || n == i + 1, "row {}: {} rows", i, n);
+                assert_eq!(dropped as usize, rows_bytes.len() - states[n].1.len());
+                for (j, (row_id, row)) in read.iter().enumerate() {
+                    assert_eq!((row_id.0, row), (j as u64 + 1, &rows[j]));
+                }
+                // 덜 쓴 LAST_ID도 이미 쓴 행의 ID보다 작지 않으므로 ID를 다시 쓰지 않음
+                let last_id = read_schema(&dir.0, &table_id).await.unwrap().last_id;
+                assert!(last_id >= n as u64, "row {}: LAST_ID {}", i, last_id);
+                let row_id = create_row(&dir.0, table_id, rows[0].clone()).await.unwrap();
+                assert_eq!(row_id.0, last_id + 1);
+                assert_eq!(read_rows(&dir.0, &table_id).await.unwrap().len(), n + 1);
+            }
+        }
+    }
+
+    // 자식 프로세스에서 k번째로 만드는 행
+    fn crash_row(k: usize) -> Vec<DataValue> {
+        vec![
+            DataValue::String("x".repeat(k % 7)),
+            DataValue::Int(k as i64),
+            DataValue::Float(k as f64 / 2.0),
+            DataValue::Bool(k.is_multiple_of(2)),
+        ]
+    }
+
+    // test_kill_during_writes가 자식 프로세스로 실행 (SQUIRREL_CRASH_DIR의 users 테이블에
+    // 죽을 때까지 행을 만들고, create_row가 끝난 행마다 번호를 출력)
+    #[tokio::test]
+    #[ignore]
+    async fn crash_child() {
+        let Some(dir) = std::env::var_os("SQUIRREL_CRASH_DIR") else {
+            return;
+        };
+        let dir = PathBuf::from(dir);
+        let (table_id, _) = list_tables(&dir).await.unwrap().remove(0);
+        for k in read_rows(&dir, &table_id).await.unwrap().len().. {
+            create_row(&dir, table_id, crash_row(k)).await.unwrap();
+            println!("created {}", k);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kill_during_writes() {
+        use std::io::BufRead;
+        use std::process::{Command, Stdio};
+
+        let (dir, table_id) = users_db("kill").await;
+        // 자식이 끝났다고 알린 행 번호 (--test-threads=1이면 첫 줄 앞에 libtest의
+        // `test ... ` 출력이 붙으므로 줄 중간에서 찾음)
+        let acked_row =
+            |line: &str| -> Option<usize> { line.split("created ").nth(1)?.trim().parse().ok() };
+        for round in 0..5 {
+            let mut child = Command::new(std::env::current_exe().unwrap())
+                .args(["--exact", "storage::tests::crash_child", "--ignored"])
+                .args(["--nocapture", "--test-threads=1"])
+                .env("SQUIRREL_CRASH_DIR", &dir.0)
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            // 행을 어느 정도 만든 뒤 쓰는 도중에 SIGKILL
+            let mut lines = std::io::BufReader::new(child.stdout.take().unwrap()).lines();
+            let target = 20 + round * 15;
+            let (mut acked, mut last) = (0, None);
+            for line in lines.by_ref() {
+                if let Some(k) = acked_row(&line.unwrap()) {
+                    (acked, last) = (acked + 1, Some(k));
+                    if acked == target {
+                        break;
+                    }
+                }
+            }
+            assert_eq!(acked, target, "the child process stopped early");
+            child.kill().unwrap();
+            child.wait().unwrap();
+            for line in lines.map_while(|line| line.ok()) {
+                last = acked_row(&line).or(last);
+            }
+            let created = last.unwrap() + 1;
+
+            // 다시 열면 끝까지 쓴 행만 남아야 함: 끝났다고 알린 행은 모두 있고,
+            // 쓰던 행은 있거나 없음
+            open_database(&dir.0).await.unwrap();
+            let rows = read_rows(&dir.0, &table_id).await.unwrap();
+            assert!(
+                rows.len() == created || rows.len() == created + 1,
+                "round {}: {} rows, {} created",
+                round,
+                rows.len(),
+                created
+            );
+            for (k, (_, row)) in rows.iter().enumerate() {
+                assert_eq!(row, &crash_row(k), "round {}: row {}", round, k);
+            }
+            // 행 ID는 늘어나기만 하고, 다음 행은 이미 쓴 ID를 다시 쓰지 않음
+            assert!(rows.windows(2).all(|w| w[0].0.0 < w[1].0.0));
+            let last_id = read_schema(&dir.0, &table_id).await.unwrap().last_id;
+            assert!(rows.last().is_none_or(|(id, _)| id.0 <= last_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_current_format_fixture() {
+        // 지금 코드로 만든 데이터베이스가 현재 형식의 fixture와 같아야 함
+        // (다르면 FORMAT_VERSION을 올리고 새 fixture와 업그레이드를 추가해야 함)
+        let dir = TempDir::new("current-format");
+        create_database(&dir.0).await.unwrap();
+        let table_id = create_table(&dir.0, "users".into()).await.unwrap();
+        create_column(&dir.0, table_id, "name".into(), DataType::String)
+            .await
+            .unwrap();
+        create_column(&dir.0, table_id, "age".into(), DataType::Int)
+            .await
+            .unwrap();
+        for row in fixture_rows() {
+            create_row(&dir.0, table_id, row).await.unwrap();
+        }
+        let fixture = fixture_path(&format!("format-{}", FORMAT_VERSION));
+        assert_eq!(dir_contents(&dir.0), dir_contents(&fixture));
+    }
+
+    #[tokio::test]
+    async fn test_open_old_formats() {
+        for (name, format, rows) in [
+            ("format-1", 1, 0),
+            ("format-1-rows", 1, 2),
+            ("format-2", 2, 2),
+        ] {
+            let dir = copy_fixture(name);
+            let found = open_database(&dir.0).await.unwrap();
+            assert_eq!(found, DatabaseFormat { format, row: 1 }, "{}", name);
+            let tables = list_tables(&dir.0).await.unwrap();
+            assert_eq!(tables.len(), 1);
+            let (table_id, schema) = &tables[0];
+            let columns: Vec<(&str, DataType)> = schema
+                .columns
+                .iter()
+                .map(|(_, ty, name)| (name.as_str(), *ty))
+                .collect();
+            assert_eq!(schema.name, "users");
+            assert_eq!(
+                columns,
+                [("name", DataType::String), ("age", DataType::Int)]
+            );
+            let read: Vec<Vec<DataValue>> = read_rows(&dir.0, table_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|(_, row)| row)
+                .collect();
+            assert_eq!(read, fixture_rows()[..rows], "{}", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upgrade() {
+        let dir = copy_fixture("format-1");
+        assert_eq!(upgrade_database(&dir.0).await.unwrap(), Some(1));
+        let table_dir = dir.0.join(name_hash("users").to_string());
+        let schema = fs::read_to_string(table_dir.join("schema")).await.unwrap();
+        assert_eq!(
+            schema,
+            format!(
+                "NAME users\nLAST_ID 0000000000000000\nCOLUMN {} String name\nCOLUMN {} Int age\n",
+                name_hash("name"),
+                name_hash("age")
+            )
+        );
+        assert_eq!(open_database(&dir.0).await.unwrap().format, FORMAT_VERSION);
+        // 다시 실행해도 바뀌는 것이 없음
+        let before = dir_contents(&dir.0);
+        assert_eq!(upgrade_database(&dir.0).await.unwrap(), None);
+        assert_eq!(dir_contents(&dir.0), before);
+
+        // 올린 데이터베이스는 지금 만든 데이터베이스와 바이트까지 같음
+        let dir = copy_fixture("format-1-rows");
+        assert_eq!(upgrade_database(&dir.0).await.unwrap(), Some(1));
+        assert_eq!(
+            dir_contents(&dir.0),
+            dir_contents(&fixture_path("format-2"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refuse_newer_format() {
+        let dir = copy_fixture("format-2");
+        let header_file = dir.0.join("header");
+        for (header, kind) in [
+            ("FORMAT 3\nROW 1\n", io::ErrorKind::Unsupported),
+            ("FORMAT 2\nROW 2\n", io::ErrorKind::Unsupported),
+            ("FORMAT 0\n", io::ErrorKind::InvalidData),
+            ("FORMAT two\n", io::ErrorKind::InvalidData),
+            ("", io::ErrorKind::InvalidData),
+        ] {
+            fs::write(&header_file, header).await.unwrap();
+            let err = open_database(&dir.0).await.unwrap_err();
+            assert_eq!(err.kind(), kind, "{:?}", header);
+            // 올리지도 않고 그대로 둠
+            let err = upgrade_database(&dir.0).await.unwrap_err();
+            assert_eq!(err.kind(), kind, "{:?}", header);
+            assert_eq!(fs::read_to_string(&header_file).await.unwrap(), header);
+        }
+        fs::write(&header_file, "FORMAT 3\nROW 1\n").await.unwrap();
+        let err = open_database(&dir.0).await.unwrap_err();
+        assert!(err.to_string().contains("storage format 3"), "{}", err);
+
+        fs::remove_file(&header_file).await.unwrap();
+        let err = open_database(&dir.0).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_reserved_names() {
+        for name in ["CON", "nul.db", "com1", "LPT9.sq", "data."] {
+            assert!(is_reserved_name(name), "{}", name);
+        }
+        for name in ["console", "com0", "shop.db", "nullable"] {
+            assert!(!is_reserved_name(name), "{}", name);
+        }
+        let err = create_database(&std::env::temp_dir().join("aux"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
 }
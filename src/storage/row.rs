@@ -0,0 +1,335 @@
+use super::{DataType, DataValue};
+use std::io;
+
+// 데이터베이스 header 파일에 `ROW <버전>`으로 기록 (형식이 바뀌면 올림)
+pub const ROW_FORMAT_VERSION: u32 = 1;
+
+fn fixed_width(data_type: DataType) -> usize {
+    match data_type {
+        DataType::Int | DataType::Float => 8,
+        DataType::Bool => 1,
+        DataType::String => 8,
+    }
+}
+
+fn corrupted(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// 스키마의 타입대로 행을 바이트로 바꿈 (값이 컬럼 타입과 맞지 않으면 오류)
+///
+/// 컬럼이 n개인 행은 세 부분을 차례로 이어 붙입니다. 정수와 실수는 모두 리틀 엔디언입니다.
+///
+/// 1. NULL 비트맵: `(n + 7) / 8` 바이트. i번째 컬럼이 NULL이면 `i / 8`번째 바이트의
+///    `i % 8`번째 비트(가장 낮은 비트부터)가 1
+/// 2. 고정 길이 부분: 컬럼 순서대로 INTEGER와 FLOAT는 8바이트, BOOLEAN은 1바이트,
+///    TEXT는 가변 길이 부분 안의 시작 위치와 길이를 u32로 하나씩.
+///    NULL인 컬럼도 0으로 채운 자리를 차지하므로 각 컬럼의 위치는 스키마만으로 정해짐
+/// 3. 가변 길이 부분: TEXT 값의 UTF-8 바이트를 컬럼 순서대로 이어 붙인 것
+pub fn encode_row(schema: &[DataType], values: &[DataValue]) -> io::Result<Vec<u8>> {
+    if schema.len() != values.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "expected {} values, but found {}",
+                schema.len(),
+                values.len()
+            ),
+        ));
+    }
+    let mut bitmap = vec![0u8; schema.len().div_ceil(8)];
+    let mut fixed = Vec::with_capacity(schema.iter().map(|ty| fixed_width(*ty)).sum());
+    let mut var: Vec<u8> = Vec::new();
+    for (i, (ty, value)) in schema.iter().zip(values).enumerate() {
+        match (ty, value) {
+            (_, DataValue::Null) => {
+                bitmap[i / 8] |= 1 << (i % 8);
+                fixed.resize(fixed.len() + fixed_width(*ty), 0);
+            }
+            (DataType::Int, DataValue::Int(n)) => fixed.extend(n.to_le_bytes()),
+            (DataType::Float, DataValue::Float(f)) => fixed.extend(f.to_bits().to_le_bytes()),
+            (DataType::Bool, DataValue::Bool(b)) => fixed.push(*b as u8),
+            (DataType::String, DataValue::String(s)) => {
+                let offset = u32::try_from(var.len());
+                let len = u32::try_from(s.len());
+                let (Ok(offset), Ok(len)) = (offset, len) else {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "row is too large",
+                    ));
+                };
+                fixed.extend(offset.to_le_bytes());
+                fixed.extend(len.to_le_bytes());
+                var.extend(s.as_bytes());
+            }
+            (ty, value) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "column {} is {}, but the value is {}",
+                        i,
+                        ty.as_str(),
+                        value.type_name()
+                    ),
+                ));
+            }
+        }
+    }
+    let mut out = bitmap;
+    out.extend(fixed);
+    out.extend(var);
+    Ok(out)
+}
+
+/// `encode_row`로 만든 바이트를 다시 값으로 바꿈
+pub fn decode_row(schema: &[DataType], bytes: &[u8]) -> io::Result<Vec<DataValue>> {
+    let bitmap_len = schema.len().div_ceil(8);
+    let fixed_len: usize = schema.iter().map(|ty| fixed_width(*ty)).sum();
+    if bytes.len() < bitmap_len + fixed_len {
+        return Err(corrupted(format!(
+            "row is {} bytes, but the schema needs at least {}",
+            bytes.len(),
+            bitmap_len + fixed_len
+        )));
+    }
+    let (bitmap, rest) = bytes.split_at(bitmap_len);
+    let (mut fixed, var) = rest.split_at(fixed_len);
+    // 비트맵에서 컬럼 수보다 뒤의 비트는 0이어야 함
+    let last_bits = schema.len() % 8;
+    if last_bits != 0 && bitmap[bitmap_len - 1] >> last_bits != 0 {
+        return Err(corrupted(
+            "null bitmap has bits past the last column".into(),
+        ));
+    }
+    let mut take = |n: usize| {
+        let (head, tail) = fixed.split_at(n);
+        fixed = tail;
+        head
+    };
+    let mut values = Vec::with_capacity(schema.len());
+    let mut var_end = 0;
+    for (i, ty) in schema.iter().enumerate() {
+        let slot = take(fixed_width(*ty));
+        if bitmap[i / 8] & (1 << (i % 8)) != 0 {
+            values.push(DataValue::Null);
+            continue;
+        }
+        let value = match ty {
+            DataType::Int => DataValue::Int(i64::from_le_bytes(slot.try_into().unwrap())),
+            DataType::Float => {
+                DataValue::Float(f64::from_bits(u64::from_le_bytes(slot.try_into().unwrap())))
+            }
+            DataType::Bool => match slot[0] {
+                0 => DataValue::Bool(false),
+                1 => DataValue::Bool(true),
+                b => {
+                    return Err(corrupted(format!(
+                        "column {}: invalid BOOLEAN byte {}",
+                        i, b
+                    )));
+                }
+            },
+            DataType::String => {
+                let offset = u32::from_le_bytes(slot[..4].try_into().unwrap()) as usize;
+                let len = u32::from_le_bytes(slot[4..].try_into().unwrap()) as usize;
+                let text = offset
+                    .checked_add(len)
+                    .and_then(|end| var.get(offset..end))
+                    .ok_or_else(|| corrupted(format!("column {}: TEXT is out of bounds", i)))?;
+                var_end = var_end.max(offset + len);
+                let text = String::from_utf8(text.to_vec())
+                    .map_err(|_| corrupted(format!("column {}: TEXT is not UTF-8", i)))?;
+                DataValue::String(text)
+            }
+        };
+        values.push(value);
+    }
+    if var_end != var.len() {
+        return Err(corrupted(format!(
+            "{} trailing bytes after the row",
+            var.len() - var_end
+        )));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TYPES: [DataType; 4] = [
+        DataType::Int,
+        DataType::Float,
+        DataType::Bool,
+        DataType::String,
+    ];
+
+    // 타입마다 경계값들 (NULL 포함)
+    fn samples(ty: DataType) -> Vec<DataValue> {
+        let mut values = match ty {
+            DataType::Int => [0, 1, -1, i64::MIN, i64::MAX].map(DataValue::Int).to_vec(),
+            DataType::Float => [
+                0.0,
+                -0.0,
+                1.5,
+                f64::MIN_POSITIVE,
+                f64::MAX,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NAN,
+            ]
+            .map(DataValue::Float)
+            .to_vec(),
+            DataType::Bool => vec![DataValue::Bool(false), DataValue::Bool(true)],
+            DataType::String => ["", "a", "다람쥐 🐿", "a\0b"]
+                .map(|s| DataValue::String(s.into()))
+                .to_vec(),
+        };
+        values.push(DataValue::Null);
+        values
+    }
+
+    fn round_trip(schema: &[DataType], values: &[DataValue]) {
+        let bytes = encode_row(schema, values).unwrap();
+        let decoded = decode_row(schema, &bytes).unwrap();
+        assert_eq!(decoded, values);
+        // -0과 NaN도 비트 그대로 돌아옴
+        for (a, b) in decoded.iter().zip(values) {
+            if let (DataValue::Float(a), DataValue::Float(b)) = (a, b) {
+                assert_eq!(a.to_bits(), b.to_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_every_pair() {
+        for a in TYPES {
+            for b in TYPES {
+                for x in samples(a) {
+                    for y in samples(b) {
+                        round_trip(&[a, b], &[x.clone(), y]);
+                    }
+                }
+            }
+        }
+        round_trip(&[], &[]);
+    }
+
+    #[test]
+    fn test_round_trip_wide_rows() {
+        // 비트맵이 여러 바이트에 걸치는 행 (8의 배수 전후)
+        for n in [7, 8, 9, 16, 17] {
+            let schema: Vec<DataType> = (0..n).map(|i| TYPES[i % TYPES.len()]).collect();
+            for null_every in [1, 2, 3, n + 1] {
+                let values: Vec<DataValue> = schema
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| {
+                        let samples = samples(*ty);
+                        if i % null_every == 0 {
+                            DataValue::Null
+                        } else {
+                            samples[i % (samples.len() - 1)].clone()
+                        }
+                    })
+                    .collect();
+                round_trip(&schema, &values);
+            }
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        let schema = [
+            DataType::String,
+            DataType::Int,
+            DataType::Bool,
+            DataType::String,
+        ];
+        let values = [
+            DataValue::String("ab".into()),
+            DataValue::Null,
+            DataValue::Bool(true),
+            DataValue::String("c".into()),
+        ];
+        let bytes = encode_row(&schema, &values).unwrap();
+        let mut expected = vec![0b0010];
+        expected.extend([0, 0, 0, 0, 2, 0, 0, 0]);
+        expected.extend([0; 8]);
+        expected.push(1);
+        expected.extend([2, 0, 0, 0, 1, 0, 0, 0]);
+        expected.extend(b"abc");
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_golden_bytes() {
+        // 어느 아키텍처에서 쓰든 이 바이트와 같아야 다른 아키텍처에서도 열 수 있음
+        let schema = [
+            DataType::Int,
+            DataType::Float,
+            DataType::Bool,
+            DataType::String,
+        ];
+        let values = [
+            DataValue::Int(-2),
+            DataValue::Float(1.5),
+            DataValue::Bool(false),
+            DataValue::String("é".into()),
+        ];
+        let bytes: &[u8] = &[
+            0x00, // bitmap
+            0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // -2
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf8, 0x3f, // 1.5
+            0x00, // FALSE
+            0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, // offset 0, length 2
+            0xc3, 0xa9, // é
+        ];
+        assert_eq!(encode_row(&schema, &values).unwrap(), bytes);
+        assert_eq!(decode_row(&schema, bytes).unwrap(), values);
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let schema = [DataType::Int];
+        assert!(encode_row(&schema, &[]).is_err());
+        assert!(encode_row(&schema, &[DataValue::String("1".into())]).is_err());
+        assert!(encode_row(&schema, &[DataValue::Float(1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_decode_corrupted() {
+        let schema = [DataType::Bool, DataType::String];
+        let good = encode_row(
+            &schema,
+            &[DataValue::Bool(true), DataValue::String("hi".into())],
+        )
+        .unwrap();
+        assert!(decode_row(&schema, &good).is_ok());
+
+        let mut truncated = good.clone();
+        truncated.pop();
+        let mut trailing = good.clone();
+        trailing.push(0);
+        let mut bad_bool = good.clone();
+        bad_bool[1] = 2;
+        let mut bad_bitmap = good.clone();
+        bad_bitmap[0] = 0b100;
+        let mut bad_offset = good.clone();
+        bad_offset[2] = 1;
+        let mut bad_utf8 = good.clone();
+        bad_utf8[10] = 0xff;
+        for bytes in [
+            &good[..3],
+            &truncated,
+            &trailing,
+            &bad_bool,
+            &bad_bitmap,
+            &bad_offset,
+            &bad_utf8,
+        ] {
+            let err = decode_row(&schema, bytes).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{:?}", bytes);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+-- EXPLAIN (PROFILE)로 연산자 트리를 확인하는 쿼리들 (결과는 explain.txt)
+SELECT * FROM users;
+SELECT name FROM users WHERE age > 30 ORDER BY name LIMIT 2;
+SELECT DISTINCT city FROM users;
+SELECT u.name, o.total FROM users u JOIN orders o ON u.id = o.user_id WHERE o.total > 10;
+SELECT u.name, i.name FROM users u LEFT JOIN orders o ON u.id = o.user_id JOIN items i ON o.item_id = i.id;
+SELECT u.name, i.name FROM users u CROSS JOIN items i;
+SELECT city, COUNT(*), SUM(age) FROM users GROUP BY city ORDER BY city;
+SELECT COUNT(*) FROM orders;
+SELECT name, RANK() OVER (PARTITION BY city ORDER BY age DESC) FROM users;
+SELECT name FROM users WHERE age = (SELECT MAX(age) FROM users);
+SELECT name FROM users u WHERE EXISTS (SELECT 1 FROM orders o WHERE o.user_id = u.id);
+SELECT s.name FROM (SELECT name, age FROM users WHERE age < 40) s ORDER BY s.age;
+WITH big AS (SELECT user_id, total FROM orders WHERE total > 10) SELECT u.name FROM users u JOIN big b ON u.id = b.user_id;
+SELECT name FROM users UNION SELECT name FROM items;
+SELECT id FROM users INTERSECT SELECT user_id FROM orders EXCEPT SELECT 3 FROM items;
+VALUES (1, 'a'), (2, 'b');
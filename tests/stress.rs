@@ -0,0 +1,217 @@
+// 여러 세션이 한 데이터베이스에 동시에 쿼리를 실행하는 스트레스 테스트
+//
+// 기본으로 1초 동안 실행하며, `SQUIRREL_STRESS_SECS`로 시간을, `SQUIRREL_STRESS_SEED`로
+// 세션들의 난수 시드를 정할 수 있습니다 (실패하면 메시지에 시드가 나옴).
+// 예: `SQUIRREL_STRESS_SECS=60 cargo test --test stress`
+// 인덱스는 아직 없으므로 테이블 내용과 제약 조건만 확인합니다.
+
+use squirrel::executor::random::Rng;
+use squirrel::executor::{Database, Executor, QueryResult};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SESSIONS: u64 = 8;
+const ACCOUNTS: u64 = 10;
+const INITIAL_BALANCE: i64 = 1000;
+const SCRATCH_TABLES: usize = 4;
+
+// 세션 하나가 성공시킨 일의 수 (끝난 뒤 테이블 내용과 맞춰 봄)
+#[derive(Default)]
+struct Counts {
+    ledger_commits: usize,
+    payments: usize,
+    scratch_rows: [usize; SCRATCH_TABLES],
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok()?.parse().ok()
+}
+
+fn run_ok(exe: &mut Executor, sql: &str) {
+    if let QueryResult::Error(e) = exe.run(sql.to_string()) {
+        panic!("{}: {}", sql, e);
+    }
+}
+
+fn single_row(exe: &mut Executor, sql: &str) -> Vec<String> {
+    match exe.run(sql.to_string()) {
+        QueryResult::Rows { mut rows, .. } if rows.len() == 1 => rows.remove(0),
+        _ => panic!("{}: expected one row", sql),
+    }
+}
+
+fn setup(exe: &mut Executor) {
+    run_ok(exe, "CREATE TABLE accounts (id INTEGER, balance INTEGER);");
+    for id in 0..ACCOUNTS {
+        run_ok(
+            exe,
+            &format!("INSERT INTO accounts VALUES ({}, {});", id, INITIAL_BALANCE),
+        );
+    }
+    run_ok(exe, "CREATE TABLE ledger (amount INTEGER);");
+    run_ok(
+        exe,
+        "CREATE TABLE payments (account INTEGER REFERENCES accounts (id), amount INTEGER);",
+    );
+}
+
+/// 멈추라고 할 때까지 섞인 DML, DDL을 실행하면서, 실행 중에도 지켜져야 하는 조건을 확인
+fn session(exe: &mut Executor, rng: &mut Rng, stop: &AtomicBool) -> Counts {
+    let mut counts = Counts::default();
+    while !stop.load(Ordering::Relaxed) {
+        match rng.next_below(6) {
+            // 계좌 이체: 두 문장 사이에는 합이 맞지 않지만 둘 다 끝나면 다시 맞음
+            0 => {
+                let from = rng.next_below(ACCOUNTS);
+                let to = (from + 1 + rng.next_below(ACCOUNTS - 1)) % ACCOUNTS;
+                let amount = 1 + rng.next_below(50);
+                run_ok(
+                    exe,
+                    &format!(
+                        "UPDATE accounts SET balance = balance - {} WHERE id = {};\
+                         UPDATE accounts SET balance = balance + {} WHERE id = {};",
+                        amount, from, amount, to
+                    ),
+                );
+            }
+            // 합이 0인 두 행을 한 트랜잭션으로 넣음
+            1 => {
+                let amount = 1 + rng.next_below(100);
+                run_ok(
+                    exe,
+                    &format!(
+                        "BEGIN; INSERT INTO ledger VALUES ({}); INSERT INTO ledger VALUES (-{}); COMMIT;",
+                        amount, amount
+                    ),
+                );
+                counts.ledger_commits += 1;
+            }
+            // 커밋된 트랜잭션은 한꺼번에 보여야 함
+            2 => {
+                let row = single_row(exe, "SELECT SUM(amount), COUNT(*) FROM ledger;");
+                assert!(row[0] == "0" || row[0] == "null", "ledger sum: {:?}", row);
+                let count: usize = row[1].parse().unwrap();
+                assert_eq!(count % 2, 0, "ledger count: {:?}", row);
+            }
+            // 없는 계좌를 가리키는 결제는 외래 키 때문에 실패해야 함
+            3 => {
+                let account = rng.next_below(ACCOUNTS + 2);
+                let sql = format!("INSERT INTO payments VALUES ({}, 1);", account);
+                let failed = matches!(exe.run(sql.clone()), QueryResult::Error(_));
+                assert_eq!(failed, account >= ACCOUNTS, "{}", sql);
+                counts.payments += !failed as usize;
+            }
+            // 여러 세션이 같은 테이블을 만들려고 다툼
+            4 => {
+                let k = rng.next_below(SCRATCH_TABLES as u64) as usize;
+                run_ok(
+                    exe,
+                    &format!(
+                        "CREATE TABLE IF NOT EXISTS scratch{} (v INTEGER); INSERT INTO scratch{} VALUES (ABS(RANDOM() % 1000));",
+                        k, k
+                    ),
+                );
+                counts.scratch_rows[k] += 1;
+            }
+            // 세션마다 따로인 임시 테이블
+            _ => {
+                run_ok(
+                    exe,
+                    "CREATE TEMP TABLE IF NOT EXISTS mine (v INTEGER); INSERT INTO mine VALUES (1);",
+                );
+                let row = single_row(exe, "SELECT COUNT(*) FROM accounts;");
+                assert_eq!(row[0], ACCOUNTS.to_string());
+            }
+        }
+    }
+    counts
+}
+
+#[test]
+fn test_concurrent_sessions() {
+    let secs = env_u64("SQUIRREL_STRESS_SECS").unwrap_or(1);
+    let seed = env_u64("SQUIRREL_STRESS_SEED").unwrap_or_else(|| Rng::from_entropy().seed());
+    let db = Database::new();
+    setup(&mut Executor::connect(db.clone()));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let handles: Vec<_> = (0..SESSIONS)
+        .map(|i| {
+            let (db, stop) = (db.clone(), stop.clone());
+            thread::spawn(move || {
+                let session_seed = seed.wrapping_add(i);
+                let mut exe = Executor::connect(db);
+                // 실행기의 해시 키와 session이 RANDOM()으로 넣는 값도 같은 시드를 따름
+                run_ok(
+                    &mut exe,
+                    &format!("PRAGMA seed = {};", session_seed as i64 & i64::MAX),
+                );
+                session(&mut exe, &mut Rng::new(session_seed), &stop)
+            })
+        })
+        .collect();
+    let deadline = Instant::now() + Duration::from_secs(secs);
+    while Instant::now() < deadline && !handles.iter().any(|h| h.is_finished()) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    stop.store(true, Ordering::Relaxed);
+    let counts: Vec<Counts> = handles
+        .into_iter()
+        .map(|h| {
+            h.join()
+                .unwrap_or_else(|_| panic!("a session failed (SQUIRREL_STRESS_SEED={})", seed))
+        })
+        .collect();
+
+    let mut exe = Executor::connect(db);
+    let check = |exe: &mut Executor, sql: &str, expected: String| {
+        assert_eq!(
+            single_row(exe, sql),
+            [expected],
+            "{} (SQUIRREL_STRESS_SEED={})",
+            sql,
+            seed
+        );
+    };
+    // 이체를 아무리 해도 잔액의 합은 그대로
+    check(
+        &mut exe,
+        "SELECT SUM(balance) FROM accounts;",
+        (ACCOUNTS as i64 * INITIAL_BALANCE).to_string(),
+    );
+    let commits: usize = counts.iter().map(|c| c.ledger_commits).sum();
+    check(
+        &mut exe,
+        "SELECT COUNT(*) FROM ledger;",
+        (commits * 2).to_string(),
+    );
+    // 모든 결제가 있는 계좌를 가리킴
+    let payments: usize = counts.iter().map(|c| c.payments).sum();
+    check(
+        &mut exe,
+        "SELECT COUNT(*) FROM payments;",
+        payments.to_string(),
+    );
+    check(
+        &mut exe,
+        "SELECT COUNT(*) FROM payments JOIN accounts ON payments.account = accounts.id;",
+        payments.to_string(),
+    );
+    for k in 0..SCRATCH_TABLES {
+        let rows: usize = counts.iter().map(|c| c.scratch_rows[k]).sum();
+        if rows > 0 {
+            check(
+                &mut exe,
+                &format!("SELECT COUNT(*) FROM scratch{};", k),
+                rows.to_string(),
+            );
+        }
+    }
+    // 다른 세션의 임시 테이블은 보이지 않음
+    assert!(matches!(
+        exe.run("SELECT * FROM mine;".into()),
+        QueryResult::Error(_)
+    ));
+}